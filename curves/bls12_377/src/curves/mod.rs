@@ -19,6 +19,10 @@ pub use self::{
 };
 
 /// Hooks for *BLS12-377* curve.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the BLS12-377 `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
 pub trait CurveHooks: 'static + Sized {
     /// Pairing multi Miller loop.
     fn bls12_377_multi_miller_loop(
@@ -56,6 +60,61 @@ pub trait CurveHooks: 'static + Sized {
     ) -> Result<g2::G2Projective<Self>, ()>;
 }
 
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
+
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn bls12_377_multi_miller_loop(
+        _g1: impl Iterator<Item = <Bls12_377<Self> as Pairing>::G1Prepared>,
+        _g2: impl Iterator<Item = <Bls12_377<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_377<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bls12_377_final_exponentiation(
+        _target: <Bls12_377<Self> as Pairing>::TargetField,
+    ) -> Result<<Bls12_377<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bls12_377_msm_g1(
+        _bases: &[g1::G1Affine<Self>],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g1::G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_377_msm_g2(
+        _bases: &[g2::G2Affine<Self>],
+        _scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g2::G2Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_377_mul_projective_g1(
+        _base: &g1::G1Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<g1::G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_377_mul_projective_g2(
+        _base: &g2::G2Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<g2::G2Projective<Self>, ()> {
+        Err(())
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
 
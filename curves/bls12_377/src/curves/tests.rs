@@ -65,3 +65,48 @@ test_group!(g1; G1Projective; sw);
 test_group!(g2; G2Projective; sw);
 test_group!(pairing_output; PairingOutput<Bls12_377>; msm);
 test_pairing!(pairing; crate::Bls12_377<super::TestHooks>);
+
+#[test]
+#[allow(deprecated)]
+fn phantom_hooks_fail_every_operation() {
+    use ark_models_ext::{short_weierstrass::SWCurveConfig, AffineRepr};
+    use ark_std::Zero;
+    type Hooks = crate::PhantomHooks;
+
+    let g1 = crate::G1Affine::<Hooks>::generator();
+    let g2 = crate::G2Affine::<Hooks>::generator();
+    let one = <crate::g1::Config<Hooks> as CurveConfig>::ScalarField::from(1);
+    assert_eq!(crate::g1::Config::<Hooks>::msm(&[g1], &[one]), Err(0));
+    assert!(crate::g1::Config::<Hooks>::mul_affine(&g1, &[2]).is_zero());
+    assert!(crate::g2::Config::<Hooks>::mul_affine(&g2, &[2]).is_zero());
+    let target = crate::Bls12_377::<Hooks>::multi_miller_loop([g1], [g2]);
+    assert!(target.0.is_zero());
+    assert!(crate::Bls12_377::<Hooks>::final_exponentiation(target).is_none());
+}
+
+#[test]
+fn scalar_limbs_match_mul_bigint() {
+    use ark_ff::PrimeField;
+    use ark_models_ext::{short_weierstrass::SWCurveConfig, PrimeGroup};
+    use ark_std::{test_rng, UniformRand};
+
+    let mut rng = test_rng();
+    let s = <G1Config as CurveConfig>::ScalarField::rand(&mut rng);
+    let limbs = test_utils::scalar_limbs::<G1Config>(&s);
+    let p = G1Projective::rand(&mut rng);
+    assert_eq!(
+        G1Config::mul_projective(&p, &limbs),
+        p.mul_bigint(s.into_bigint())
+    );
+    let q = G2Projective::rand(&mut rng);
+    assert_eq!(
+        G2Config::mul_projective(&q, &limbs),
+        q.mul_bigint(s.into_bigint())
+    );
+}
+
+#[test]
+fn serialization_invariants() {
+    test_utils::check_sw_serialization::<G1Config>(16);
+    test_utils::check_sw_serialization::<G2Config>(16);
+}
@@ -0,0 +1,29 @@
+//! Native MSM over a range of window sizes, around the default given by
+//! `msm_window_bits` for each input size.
+
+use ark_bls12_381::{Fr, G1Affine};
+use ark_std::{test_rng, UniformRand};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use test_utils::{msm_native_sw_with_window, msm_window_bits};
+
+fn native_msm_window(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let mut group = c.benchmark_group("native msm window");
+    group.sample_size(10);
+    for len in [64, 1024] {
+        let bases: Vec<G1Affine> = (0..len).map(|_| G1Affine::rand(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+        let default = msm_window_bits(len);
+        for bits in default.saturating_sub(2).max(1)..=default + 2 {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{len} pairs"), bits),
+                &bits,
+                |b, bits| b.iter(|| msm_native_sw_with_window(&bases, &scalars, *bits).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, native_msm_window);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Pairing with cached G2 preparations against the affine-passing pairing.
+
+use ark_bls12_381::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bls12_381 as ArkBls12_381,
+};
+use ark_bls12_381_ext::{pairing_with_prepared, CachedG2Prepared, CurveHooks};
+use ark_ec::{pairing::Pairing, CurveConfig};
+use ark_std::{test_rng, UniformRand};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+struct BenchHooks;
+
+type Bls12_381 = ark_bls12_381_ext::Bls12_381<BenchHooks>;
+type G1Affine = ark_bls12_381_ext::G1Affine<BenchHooks>;
+type G2Affine = ark_bls12_381_ext::G2Affine<BenchHooks>;
+type G1Projective = ark_bls12_381_ext::G1Projective<BenchHooks>;
+type G2Projective = ark_bls12_381_ext::G2Projective<BenchHooks>;
+type G1Config = ark_bls12_381_ext::g1::Config<BenchHooks>;
+type G2Config = ark_bls12_381_ext::g2::Config<BenchHooks>;
+
+impl CurveHooks for BenchHooks {
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_381 as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_381 as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bls12_381, ArkBls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <Bls12_381 as Pairing>::TargetField,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bls12_381, ArkBls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective, ()> {
+        test_utils::msm_sw_generic::<G1Config, ArkG1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[G2Affine],
+        scalars: &[<G2Config as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective, ()> {
+        test_utils::msm_sw_generic::<G2Config, ArkG2Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &G1Projective,
+        scalar: &[u64],
+    ) -> Result<G1Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &G2Projective,
+        scalar: &[u64],
+    ) -> Result<G2Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G2Config, ArkG2Config>(base, scalar)
+    }
+}
+
+fn pairing(c: &mut Criterion) {
+    let mut rng = test_rng();
+    let g1 = G1Affine::rand(&mut rng);
+    let g2 = G2Affine::rand(&mut rng);
+    let prepared = [CachedG2Prepared::new(&g2)];
+
+    c.bench_function("pairing affine", |b| b.iter(|| Bls12_381::pairing(g1, g2)));
+    c.bench_function("pairing with prepared", |b| {
+        b.iter(|| pairing_with_prepared(&[g1], &prepared))
+    });
+}
+
+criterion_group!(benches, pairing);
+criterion_main!(benches);
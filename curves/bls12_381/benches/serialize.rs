@@ -0,0 +1,139 @@
+//! Encoding of the MSM inputs for the host, reusing a scratch buffer or not, and
+//! decoding under every codec policy.
+
+use ark_bls12_381::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bls12_381 as ArkBls12_381,
+};
+use ark_bls12_381_ext::{
+    codec::{self, SerializeScratch, Usage},
+    CurveHooks, Fr,
+};
+use ark_ec::{pairing::Pairing, CurveConfig};
+use ark_serialize::{Compress, Validate};
+use ark_std::{test_rng, UniformRand};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+struct BenchHooks;
+
+type Bls12_381 = ark_bls12_381_ext::Bls12_381<BenchHooks>;
+type G1Affine = ark_bls12_381_ext::G1Affine<BenchHooks>;
+type G2Affine = ark_bls12_381_ext::G2Affine<BenchHooks>;
+type G1Projective = ark_bls12_381_ext::G1Projective<BenchHooks>;
+type G2Projective = ark_bls12_381_ext::G2Projective<BenchHooks>;
+type G1Config = ark_bls12_381_ext::g1::Config<BenchHooks>;
+type G2Config = ark_bls12_381_ext::g2::Config<BenchHooks>;
+
+impl CurveHooks for BenchHooks {
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_381 as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_381 as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bls12_381, ArkBls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <Bls12_381 as Pairing>::TargetField,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bls12_381, ArkBls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective, ()> {
+        test_utils::msm_sw_generic::<G1Config, ArkG1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[G2Affine],
+        scalars: &[<G2Config as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective, ()> {
+        test_utils::msm_sw_generic::<G2Config, ArkG2Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &G1Projective,
+        scalar: &[u64],
+    ) -> Result<G1Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &G2Projective,
+        scalar: &[u64],
+    ) -> Result<G2Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G2Config, ArkG2Config>(base, scalar)
+    }
+}
+
+const MSM_LEN: usize = 64;
+
+fn msm_inputs() -> (Vec<G1Affine>, Vec<Fr>) {
+    let mut rng = test_rng();
+    let bases = (0..MSM_LEN).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars = (0..MSM_LEN).map(|_| Fr::rand(&mut rng)).collect();
+    (bases, scalars)
+}
+
+fn encode_msm_inputs(c: &mut Criterion) {
+    let (bases, scalars) = msm_inputs();
+    let mut group = c.benchmark_group("encode msm inputs");
+    // Validation only matters when decoding.
+    for (id, usage) in [
+        ("uncompressed", Usage::new(Compress::No, Validate::No)),
+        ("compressed", Usage::new(Compress::Yes, Validate::No)),
+    ] {
+        group.bench_with_input(BenchmarkId::new("alloc", id), &usage, |b, usage| {
+            b.iter(|| {
+                let bases = codec::encode(&bases, *usage);
+                let scalars = codec::encode(&scalars, *usage);
+                bases.len() + scalars.len()
+            })
+        });
+        let mut scratch = SerializeScratch::new();
+        group.bench_with_input(BenchmarkId::new("scratch", id), &usage, |b, usage| {
+            b.iter(|| {
+                let (bases, scalars) = scratch.encode_pair(&bases[..], &scalars[..], *usage);
+                bases.len() + scalars.len()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn decode_msm_inputs(c: &mut Criterion) {
+    let (bases, scalars) = msm_inputs();
+    let mut group = c.benchmark_group("decode msm inputs");
+    for (id, usage) in [
+        (
+            "uncompressed/unchecked",
+            Usage::new(Compress::No, Validate::No),
+        ),
+        (
+            "uncompressed/validated",
+            Usage::new(Compress::No, Validate::Yes),
+        ),
+        (
+            "compressed/unchecked",
+            Usage::new(Compress::Yes, Validate::No),
+        ),
+        (
+            "compressed/validated",
+            Usage::new(Compress::Yes, Validate::Yes),
+        ),
+    ] {
+        let encoded = (codec::encode(&bases, usage), codec::encode(&scalars, usage));
+        group.bench_with_input(BenchmarkId::from_parameter(id), &usage, |b, usage| {
+            b.iter(|| {
+                (
+                    codec::decode::<Vec<G1Affine>>(&encoded.0, *usage).unwrap(),
+                    codec::decode::<Vec<Fr>>(&encoded.1, *usage).unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode_msm_inputs, decode_msm_inputs);
+criterion_main!(benches);
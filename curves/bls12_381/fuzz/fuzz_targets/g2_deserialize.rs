@@ -0,0 +1,6 @@
+#![no_main]
+
+use ark_bls12_381_ext_fuzz::{check_deserialize, G2Affine};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| check_deserialize::<G2Affine>(data));
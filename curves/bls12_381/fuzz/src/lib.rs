@@ -0,0 +1,97 @@
+//! Support for the *BLS12-381* point deserialization fuzz targets.
+
+use ark_bls12_381::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bls12_381 as ArkBls12_381,
+};
+use ark_bls12_381_ext::CurveHooks;
+use ark_ec::{pairing::Pairing, CurveConfig};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use core::fmt::Debug;
+
+pub struct FuzzHooks;
+
+pub type Bls12_381 = ark_bls12_381_ext::Bls12_381<FuzzHooks>;
+pub type G1Affine = ark_bls12_381_ext::G1Affine<FuzzHooks>;
+pub type G2Affine = ark_bls12_381_ext::G2Affine<FuzzHooks>;
+type G1Projective = ark_bls12_381_ext::G1Projective<FuzzHooks>;
+type G2Projective = ark_bls12_381_ext::G2Projective<FuzzHooks>;
+type G1Config = ark_bls12_381_ext::g1::Config<FuzzHooks>;
+type G2Config = ark_bls12_381_ext::g2::Config<FuzzHooks>;
+
+impl CurveHooks for FuzzHooks {
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_381 as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_381 as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bls12_381, ArkBls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <Bls12_381 as Pairing>::TargetField,
+    ) -> Result<<Bls12_381 as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bls12_381, ArkBls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective, ()> {
+        test_utils::msm_sw_generic::<G1Config, ArkG1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[G2Affine],
+        scalars: &[<G2Config as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective, ()> {
+        test_utils::msm_sw_generic::<G2Config, ArkG2Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &G1Projective,
+        scalar: &[u64],
+    ) -> Result<G1Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &G2Projective,
+        scalar: &[u64],
+    ) -> Result<G2Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G2Config, ArkG2Config>(base, scalar)
+    }
+}
+
+/// Feed `data` to `deserialize_with_mode` in every compress and validate combination.
+///
+/// Decoding must never panic. Whenever a point is obtained, its compressed encoding
+/// must be stable across a decompression round trip.
+pub fn check_deserialize<T>(data: &[u8])
+where
+    T: CanonicalSerialize + CanonicalDeserialize + PartialEq + Debug,
+{
+    for compress in [Compress::Yes, Compress::No] {
+        for validate in [Validate::Yes, Validate::No] {
+            let Ok(point) = T::deserialize_with_mode(data, compress, validate) else {
+                continue;
+            };
+            // Uncompressed unchecked points may be off the curve, thus without a
+            // meaningful compressed encoding.
+            if compress == Compress::No && validate == Validate::No {
+                continue;
+            }
+            let compressed = encode(&point, Compress::Yes);
+            let decompressed = T::deserialize_with_mode(&compressed[..], Compress::Yes, validate)
+                .expect("compressed encoding of a decoded point must decode");
+            assert_eq!(decompressed, point);
+            assert_eq!(encode(&decompressed, Compress::Yes), compressed);
+        }
+    }
+}
+
+fn encode<T: CanonicalSerialize>(point: &T, compress: Compress) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(point.serialized_size(compress));
+    point
+        .serialize_with_mode(&mut buf, compress)
+        .expect("serialization into a vector never fails");
+    buf
+}
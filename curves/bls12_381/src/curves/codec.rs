@@ -0,0 +1,418 @@
+//! Encoding helpers for the data exchanged with the hooks.
+
+use crate::{fr::Fr, g1, g2, CurveHooks, G1Affine, G1Projective, G2Affine, G2Projective};
+
+use ark_bls12_381::Fq12;
+use ark_models_ext::{AffineRepr, CurveGroup};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Validate,
+};
+use ark_std::{
+    fmt,
+    io::{Read, Write},
+    string::String,
+    vec::Vec,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+/// Codec policy used to encode the data exchanged with the host.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    pub compress: Compress,
+    pub validate: Validate,
+}
+
+impl Usage {
+    pub const fn new(compress: Compress, validate: Validate) -> Self {
+        Self { compress, validate }
+    }
+}
+
+/// Uncompressed and unvalidated, i.e. the cheapest to encode and decode.
+pub const DEFAULT_USAGE: Usage = Usage::new(Compress::No, Validate::No);
+
+/// Encode `value` according to `usage`.
+pub fn encode<T: CanonicalSerialize>(value: &T, usage: Usage) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(value.serialized_size(usage.compress));
+    // Writing into a `Vec` can't fail.
+    let _ = value.serialize_with_mode(&mut buf, usage.compress);
+    buf
+}
+
+/// Decode a `T` from `bytes` according to `usage`.
+pub fn decode<T: CanonicalDeserialize>(mut bytes: &[u8], usage: Usage) -> Result<T, ()> {
+    T::deserialize_with_mode(&mut bytes, usage.compress, usage.validate).map_err(|_| ())
+}
+
+/// Reusable buffer for the data encoded for the host.
+///
+/// `encode` allocates a fresh `Vec` on every call. Tight loops can instead keep a
+/// `SerializeScratch` around: the allocation is reused as long as the encodings fit.
+#[derive(Clone, Default)]
+pub struct SerializeScratch {
+    buf: Vec<u8>,
+}
+
+impl SerializeScratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Bytes currently allocated by the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Encode `value` according to `usage`, overwriting the previous encoding.
+    ///
+    /// Same bytes as `encode(value, usage)`.
+    pub fn encode<T: CanonicalSerialize + ?Sized>(&mut self, value: &T, usage: Usage) -> &[u8] {
+        self.buf.clear();
+        // Writing into a `Vec` can't fail.
+        let _ = value.serialize_with_mode(&mut self.buf, usage.compress);
+        &self.buf
+    }
+
+    /// Encode `a` and `b` according to `usage`, overwriting the previous encodings.
+    ///
+    /// Same bytes as `(encode(a, usage), encode(b, usage))`, e.g. the bases and the
+    /// scalars of a multi scalar multiplication.
+    pub fn encode_pair<A: CanonicalSerialize + ?Sized, B: CanonicalSerialize + ?Sized>(
+        &mut self,
+        a: &A,
+        b: &B,
+        usage: Usage,
+    ) -> (&[u8], &[u8]) {
+        self.buf.clear();
+        let _ = a.serialize_with_mode(&mut self.buf, usage.compress);
+        let mid = self.buf.len();
+        let _ = b.serialize_with_mode(&mut self.buf, usage.compress);
+        self.buf.split_at(mid)
+    }
+
+    /// First `len` bytes of the allocation, whatever the length of the current encoding.
+    #[cfg(all(test, feature = "zeroize"))]
+    #[allow(unsafe_code)]
+    pub(crate) fn allocation(&self, len: usize) -> &[u8] {
+        assert!(len <= self.buf.capacity());
+        // SAFETY: in bounds of the allocation, only read after an encoding of at
+        // least `len` bytes initialized them.
+        unsafe { ark_std::slice::from_raw_parts(self.buf.as_ptr(), len) }
+    }
+}
+
+/// Wipes the whole allocation, keeping it for the next encodings.
+///
+/// Run on the scratch given to the `*_with_scratch` hooks, which may hold the
+/// encoded scalars once the hook returns.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SerializeScratch {
+    fn zeroize(&mut self) {
+        self.buf.zeroize();
+    }
+}
+
+/// Streaming serialization of a slice of points.
+///
+/// Writes the same bytes as `points.serialize_with_mode(w, compress)`, i.e. the
+/// `u64` length prefix followed by each point, without building the whole
+/// encoding in memory first. Useful to feed very large base vectors into a
+/// bounded buffer or a hasher.
+pub fn serialize_points_into<A: AffineRepr, W: Write>(
+    points: &[A],
+    mut w: W,
+    compress: Compress,
+) -> Result<(), SerializationError> {
+    (points.len() as u64).serialize_with_mode(&mut w, compress)?;
+    for point in points {
+        point.serialize_with_mode(&mut w, compress)?;
+    }
+    Ok(())
+}
+
+/// Strict deserialization of a single value.
+///
+/// Like `T::deserialize_with_mode`, but fails with `SerializationError::InvalidData`
+/// if `reader` is not exhausted afterwards, e.g. when trailing bytes follow the point.
+pub fn deserialize_exact<T: CanonicalDeserialize, R: Read>(
+    mut reader: R,
+    compress: Compress,
+    validate: Validate,
+) -> Result<T, SerializationError> {
+    let value = T::deserialize_with_mode(&mut reader, compress, validate)?;
+    match reader.read(&mut [0u8])? {
+        0 => Ok(value),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+/// Point decoded without any validation.
+///
+/// Returned by `deserialize_unvalidated`. The point is only reachable through
+/// `validate` (or `validate_g2_batch`), thus it can't be fed to a pairing or an MSM
+/// before being checked:
+///
+/// ```compile_fail
+/// # #![allow(deprecated)]
+/// use ark_bls12_381_ext::{codec, Bls12_381, G1Affine, G2Affine, PhantomHooks};
+/// use ark_ec::{pairing::Pairing, AffineRepr};
+/// use ark_serialize::Compress;
+///
+/// let mut bytes = [0; 48];
+/// bytes[0] = 0xc0;
+/// let p = codec::deserialize_unvalidated::<G1Affine<PhantomHooks>, _>(&bytes[..], Compress::Yes)
+///     .unwrap();
+/// Bls12_381::<PhantomHooks>::multi_miller_loop([p], [G2Affine::generator()]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unvalidated<A>(A);
+
+impl<A: AffineRepr> Unvalidated<A> {
+    /// Check that the point is on the curve and in the prime order subgroup.
+    pub fn validate(self) -> Result<A, SerializationError> {
+        self.0.check()?;
+        Ok(self.0)
+    }
+}
+
+/// Deserialize a point skipping the curve and subgroup checks, to be run later
+/// through `Unvalidated::validate`.
+pub fn deserialize_unvalidated<A: AffineRepr, R: Read>(
+    reader: R,
+    compress: Compress,
+) -> Result<Unvalidated<A>, SerializationError> {
+    A::deserialize_with_mode(reader, compress, Validate::No).map(Unvalidated)
+}
+
+/// Validate G2 points with a single `g2_in_subgroup_batch` hook call.
+///
+/// Returns the index of the first point not on the curve or out of the subgroup.
+pub fn validate_g2_batch<H: CurveHooks>(
+    points: Vec<Unvalidated<G2Affine<H>>>,
+) -> Result<Vec<G2Affine<H>>, usize> {
+    let points: Vec<G2Affine<H>> = points.into_iter().map(|p| p.0).collect();
+    g2::validate_on_curve_g2(&points)?;
+    g2::validate_subgroup_g2_batch(&points)?;
+    Ok(points)
+}
+
+/// Encode a G1 projective point as exchanged with the `mul_projective_g1` hook host.
+///
+/// The point is encoded in affine form according to `g1::Config::<H>::USAGE`.
+pub fn encode_projective_g1<H: CurveHooks>(point: &G1Projective<H>) -> Vec<u8> {
+    encode(&point.into_affine(), g1::Config::<H>::USAGE)
+}
+
+/// Decode a G1 projective point encoded via `encode_projective_g1`.
+///
+/// Trailing bytes are rejected.
+pub fn decode_projective_g1<H: CurveHooks>(bytes: &[u8]) -> Result<G1Projective<H>, ()> {
+    decode_exact::<G1Affine<H>>(bytes, g1::Config::<H>::USAGE).map(Into::into)
+}
+
+/// Encode a G2 projective point as exchanged with the `mul_projective_g2` hook host.
+///
+/// The point is encoded in affine form according to `g2::Config::<H>::USAGE`.
+pub fn encode_projective_g2<H: CurveHooks>(point: &G2Projective<H>) -> Vec<u8> {
+    encode(&point.into_affine(), g2::Config::<H>::USAGE)
+}
+
+/// Decode a G2 projective point encoded via `encode_projective_g2`.
+///
+/// Trailing bytes are rejected.
+pub fn decode_projective_g2<H: CurveHooks>(bytes: &[u8]) -> Result<G2Projective<H>, ()> {
+    decode_exact::<G2Affine<H>>(bytes, g2::Config::<H>::USAGE).map(Into::into)
+}
+
+/// Encode the little-endian scalar limbs given to the `mul_projective` hooks.
+///
+/// The encoding is the `u64` length prefix followed by each limb in little-endian.
+pub fn encode_scalar_limbs(limbs: &[u64]) -> Vec<u8> {
+    encode(&limbs, DEFAULT_USAGE)
+}
+
+/// Decode scalar limbs encoded via `encode_scalar_limbs`.
+///
+/// Trailing bytes are rejected.
+pub fn decode_scalar_limbs(bytes: &[u8]) -> Result<Vec<u64>, ()> {
+    decode_exact(bytes, DEFAULT_USAGE)
+}
+
+/// Encode the scalars given to the `msm` hooks.
+///
+/// The encoding is the `u64` length prefix followed by each scalar canonical encoding.
+pub fn encode_scalars(scalars: &[Fr]) -> Vec<u8> {
+    encode(&scalars, DEFAULT_USAGE)
+}
+
+/// Decode scalars encoded via `encode_scalars`.
+///
+/// Non canonical scalars and trailing bytes are rejected.
+pub fn decode_scalars(bytes: &[u8]) -> Result<Vec<Fr>, ()> {
+    decode_exact(bytes, Usage::new(Compress::No, Validate::Yes))
+}
+
+/// Encode a target field element as exchanged with the pairing hooks.
+///
+/// The twelve `Fq` coefficients are encoded according to `DEFAULT_USAGE`, i.e.
+/// 576 bytes.
+pub fn encode_target_field(f: &Fq12) -> Vec<u8> {
+    encode(f, DEFAULT_USAGE)
+}
+
+/// Decode a target field element encoded via `encode_target_field`.
+///
+/// Non canonical coefficients and trailing bytes are rejected.
+pub fn decode_target_field(bytes: &[u8]) -> Result<Fq12, ()> {
+    decode_exact(bytes, DEFAULT_USAGE)
+}
+
+pub(crate) fn decode_exact<T: CanonicalDeserialize>(bytes: &[u8], usage: Usage) -> Result<T, ()> {
+    deserialize_exact(bytes, usage.compress, usage.validate).map_err(|_| ())
+}
+
+/// Encode G1 points into a single buffer, without the length prefix.
+///
+/// Returns the concatenated encodings together with the start offset of each
+/// point, so that the receiver can decode any point without decoding the others.
+pub fn serialize_indexed_g1<H: CurveHooks>(
+    points: &[G1Affine<H>],
+    compress: Compress,
+) -> (Vec<u8>, Vec<usize>) {
+    let size = G1Affine::<H>::generator().serialized_size(compress);
+    let mut buf = Vec::with_capacity(points.len() * size);
+    let mut offsets = Vec::with_capacity(points.len());
+    for point in points {
+        offsets.push(buf.len());
+        // Writing into a `Vec` can't fail.
+        let _ = point.serialize_with_mode(&mut buf, compress);
+    }
+    (buf, offsets)
+}
+
+/// Decode `count` concatenated G1 points.
+///
+/// The points are read in place from `bytes`, which must be exactly `count`
+/// encodings of `G1Affine::serialized_size(compress)` bytes. Every point is
+/// validated. On any error returns `Err(())`.
+pub fn decode_g1_points<H: CurveHooks>(
+    bytes: &[u8],
+    count: usize,
+    compress: Compress,
+) -> Result<Vec<G1Affine<H>>, ()> {
+    decode_points(bytes, count, compress, Validate::Yes)
+}
+
+/// Decode `count` concatenated G2 points.
+///
+/// The points are read in place from `bytes`, which must be exactly `count`
+/// encodings of `G2Affine::serialized_size(compress)` bytes. Every point is
+/// validated, the subgroup checks with a single `g2_in_subgroup_batch` hook call.
+/// On any error returns `Err(())`.
+pub fn decode_g2_points<H: CurveHooks>(
+    bytes: &[u8],
+    count: usize,
+    compress: Compress,
+) -> Result<Vec<G2Affine<H>>, ()> {
+    let points = decode_points(bytes, count, compress, Validate::No)?;
+    g2::validate_on_curve_g2(&points).map_err(|_| ())?;
+    g2::validate_subgroup_g2_batch(&points).map_err(|_| ())?;
+    Ok(points)
+}
+
+fn decode_points<A: AffineRepr>(
+    bytes: &[u8],
+    count: usize,
+    compress: Compress,
+    validate: Validate,
+) -> Result<Vec<A>, ()> {
+    let size = A::zero().serialized_size(compress);
+    if count.checked_mul(size) != Some(bytes.len()) {
+        return Err(());
+    }
+    let mut points = Vec::with_capacity(count);
+    for chunk in bytes.chunks_exact(size) {
+        let point = A::deserialize_with_mode(chunk, compress, validate).map_err(|_| ())?;
+        points.push(point);
+    }
+    Ok(points)
+}
+
+/// Decode a compressed and validated point from a hex string, optionally `0x` prefixed.
+pub(crate) fn point_from_hex<A: AffineRepr>(s: &str) -> Result<A, ()> {
+    let s = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    let bytes = hex::decode(s).map_err(|_| ())?;
+    deserialize_exact(&bytes[..], Compress::Yes, Validate::Yes).map_err(|_| ())
+}
+
+/// Decode a validated point, compressed or not according to the length of `bytes`.
+pub(crate) fn point_from_bytes<A: AffineRepr>(bytes: &[u8]) -> Result<A, SerializationError> {
+    let compress = match bytes.len() {
+        len if len == A::generator().compressed_size() => Compress::Yes,
+        len if len == A::generator().uncompressed_size() => Compress::No,
+        _ => return Err(SerializationError::InvalidData),
+    };
+    deserialize_exact(bytes, compress, Validate::Yes)
+}
+
+/// Decode a compressed and validated point from a standard padded base64 string.
+pub(crate) fn point_from_base64<A: AffineRepr>(s: &str) -> Result<A, ()> {
+    let bytes = BASE64.decode(s).map_err(|_| ())?;
+    deserialize_exact(&bytes[..], Compress::Yes, Validate::Yes).map_err(|_| ())
+}
+
+/// Encode a point as a `0x` prefixed hex string of its compressed encoding.
+pub(crate) fn point_to_hex<A: AffineRepr>(point: &A) -> String {
+    let bytes = encode(point, Usage::new(Compress::Yes, Validate::No));
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    s.push_str(&hex::encode(bytes));
+    s
+}
+
+/// Number of leading and trailing bytes shown by `HexDebug`.
+const HEX_DEBUG_BYTES: usize = 4;
+
+/// `Debug` wrapper printing a point as its compressed encoding in hex.
+///
+/// Only the first and last bytes are printed, e.g. `0x97f1d3a7..db22c6bb` for the
+/// G1 generator, while the alternate form (`{:#?}`) prints the whole encoding.
+/// Meant for logs, where the upstream `Debug` of the coordinates is unreadable.
+pub struct HexDebug<'a, A: AffineRepr>(pub &'a A);
+
+impl<A: AffineRepr> fmt::Debug for HexDebug<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = encode(self.0, Usage::new(Compress::Yes, Validate::No));
+        if f.alternate() || bytes.len() <= 2 * HEX_DEBUG_BYTES {
+            return write!(f, "0x{}", hex::encode(&bytes));
+        }
+        let (head, tail) = (
+            &bytes[..HEX_DEBUG_BYTES],
+            &bytes[bytes.len() - HEX_DEBUG_BYTES..],
+        );
+        write!(f, "0x{}..{}", hex::encode(head), hex::encode(tail))
+    }
+}
+
+/// `Display` wrapper printing a point as the whole lowercase hex of its compressed
+/// encoding, with no `0x` prefix.
+///
+/// Matches the format of common BLS tooling, e.g. to copy a public key from logs.
+/// The output is accepted by `g1::from_hex` and `g2::from_hex`.
+pub struct HexDisplay<'a, A: AffineRepr>(pub &'a A);
+
+impl<A: AffineRepr> fmt::Display for HexDisplay<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = encode(self.0, Usage::new(Compress::Yes, Validate::No));
+        f.write_str(&hex::encode(bytes))
+    }
+}
@@ -0,0 +1,83 @@
+//! Reference cost model of the hooked operations.
+//!
+//! Every cost is a rough count of `Fq` multiplications performed by the textbook
+//! algorithm, as a pure function of the input size. The counts are meant to be
+//! scaled by a benchmarked per-unit weight, not to be used as weights directly.
+
+use ark_ff::PrimeField;
+
+use crate::fr::Fr;
+
+const SCALAR_BITS: u64 = Fr::MODULUS_BIT_SIZE as u64;
+
+/// Mixed addition on G1.
+pub const G1_ADD: u64 = 11;
+/// Doubling on G1.
+pub const G1_DOUBLE: u64 = 6;
+/// Mixed addition on G2 (an `Fq2` multiplication costs three `Fq` ones).
+pub const G2_ADD: u64 = 3 * G1_ADD;
+/// Doubling on G2.
+pub const G2_DOUBLE: u64 = 3 * G1_DOUBLE;
+
+/// Miller loop cost of a single pair, without the shared `Fq12` squarings.
+pub const MILLER_LOOP_PAIR: u64 = 63 * 75 + 5 * 81;
+/// Miller loop cost shared by all the pairs (the `Fq12` squarings).
+pub const MILLER_LOOP_BASE: u64 = 63 * 36;
+/// Final exponentiation.
+pub const FINAL_EXPONENTIATION: u64 = 7_500;
+
+/// Window size of the bucket method for `n` bases.
+fn msm_window(n: u64) -> u64 {
+    if n < 32 {
+        3
+    } else {
+        (u64::BITS - n.leading_zeros()) as u64 * 69 / 100 + 2
+    }
+}
+
+fn msm_cost(n: usize, add: u64, double: u64) -> u64 {
+    let n = n as u64;
+    if n == 0 {
+        return 0;
+    }
+    let c = msm_window(n);
+    let windows = SCALAR_BITS.div_ceil(c);
+    // Per window: one addition per base and two per bucket for the running sums.
+    windows * (n + 2 * (1 << c)) * add + SCALAR_BITS * double
+}
+
+/// Multi scalar multiplication on G1 of `n` bases.
+pub fn msm_g1_cost(n: usize) -> u64 {
+    msm_cost(n, G1_ADD, G1_DOUBLE)
+}
+
+/// Multi scalar multiplication on G2 of `n` bases.
+pub fn msm_g2_cost(n: usize) -> u64 {
+    msm_cost(n, G2_ADD, G2_DOUBLE)
+}
+
+/// Scalar multiplication on G1 (double and add, half of the bits set on average).
+pub fn mul_g1_cost() -> u64 {
+    SCALAR_BITS * G1_DOUBLE + SCALAR_BITS / 2 * G1_ADD
+}
+
+/// Scalar multiplication on G2 (double and add, half of the bits set on average).
+pub fn mul_g2_cost() -> u64 {
+    SCALAR_BITS * G2_DOUBLE + SCALAR_BITS / 2 * G2_ADD
+}
+
+/// Multi Miller loop over `n` pairs.
+pub fn multi_miller_loop_cost(n: usize) -> u64 {
+    match n {
+        0 => 0,
+        n => MILLER_LOOP_BASE + n as u64 * MILLER_LOOP_PAIR,
+    }
+}
+
+/// Product of `n` pairings, i.e. a multi Miller loop followed by a final exponentiation.
+pub fn pairing_cost(n: usize) -> u64 {
+    match n {
+        0 => 0,
+        n => multi_miller_loop_cost(n) + FINAL_EXPONENTIATION,
+    }
+}
@@ -0,0 +1,135 @@
+//! Field element helpers.
+//!
+//! Conversions between field elements and plain `u64` limbs: limbs are
+//! little-endian (least significant limb first) and hold the canonical value of
+//! the element, not its internal Montgomery form.
+
+use crate::{fq::Fq, fq12::Fq12, fr::Fr, util, CurveHooks};
+
+use ark_ff::{batch_inversion, BigInt, BigInteger, Field, PrimeField};
+use ark_std::vec::Vec;
+
+/// Inverses of `scalars` jumping into the user-defined `fr_batch_inverse` hook.
+///
+/// A zero scalar is mapped to zero. On any internal error falls back to the
+/// in-Rust batch inversion.
+pub fn fr_batch_inverse<H: CurveHooks>(scalars: &[Fr]) -> Vec<Fr> {
+    util::trace_hook!("bls12_381_fr_batch_inverse", scalars => crate::codec::DEFAULT_USAGE);
+    util::strict_hook::<H, _>(
+        H::bls12_381_fr_batch_inverse(scalars),
+        "bls12_381_fr_batch_inverse",
+    )
+    .ok()
+    .filter(|res| res.len() == scalars.len())
+    .unwrap_or_else(|| fr_batch_inverse_default(scalars))
+}
+
+/// In-Rust batch inversion (Montgomery's trick), zero scalars are left as they are.
+pub(crate) fn fr_batch_inverse_default(scalars: &[Fr]) -> Vec<Fr> {
+    let mut res = scalars.to_vec();
+    batch_inversion(&mut res);
+    res
+}
+
+/// Square root of `x` jumping into the user-defined `fq_sqrt` hook.
+///
+/// Returns `None` if `x` is not a square. Neither a root not squaring to `x` nor a
+/// non-square report for a square, checked through the Legendre symbol, is trusted:
+/// as on any internal error, falls back to the in-Rust computation.
+pub fn fq_sqrt<H: CurveHooks>(x: &Fq) -> Option<Fq> {
+    util::trace_hook!("bls12_381_fq_sqrt", x => crate::codec::DEFAULT_USAGE);
+    util::strict_hook::<H, _>(H::bls12_381_fq_sqrt(x), "bls12_381_fq_sqrt")
+        .ok()
+        .filter(|res| match res {
+            Some(root) => root.square() == *x,
+            None => x.legendre().is_qnr(),
+        })
+        .unwrap_or_else(|| fq_sqrt_default(x))
+}
+
+/// In-Rust square root.
+///
+/// As `q = 3 mod 4` (i.e. `valuation(q - 1, 2) = 1`), Tonelli-Shanks reduces to
+/// the single exponentiation `r = x^((q+1)/4)`, then `r` is a root iff `r^2 = x`.
+pub(crate) fn fq_sqrt_default(x: &Fq) -> Option<Fq> {
+    x.sqrt()
+}
+
+/// Scalar field element with value `x`, without importing any *Arkworks* trait.
+///
+/// Same as `Fr::from(x)`.
+///
+/// ```
+/// # #![allow(deprecated)]
+/// use ark_bls12_381_ext::{fr_from_str, fr_from_u64, g1, G1Affine, PhantomHooks};
+///
+/// let s = fr_from_u64(3);
+/// assert_eq!(fr_from_str("3"), Ok(s));
+///
+/// let g = G1Affine::<PhantomHooks>::new_unchecked(g1::G1_GENERATOR_X, g1::G1_GENERATOR_Y);
+/// assert_eq!(g1::mul_glv(&g, &s), g + g + g);
+/// ```
+pub fn fr_from_u64(x: u64) -> Fr {
+    Fr::from(x)
+}
+
+/// Scalar field element from its decimal representation.
+///
+/// Same as `Fr::from_str(s)`, without importing `FromStr`. The value, possibly
+/// negative, is reduced modulo `r`.
+pub fn fr_from_str(s: &str) -> Result<Fr, ()> {
+    s.parse()
+}
+
+/// Frobenius map `x^(q^power)` jumping into the user-defined `fq12_frobenius` hook.
+///
+/// On any internal error falls back to the in-Rust `Field::frobenius_map`.
+pub fn fq12_frobenius<H: CurveHooks>(x: &Fq12, power: usize) -> Fq12 {
+    util::trace_hook!("bls12_381_fq12_frobenius", x => crate::codec::DEFAULT_USAGE);
+    util::strict_hook::<H, _>(
+        H::bls12_381_fq12_frobenius(x, power),
+        "bls12_381_fq12_frobenius",
+    )
+    .unwrap_or_else(|_| fq12_frobenius_default(x, power))
+}
+
+/// In-Rust Frobenius map.
+pub(crate) fn fq12_frobenius_default(x: &Fq12, power: usize) -> Fq12 {
+    x.frobenius_map(power)
+}
+
+/// Scalar field modulus `r`, i.e. the order of the prime order subgroups.
+pub fn scalar_field_modulus() -> BigInt<4> {
+    Fr::MODULUS
+}
+
+/// Base field modulus `q`.
+pub fn base_field_modulus() -> BigInt<6> {
+    Fq::MODULUS
+}
+
+/// Scalar field element with the value given by `limbs`, reduced modulo `r`.
+pub fn fr_from_limbs(limbs: [u64; 4]) -> Fr {
+    from_limbs(limbs)
+}
+
+/// Canonical limbs of `fr`.
+pub fn fr_to_limbs(fr: &Fr) -> [u64; 4] {
+    fr.into_bigint().0
+}
+
+/// Base field element with the value given by `limbs`, reduced modulo `q`.
+pub fn fq_from_limbs(limbs: [u64; 6]) -> Fq {
+    from_limbs(limbs)
+}
+
+/// Canonical limbs of `fq`.
+pub fn fq_to_limbs(fq: &Fq) -> [u64; 6] {
+    fq.into_bigint().0
+}
+
+fn from_limbs<F: PrimeField<BigInt = BigInt<N>>, const N: usize>(limbs: [u64; N]) -> F {
+    let int = BigInt::new(limbs);
+    // Values above the modulus go through the (slower) generic reduction.
+    F::from_bigint(int).unwrap_or_else(|| F::from_le_bytes_mod_order(&int.to_bytes_le()))
+}
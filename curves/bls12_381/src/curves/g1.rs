@@ -1,24 +1,32 @@
 use crate::{
+    codec::{self, SerializeScratch, Usage},
+    fr::Fr,
+    msm::CheckedMsmError,
     util::{
-        read_g1_compressed, read_g1_uncompressed, serialize_fq, EncodingFlags, G1_SERIALIZED_SIZE,
+        self, read_g1_compressed, read_g1_uncompressed, serialize_fq, EncodingFlags,
+        G1_SERIALIZED_SIZE,
     },
     CurveHooks,
 };
 
-use ark_bls12_381::g1::Config as ArkConfig;
-use ark_ff::PrimeField;
+use ark_bls12_381::{fq::Fq, g1::Config as ArkConfig};
+use ark_ec::scalar_mul::sw_double_and_add_projective;
+use ark_ff::{AdditiveGroup, BigInt, BigInteger, Field, PrimeField};
 use ark_models_ext::{
     bls12,
     bls12::Bls12Config,
     short_weierstrass::{Affine, SWCurveConfig},
-    AffineRepr, CurveConfig, PrimeGroup,
+    AffineRepr, CurveConfig, CurveGroup,
 };
 use ark_serialize::{Compress, SerializationError, Validate};
 use ark_std::{
     io::{Read, Write},
     marker::PhantomData,
     ops::Neg,
-    One,
+    rand::Rng,
+    string::String,
+    vec::Vec,
+    One, UniformRand, Zero,
 };
 
 pub use ark_bls12_381::g1::{BETA, G1_GENERATOR_X, G1_GENERATOR_Y};
@@ -29,6 +37,55 @@ pub type G1Projective<H> = bls12::G1Projective<crate::Config<H>>;
 #[derive(Clone, Copy)]
 pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
 
+impl<H: CurveHooks> Config<H> {
+    /// Default codec policy of the `codec` helpers for G1, see `CurveHooks::G1_USAGE`.
+    pub const USAGE: Usage = H::G1_USAGE;
+
+    /// Multi scalar multiplication jumping into the user-defined `msm_g1_with_scratch` hook.
+    ///
+    /// Same contract as `msm`, the hook may reuse `scratch` to encode the inputs.
+    /// With the `zeroize` feature `scratch` is wiped once the hook returns.
+    pub fn msm_with_scratch(
+        bases: &[G1Affine<H>],
+        scalars: &[<Self as CurveConfig>::ScalarField],
+        scratch: &mut SerializeScratch,
+    ) -> Result<G1Projective<H>, usize> {
+        if bases.len() != scalars.len() {
+            return Err(bases.len().min(scalars.len()));
+        }
+        util::trace_hook!(
+            "bls12_381_msm_g1_with_scratch",
+            bases => H::G1_USAGE,
+            scalars => H::G1_USAGE,
+        );
+        let res = H::bls12_381_msm_g1_with_scratch(bases, scalars, scratch);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(scratch);
+        util::expect_hook::<H, _>(res, "bls12_381_msm_g1_with_scratch").map_err(|_| 0)
+    }
+
+    /// Projective multiplication jumping into the user-defined `mul_projective_g1_with_scratch` hook.
+    ///
+    /// Same contract as `mul_projective`, the hook may reuse `scratch` to encode the inputs.
+    /// With the `zeroize` feature `scratch` is wiped once the hook returns.
+    pub fn mul_projective_with_scratch(
+        base: &G1Projective<H>,
+        scalar: &[u64],
+        scratch: &mut SerializeScratch,
+    ) -> G1Projective<H> {
+        util::trace_hook!(
+            "bls12_381_mul_projective_g1_with_scratch",
+            base => H::G1_USAGE,
+            scalar => H::G1_USAGE,
+        );
+        let res = H::bls12_381_mul_projective_g1_with_scratch(base, scalar, scratch);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(scratch);
+        util::expect_hook::<H, _>(res, "bls12_381_mul_projective_g1_with_scratch")
+            .unwrap_or_default()
+    }
+}
+
 impl<H: CurveHooks> CurveConfig for Config<H> {
     const COFACTOR: &'static [u64] = <ArkConfig as CurveConfig>::COFACTOR;
     const COFACTOR_INV: Self::ScalarField = <ArkConfig as CurveConfig>::COFACTOR_INV;
@@ -51,7 +108,7 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         if bases.len() != scalars.len() {
             return Err(bases.len().min(scalars.len()));
         }
-        H::bls12_381_msm_g1(bases, scalars).map_err(|_| 0)
+        util::expect_hook::<H, _>(try_msm(bases, scalars), "bls12_381_msm_g1").map_err(|_| 0)
     }
 
     /// Projective multiplication jumping into the user-defined `mul_projective` hook.
@@ -59,7 +116,7 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
     /// On any internal error returns `Projective::zero()`.
     #[inline(always)]
     fn mul_projective(base: &G1Projective<H>, scalar: &[u64]) -> G1Projective<H> {
-        H::bls12_381_mul_projective_g1(base, scalar).unwrap_or_default()
+        try_mul_projective(base, scalar).unwrap_or_default()
     }
 
     /// Affine multiplication jumping into the user-defined `mul_projective` hook.
@@ -75,19 +132,10 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         <ArkConfig as SWCurveConfig>::mul_by_a(elem)
     }
 
-    // Verbatim copy of upstream implementation.
-    //
-    // Can't call it directly because of different `Affine` configuration.
+    // Same check as upstream, see `is_in_subgroup_with`.
     #[inline(always)]
     fn is_in_correct_subgroup_assuming_on_curve(p: &G1Affine<H>) -> bool {
-        let x_times_p = p.mul_bigint(crate::Config::<H>::X);
-        if x_times_p.eq(p) && !p.infinity {
-            return false;
-        }
-
-        let minus_x_squared_times_p = x_times_p.mul_bigint(crate::Config::<H>::X).neg();
-        let endomorphism_p = endomorphism(p);
-        minus_x_squared_times_p.eq(&endomorphism_p)
+        is_in_subgroup_with(p, SubgroupCheck::Endomorphism)
     }
 
     // Verbatim copy of upstream implementation.
@@ -170,6 +218,58 @@ fn one_minus_x(
     <ArkConfig as CurveConfig>::ScalarField::one() - x
 }
 
+/// G1 subgroup membership check algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SubgroupCheck {
+    /// Check `[-x^2]P == φ(P)`, with `φ` the GLV endomorphism.
+    ///
+    /// Used by `is_in_correct_subgroup_assuming_on_curve`, and thus by deserialization.
+    #[default]
+    Endomorphism,
+    /// Check `[r]P == 0`, with `r` the subgroup order.
+    ///
+    /// Much slower, but follows the definition of the subgroup. Always runs in-Rust,
+    /// whatever the hooks return.
+    Exact,
+}
+
+/// Check whether `p`, assumed to be on the curve, is in the prime order subgroup using `method`.
+pub fn is_in_subgroup_with<H: CurveHooks>(p: &G1Affine<H>, method: SubgroupCheck) -> bool {
+    match method {
+        // Copy of upstream implementation, with the multiplications by `x` run in-Rust
+        // only with the `native-subgroup-mul` feature.
+        SubgroupCheck::Endomorphism => {
+            let x_times_p = util::mul_by_x::<H, Config<H>>(&p.into_group());
+            // `x - 1` is coprime with the subgroup order, thus a point fixed by `[x]`
+            // other than the identity can't be in the subgroup. Rejected early, with
+            // no need of the second multiplication.
+            if x_times_p.eq(p) && !p.infinity {
+                return false;
+            }
+
+            let minus_x_squared_times_p = util::negate(&util::mul_by_x::<H, Config<H>>(&x_times_p));
+            let endomorphism_p = endomorphism(p);
+            minus_x_squared_times_p.eq(&endomorphism_p)
+        }
+        // In-Rust double-and-add, not trusting the `mul_projective` hook.
+        SubgroupCheck::Exact => {
+            let r = <Config<H> as CurveConfig>::ScalarField::MODULUS;
+            sw_double_and_add_projective(&p.into_group(), r).is_zero()
+        }
+    }
+}
+
+/// Check whether `p`, assumed to be on the curve, is in the prime order subgroup.
+///
+/// Same as `is_in_correct_subgroup_assuming_on_curve`. Following
+/// <https://eprint.iacr.org/2019/814.pdf>, checks `[-x^2]P == φ(P)`, with `x` the BLS
+/// parameter and `φ(x, y) = (βx, y)` the GLV endomorphism, which acts on the subgroup
+/// as the multiplication by `-x^2`. Non-identity points fixed by `[x]` are rejected
+/// before the second multiplication, as none of them is in the subgroup.
+pub fn is_torsion_free<H: CurveHooks>(p: &G1Affine<H>) -> bool {
+    is_in_subgroup_with(p, SubgroupCheck::Endomorphism)
+}
+
 pub fn endomorphism<H: CurveHooks>(p: &G1Affine<H>) -> G1Affine<H> {
     // Endomorphism of the points on the curve.
     // endomorphism_p(x,y) = (BETA * x, y)
@@ -178,3 +278,303 @@ pub fn endomorphism<H: CurveHooks>(p: &G1Affine<H>) -> G1Affine<H> {
     res.x *= BETA;
     res
 }
+
+/// `x^2`, with `x` the BLS parameter.
+///
+/// The endomorphism acts on the subgroup as the multiplication by `λ = -x^2`.
+const X_SQUARED: u128 = {
+    let x = <ark_bls12_381::Config as ark_ec::bls12::Bls12Config>::X[0] as u128;
+    x * x
+};
+
+/// Split `k` into `(k1, k2)`, both smaller than `2^128`, such that `k = k1 - k2 * λ`.
+///
+/// Reduces `(k, 0)` by the lattice vector `(x^2, 1)`, i.e. `k1 = k mod x^2` and
+/// `k2 = k / x^2`. As `r = x^4 - x^2 + 1`, `k2` is smaller than `x^2` as well.
+fn glv_decompose(k: &BigInt<4>) -> (BigInt<4>, BigInt<4>) {
+    let z = BigInt::<4>::new([X_SQUARED as u64, (X_SQUARED >> 64) as u64, 0, 0]);
+    let mut quotient = BigInt::<4>::zero();
+    let mut rem = BigInt::<4>::zero();
+    for i in (0..k.num_bits() as usize).rev() {
+        rem.mul2();
+        rem.0[0] |= k.get_bit(i) as u64;
+        if rem >= z {
+            rem.sub_with_borrow(&z);
+            quotient.0[i / 64] |= 1 << (i % 64);
+        }
+    }
+    (rem, quotient)
+}
+
+/// In-Rust multiplication of `p` by `scalar`, accelerated with the GLV endomorphism.
+///
+/// Decomposes the scalar into two halves `k = k1 - k2 * λ` and computes
+/// `[k1]P + [k2](-φ(P))` with a joint double-and-add, thus halving the doublings.
+/// Never jumps into the hooks, `p` is assumed to be in the prime order subgroup.
+pub fn mul_glv<H: CurveHooks>(p: &G1Affine<H>, scalar: &Fr) -> G1Projective<H> {
+    let (k1, k2) = glv_decompose(&scalar.into_bigint());
+    let p1 = p.into_group();
+    let p2 = endomorphism(p).into_group().neg();
+    let p12 = p1 + p2;
+
+    let bits = k1.num_bits().max(k2.num_bits()) as usize;
+    let mut res = G1Projective::<H>::zero();
+    for i in (0..bits).rev() {
+        res.double_in_place();
+        match (k1.get_bit(i), k2.get_bit(i)) {
+            (true, true) => res += p12,
+            (true, false) => res += p1,
+            (false, true) => res += p2,
+            (false, false) => (),
+        }
+    }
+    res
+}
+
+/// Multi scalar multiplication jumping into the user-defined `msm_g1_affine` hook.
+///
+/// Same as `msm(...).into_affine()` but allows the host to perform the affine
+/// conversion. On length mismatch returns the shorter length, on any internal
+/// error returns `Err(0)`.
+pub fn msm_into_affine<H: CurveHooks>(
+    bases: &[G1Affine<H>],
+    scalars: &[<Config<H> as CurveConfig>::ScalarField],
+) -> Result<G1Affine<H>, usize> {
+    if bases.len() != scalars.len() {
+        return Err(bases.len().min(scalars.len()));
+    }
+    util::trace_hook!(
+        "bls12_381_msm_g1_affine",
+        bases => H::G1_USAGE,
+        scalars => H::G1_USAGE,
+    );
+    util::expect_hook::<H, _>(
+        H::bls12_381_msm_g1_affine(bases, scalars),
+        "bls12_381_msm_g1_affine",
+    )
+    .map_err(|_| 0)
+}
+
+/// Check that every point is in the prime order subgroup, jumping into the
+/// user-defined `g1_in_subgroup_batch` hook.
+///
+/// Points are assumed to be on the curve. Returns the index of the first point out
+/// of the subgroup. On any internal error falls back to the in-Rust per point check.
+pub fn validate_subgroup_g1_batch<H: CurveHooks>(points: &[G1Affine<H>]) -> Result<(), usize> {
+    util::trace_hook!("bls12_381_g1_in_subgroup_batch", points => H::G1_USAGE);
+    let res = util::strict_hook::<H, _>(
+        H::bls12_381_g1_in_subgroup_batch(points),
+        "bls12_381_g1_in_subgroup_batch",
+    )
+    .ok()
+    .filter(|res| res.len() == points.len())
+    .unwrap_or_else(|| subgroup_check_g1_batch(points));
+    res.iter()
+        .position(|in_subgroup| !in_subgroup)
+        .map_or(Ok(()), Err)
+}
+
+/// In-Rust per point subgroup check.
+pub(crate) fn subgroup_check_g1_batch<H: CurveHooks>(points: &[G1Affine<H>]) -> Vec<bool> {
+    points.iter().map(is_torsion_free).collect()
+}
+
+/// Multi scalar multiplication of untrusted bases.
+///
+/// Checks the lengths, then that every base is on the curve and, through
+/// `validate_subgroup_g1_batch`, in the prime order subgroup before jumping into
+/// the user-defined `msm_g1` hook. Otherwise same contract as `msm`.
+pub fn checked_msm<H: CurveHooks>(
+    bases: &[G1Affine<H>],
+    scalars: &[<Config<H> as CurveConfig>::ScalarField],
+) -> Result<G1Projective<H>, CheckedMsmError> {
+    if bases.len() != scalars.len() {
+        return Err(CheckedMsmError::LengthMismatch(
+            bases.len().min(scalars.len()),
+        ));
+    }
+    validate_on_curve_g1(bases).map_err(CheckedMsmError::InvalidBase)?;
+    validate_subgroup_g1_batch(bases).map_err(CheckedMsmError::InvalidBase)?;
+    Config::<H>::msm(bases, scalars).map_err(|_| CheckedMsmError::Hook)
+}
+
+/// Multi scalar multiplication jumping into the user-defined `msm_g1` hook.
+///
+/// Same as `msm`, but a failure is returned as `Err(())` even with the
+/// `OnError::Default` policy, whose point at infinity is a valid sum for the
+/// verification helpers. The lengths are assumed to match.
+pub(crate) fn try_msm<H: CurveHooks>(
+    bases: &[G1Affine<H>],
+    scalars: &[<Config<H> as CurveConfig>::ScalarField],
+) -> Result<G1Projective<H>, ()> {
+    util::trace_hook!("bls12_381_msm_g1", bases => H::G1_USAGE, scalars => H::G1_USAGE);
+    util::strict_hook::<H, _>(H::bls12_381_msm_g1(bases, scalars), "bls12_381_msm_g1")
+}
+
+/// Projective multiplication jumping into the user-defined `mul_projective_g1` hook.
+///
+/// Same as `mul_projective`, but a failure is returned as `Err(())` instead of the
+/// point at infinity, which is a valid product for the verification helpers, even
+/// with the `OnError::Default` policy.
+pub(crate) fn try_mul_projective<H: CurveHooks>(
+    base: &G1Projective<H>,
+    scalar: &[u64],
+) -> Result<G1Projective<H>, ()> {
+    util::trace_hook!(
+        "bls12_381_mul_projective_g1",
+        base => H::G1_USAGE,
+        scalar => H::G1_USAGE,
+    );
+    util::strict_hook::<H, _>(
+        H::bls12_381_mul_projective_g1(base, scalar),
+        "bls12_381_mul_projective_g1",
+    )
+}
+
+/// Recover the G1 point with abscissa `x`.
+///
+/// `greatest` selects the lexicographically largest of the two candidate ordinates.
+/// Returns `None` if `x` is not the abscissa of a curve point or if the point is not
+/// in the prime order subgroup.
+pub fn from_x<H: CurveHooks>(x: Fq, greatest: bool) -> Option<G1Affine<H>> {
+    G1Affine::<H>::get_point_from_x_unchecked(x, greatest)
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+}
+
+/// Batch conversion to affine jumping into the user-defined `normalize_batch_g1` hook.
+///
+/// On any internal error falls back to the in-Rust `CurveGroup::normalize_batch`.
+pub fn normalize_batch_g1<H: CurveHooks>(points: &[G1Projective<H>]) -> Vec<G1Affine<H>> {
+    util::trace_hook!("bls12_381_normalize_batch_g1", points => H::G1_USAGE);
+    util::strict_hook::<H, _>(
+        H::bls12_381_normalize_batch_g1(points),
+        "bls12_381_normalize_batch_g1",
+    )
+    .ok()
+    .filter(|res| res.len() == points.len())
+    .unwrap_or_else(|| G1Projective::<H>::normalize_batch(points))
+}
+
+/// Batch conversion to affine jumping into the user-defined `batch_to_affine_g1` hook.
+///
+/// On any internal error falls back to the in-Rust `CurveGroup::normalize_batch`.
+pub fn batch_to_affine_g1<H: CurveHooks>(points: &[G1Projective<H>]) -> Vec<G1Affine<H>> {
+    util::trace_hook!("bls12_381_batch_to_affine_g1", points => H::G1_USAGE);
+    util::strict_hook::<H, _>(
+        H::bls12_381_batch_to_affine_g1(points),
+        "bls12_381_batch_to_affine_g1",
+    )
+    .ok()
+    .filter(|res| res.len() == points.len())
+    .unwrap_or_else(|| G1Projective::<H>::normalize_batch(points))
+}
+
+/// Sum of `points` jumping into the user-defined `sum_g1` hook.
+///
+/// On any internal error falls back to the in-Rust sum.
+pub fn sum_g1<H: CurveHooks>(points: &[G1Affine<H>]) -> G1Projective<H> {
+    util::trace_hook!("bls12_381_sum_g1", points => H::G1_USAGE);
+    util::strict_hook::<H, _>(H::bls12_381_sum_g1(points), "bls12_381_sum_g1")
+        .unwrap_or_else(|_| sum_g1_default(points))
+}
+
+/// In-Rust sum, with mixed additions.
+pub(crate) fn sum_g1_default<H: CurveHooks>(points: &[G1Affine<H>]) -> G1Projective<H> {
+    points.iter().sum()
+}
+
+/// Check whether `p` satisfies the curve equation `y^2 = x^3 + b` over `Fq`.
+///
+/// The point at infinity is on the curve. Says nothing about the subgroup
+/// membership, see `is_torsion_free`.
+pub fn is_on_curve<H: CurveHooks>(p: &G1Affine<H>) -> bool {
+    let b = <Config<H> as SWCurveConfig>::COEFF_B;
+    p.infinity || p.y.square() == p.x.square() * p.x + b
+}
+
+/// Check that every point satisfies the curve equation `y^2 = x^3 + b`.
+///
+/// Returns the index of the first point off the curve. The point at infinity is
+/// accepted, and the subgroup membership is not checked.
+pub fn validate_on_curve_g1<H: CurveHooks>(points: &[G1Affine<H>]) -> Result<(), usize> {
+    points
+        .iter()
+        .position(|p| !is_on_curve(p))
+        .map_or(Ok(()), Err)
+}
+
+/// Random point of the prime order subgroup, sampled without any host call.
+///
+/// Samples base field elements until one is the `x` coordinate of a curve point,
+/// then clears the cofactor with an in-Rust double-and-add. Usable where no host
+/// is available, e.g. for nothing-up-my-sleeve generators in a `no_std` runtime.
+pub fn random_g1<H: CurveHooks, R: Rng + ?Sized>(rng: &mut R) -> G1Projective<H> {
+    let h_eff = one_minus_x(crate::Config::<H>::X_IS_NEGATIVE, crate::Config::<H>::X).into_bigint();
+    loop {
+        let x = Fq::rand(rng);
+        if let Some(p) = G1Affine::<H>::get_point_from_x_unchecked(x, rng.gen()) {
+            return sw_double_and_add_projective(&p.into_group(), h_eff);
+        }
+    }
+}
+
+/// Negation of `p`.
+pub fn negate_g1<H: CurveHooks>(p: &G1Projective<H>) -> G1Projective<H> {
+    util::negate(p)
+}
+
+/// Constant time selection of `b` if `choice` is set, of `a` otherwise.
+///
+/// Runs in the same time for both choices, e.g. to select an opening point from
+/// a secret bit.
+#[cfg(feature = "ct")]
+pub fn conditional_select<H: CurveHooks>(
+    a: &G1Affine<H>,
+    b: &G1Affine<H>,
+    choice: subtle::Choice,
+) -> G1Affine<H> {
+    util::conditional_select_affine(a, b, choice, util::conditional_select_fq)
+}
+
+/// Decode a G1 point from its compressed (48 bytes) or uncompressed (96 bytes)
+/// encoding, telling the two apart by the length of `bytes`.
+///
+/// The point is validated. Any other length is rejected.
+pub fn try_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<G1Affine<H>, SerializationError> {
+    codec::point_from_bytes(bytes)
+}
+
+/// Decode a G1 point from the hex string of its compressed encoding.
+///
+/// The string may be `0x` prefixed. The point is validated and trailing bytes are rejected.
+pub fn from_hex<H: CurveHooks>(s: &str) -> Result<G1Affine<H>, ()> {
+    codec::point_from_hex(s)
+}
+
+/// Decode a G1 point from the standard padded base64 string of its compressed encoding.
+///
+/// The point is validated and trailing bytes are rejected.
+pub fn from_base64<H: CurveHooks>(s: &str) -> Result<G1Affine<H>, ()> {
+    codec::point_from_base64(s)
+}
+
+/// Encode a G1 point as the `0x` prefixed hex string of its compressed encoding.
+pub fn to_hex<H: CurveHooks>(point: &G1Affine<H>) -> String {
+    codec::point_to_hex(point)
+}
+
+/// Compressed encoding of a G1 point, without any allocation.
+pub fn to_compressed<H: CurveHooks>(point: &G1Affine<H>) -> [u8; G1_SERIALIZED_SIZE] {
+    let mut buf = [0u8; G1_SERIALIZED_SIZE];
+    // The buffer is exactly as large as the encoding.
+    let _ = Config::<H>::serialize_with_mode(point, &mut buf[..], Compress::Yes);
+    buf
+}
+
+/// Uncompressed encoding of a G1 point, without any allocation.
+pub fn to_uncompressed<H: CurveHooks>(point: &G1Affine<H>) -> [u8; 2 * G1_SERIALIZED_SIZE] {
+    let mut buf = [0u8; 2 * G1_SERIALIZED_SIZE];
+    // The buffer is exactly as large as the encoding.
+    let _ = Config::<H>::serialize_with_mode(point, &mut buf[..], Compress::No);
+    buf
+}
@@ -10,11 +10,16 @@ use ark_std::{
     io::{Read, Write},
     marker::PhantomData,
     ops::Neg,
+    string::String,
+    vec::Vec,
 };
 
 use crate::{
+    codec::{self, Usage},
+    msm::CheckedMsmError,
     util::{
-        read_g2_compressed, read_g2_uncompressed, serialize_fq, EncodingFlags, G2_SERIALIZED_SIZE,
+        self, read_g2_compressed, read_g2_uncompressed, serialize_fq2, EncodingFlags,
+        G2_SERIALIZED_SIZE,
     },
     CurveHooks,
 };
@@ -48,6 +53,11 @@ pub type G2Projective<H> = bls12::G2Projective<crate::Config<H>>;
 #[derive(Clone, Copy)]
 pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
 
+impl<H: CurveHooks> Config<H> {
+    /// Default codec policy of the `codec` helpers for G2, see `CurveHooks::G2_USAGE`.
+    pub const USAGE: Usage = H::G2_USAGE;
+}
+
 impl<H: CurveHooks> CurveConfig for Config<H> {
     const COFACTOR: &'static [u64] = <ArkConfig as CurveConfig>::COFACTOR;
     const COFACTOR_INV: Self::ScalarField = <ArkConfig as CurveConfig>::COFACTOR_INV;
@@ -70,7 +80,9 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         if bases.len() != scalars.len() {
             return Err(bases.len().min(scalars.len()));
         }
-        H::bls12_381_msm_g2(bases, scalars).map_err(|_| 0)
+        util::trace_hook!("bls12_381_msm_g2", bases => H::G2_USAGE, scalars => H::G2_USAGE);
+        util::expect_hook::<H, _>(H::bls12_381_msm_g2(bases, scalars), "bls12_381_msm_g2")
+            .map_err(|_| 0)
     }
 
     /// Projective multiplication jumping into the user-defined `mul_projective_g2` hook.
@@ -78,7 +90,7 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
     /// On any *external* error returns `Projective::zero()`.
     #[inline(always)]
     fn mul_projective(base: &G2Projective<H>, scalar: &[u64]) -> G2Projective<H> {
-        H::bls12_381_mul_projective_g2(base, scalar).unwrap_or_default()
+        try_mul_projective(base, scalar).unwrap_or_default()
     }
 
     /// Affine multiplication jumping into the user-defined `mul_projective_g2` hook.
@@ -94,14 +106,15 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         <ArkConfig as SWCurveConfig>::mul_by_a(elem)
     }
 
-    // Verbatim copy of upstream implementation.
+    // Copy of upstream implementation, with the multiplication by `x` run in-Rust
+    // only with the `native-subgroup-mul` feature.
     //
     // Can't call it directly because of different `Affine` configuration.
     #[inline(always)]
     fn is_in_correct_subgroup_assuming_on_curve(point: &G2Affine<H>) -> bool {
-        let mut x_times_point = point.mul_bigint(crate::Config::<H>::X);
+        let mut x_times_point = util::mul_by_x::<H, Self>(&point.into_group());
         if crate::Config::<H>::X_IS_NEGATIVE {
-            x_times_point = -x_times_point;
+            x_times_point = util::negate(&x_times_point);
         }
 
         let p_times_point = p_power_endomorphism(point);
@@ -154,10 +167,11 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         compress: Compress,
         validate: Validate,
     ) -> Result<G2Affine<H>, SerializationError> {
+        let twist = <crate::Config<H> as Bls12Config>::TWIST_TYPE;
         let p = if compress == Compress::Yes {
-            read_g2_compressed(&mut reader)?
+            read_g2_compressed(&mut reader, &twist)?
         } else {
-            read_g2_uncompressed(&mut reader)?
+            read_g2_uncompressed(&mut reader, &twist)?
         };
 
         if validate == Validate::Yes && !p.is_in_correct_subgroup_assuming_on_curve() {
@@ -184,11 +198,8 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
             p = G2Affine::<H>::zero();
         }
 
-        let mut x_bytes = [0u8; G2_SERIALIZED_SIZE];
-        let c1_bytes = serialize_fq(p.x.c1);
-        let c0_bytes = serialize_fq(p.x.c0);
-        x_bytes[0..48].copy_from_slice(&c1_bytes[..]);
-        x_bytes[48..96].copy_from_slice(&c0_bytes[..]);
+        let twist = <crate::Config<H> as Bls12Config>::TWIST_TYPE;
+        let x_bytes = serialize_fq2(p.x, &twist);
         if encoding.is_compressed {
             let mut bytes: [u8; G2_SERIALIZED_SIZE] = x_bytes;
 
@@ -197,11 +208,7 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
         } else {
             let mut bytes = [0u8; 2 * G2_SERIALIZED_SIZE];
 
-            let mut y_bytes = [0u8; G2_SERIALIZED_SIZE];
-            let c1_bytes = serialize_fq(p.y.c1);
-            let c0_bytes = serialize_fq(p.y.c0);
-            y_bytes[0..48].copy_from_slice(&c1_bytes[..]);
-            y_bytes[48..96].copy_from_slice(&c0_bytes[..]);
+            let y_bytes = serialize_fq2(p.y, &twist);
             bytes[0..G2_SERIALIZED_SIZE].copy_from_slice(&x_bytes);
             bytes[G2_SERIALIZED_SIZE..].copy_from_slice(&y_bytes);
 
@@ -220,6 +227,54 @@ impl<H: CurveHooks> SWCurveConfig for Config<H> {
     }
 }
 
+/// Projective multiplication jumping into the user-defined `mul_projective_g2` hook.
+///
+/// Same as `mul_projective`, but a failure is returned as `Err(())` instead of the
+/// point at infinity, which is a valid product for the verification helpers, even
+/// with the `OnError::Default` policy.
+pub(crate) fn try_mul_projective<H: CurveHooks>(
+    base: &G2Projective<H>,
+    scalar: &[u64],
+) -> Result<G2Projective<H>, ()> {
+    util::trace_hook!(
+        "bls12_381_mul_projective_g2",
+        base => H::G2_USAGE,
+        scalar => H::G2_USAGE,
+    );
+    util::strict_hook::<H, _>(
+        H::bls12_381_mul_projective_g2(base, scalar),
+        "bls12_381_mul_projective_g2",
+    )
+}
+
+/// Recover the G2 point with abscissa `x`.
+///
+/// `greatest` selects the lexicographically largest of the two candidate ordinates.
+/// Returns `None` if `x` is not the abscissa of a curve point or if the point is not
+/// in the prime order subgroup.
+pub fn from_x<H: CurveHooks>(x: Fq2, greatest: bool) -> Option<G2Affine<H>> {
+    G2Affine::<H>::get_point_from_x_unchecked(x, greatest)
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+}
+
+/// Cofactor of the G2 prime order subgroup, as little-endian `u64` limbs.
+///
+/// That is `(x^8 - 4x^7 + 5x^6 - 4x^4 + 6x^3 - 4x^2 - 4x + 13) / 9`, with `x` the
+/// curve parameter.
+pub fn g2_cofactor() -> &'static [u64] {
+    <ArkConfig as CurveConfig>::COFACTOR
+}
+
+/// Check whether `p`, assumed to be on the curve, is in the prime order subgroup.
+///
+/// Same as `is_in_correct_subgroup_assuming_on_curve`. Following
+/// <https://eprint.iacr.org/2019/814.pdf>, checks `[x]P == ψ(P)`, with `x` the BLS
+/// parameter and `ψ` the untwist-Frobenius-twist endomorphism, which acts on the
+/// subgroup as the multiplication by `x`. No early return is needed on G2.
+pub fn is_torsion_free<H: CurveHooks>(p: &G2Affine<H>) -> bool {
+    p.is_in_correct_subgroup_assuming_on_curve()
+}
+
 /// psi(P) is the untwist-Frobenius-twist endomorhism on E'(Fq2)
 fn p_power_endomorphism<H: CurveHooks>(p: &G2Affine<H>) -> G2Affine<H> {
     // The p-power endomorphism for G2 is defined as follows:
@@ -257,3 +312,145 @@ fn double_p_power_endomorphism<H: CurveHooks>(p: &G2Projective<H>) -> G2Projecti
 
     res
 }
+
+/// The untwist-Frobenius-twist endomorphism `ψ` of `E'(Fq2)`.
+///
+/// Maps `(x, y)` to `(x^p / (u+1)^((p-1)/3), y^p / (u+1)^((p-1)/2))`. `ψ` has order
+/// 12 and acts on the prime order subgroup as the multiplication by the curve
+/// parameter `x`, which is what the subgroup check relies on. Never jumps into the
+/// hooks.
+pub fn psi<H: CurveHooks>(p: &G2Affine<H>) -> G2Affine<H> {
+    p_power_endomorphism(p)
+}
+
+/// `ψ(ψ(p))`, see `psi`, computed with a single multiplication.
+pub fn psi2<H: CurveHooks>(p: &G2Affine<H>) -> G2Affine<H> {
+    double_p_power_endomorphism(&(*p).into()).into_affine()
+}
+
+/// Check whether `p` satisfies the curve equation `y^2 = x^3 + b` over `Fq2`.
+///
+/// The point at infinity is on the curve. Says nothing about the subgroup
+/// membership, see `is_torsion_free`.
+pub fn is_on_curve<H: CurveHooks>(p: &G2Affine<H>) -> bool {
+    let b = <Config<H> as SWCurveConfig>::COEFF_B;
+    p.infinity || p.y.square() == p.x.square() * p.x + b
+}
+
+/// Check that every point satisfies the curve equation `y^2 = x^3 + b`.
+///
+/// Returns the index of the first point off the curve. The point at infinity is
+/// accepted, and the subgroup membership is not checked.
+pub fn validate_on_curve_g2<H: CurveHooks>(points: &[G2Affine<H>]) -> Result<(), usize> {
+    points
+        .iter()
+        .position(|p| !is_on_curve(p))
+        .map_or(Ok(()), Err)
+}
+
+/// Check that every point is in the prime order subgroup, jumping into the
+/// user-defined `g2_in_subgroup_batch` hook.
+///
+/// Points are assumed to be on the curve. Returns the index of the first point out
+/// of the subgroup. On any internal error falls back to the in-Rust per point check.
+pub fn validate_subgroup_g2_batch<H: CurveHooks>(points: &[G2Affine<H>]) -> Result<(), usize> {
+    util::trace_hook!("bls12_381_g2_in_subgroup_batch", points => H::G2_USAGE);
+    let res = util::strict_hook::<H, _>(
+        H::bls12_381_g2_in_subgroup_batch(points),
+        "bls12_381_g2_in_subgroup_batch",
+    )
+    .ok()
+    .filter(|res| res.len() == points.len())
+    .unwrap_or_else(|| subgroup_check_g2_batch(points));
+    res.iter()
+        .position(|in_subgroup| !in_subgroup)
+        .map_or(Ok(()), Err)
+}
+
+/// Multi scalar multiplication of untrusted bases.
+///
+/// Checks the lengths, then that every base is on the curve and, through
+/// `validate_subgroup_g2_batch`, in the prime order subgroup before jumping into
+/// the user-defined `msm_g2` hook. Otherwise same contract as `msm`.
+pub fn checked_msm<H: CurveHooks>(
+    bases: &[G2Affine<H>],
+    scalars: &[<Config<H> as CurveConfig>::ScalarField],
+) -> Result<G2Projective<H>, CheckedMsmError> {
+    if bases.len() != scalars.len() {
+        return Err(CheckedMsmError::LengthMismatch(
+            bases.len().min(scalars.len()),
+        ));
+    }
+    validate_on_curve_g2(bases).map_err(CheckedMsmError::InvalidBase)?;
+    validate_subgroup_g2_batch(bases).map_err(CheckedMsmError::InvalidBase)?;
+    Config::<H>::msm(bases, scalars).map_err(|_| CheckedMsmError::Hook)
+}
+
+/// In-Rust per point subgroup check.
+pub(crate) fn subgroup_check_g2_batch<H: CurveHooks>(points: &[G2Affine<H>]) -> Vec<bool> {
+    points
+        .iter()
+        .map(|p| p.is_in_correct_subgroup_assuming_on_curve())
+        .collect()
+}
+
+/// Negation of `p`.
+pub fn negate_g2<H: CurveHooks>(p: &G2Projective<H>) -> G2Projective<H> {
+    util::negate(p)
+}
+
+/// Constant time selection of `b` if `choice` is set, of `a` otherwise.
+///
+/// Runs in the same time for both choices, e.g. to select an opening point from
+/// a secret bit.
+#[cfg(feature = "ct")]
+pub fn conditional_select<H: CurveHooks>(
+    a: &G2Affine<H>,
+    b: &G2Affine<H>,
+    choice: subtle::Choice,
+) -> G2Affine<H> {
+    util::conditional_select_affine(a, b, choice, util::conditional_select_fq2)
+}
+
+/// Decode a G2 point from its compressed (96 bytes) or uncompressed (192 bytes)
+/// encoding, telling the two apart by the length of `bytes`.
+///
+/// The point is validated. Any other length is rejected.
+pub fn try_from_bytes<H: CurveHooks>(bytes: &[u8]) -> Result<G2Affine<H>, SerializationError> {
+    codec::point_from_bytes(bytes)
+}
+
+/// Decode a G2 point from the hex string of its compressed encoding.
+///
+/// The string may be `0x` prefixed. The point is validated and trailing bytes are rejected.
+pub fn from_hex<H: CurveHooks>(s: &str) -> Result<G2Affine<H>, ()> {
+    codec::point_from_hex(s)
+}
+
+/// Decode a G2 point from the standard padded base64 string of its compressed encoding.
+///
+/// The point is validated and trailing bytes are rejected.
+pub fn from_base64<H: CurveHooks>(s: &str) -> Result<G2Affine<H>, ()> {
+    codec::point_from_base64(s)
+}
+
+/// Encode a G2 point as the `0x` prefixed hex string of its compressed encoding.
+pub fn to_hex<H: CurveHooks>(point: &G2Affine<H>) -> String {
+    codec::point_to_hex(point)
+}
+
+/// Compressed encoding of a G2 point, without any allocation.
+pub fn to_compressed<H: CurveHooks>(point: &G2Affine<H>) -> [u8; G2_SERIALIZED_SIZE] {
+    let mut buf = [0u8; G2_SERIALIZED_SIZE];
+    // The buffer is exactly as large as the encoding.
+    let _ = Config::<H>::serialize_with_mode(point, &mut buf[..], Compress::Yes);
+    buf
+}
+
+/// Uncompressed encoding of a G2 point, without any allocation.
+pub fn to_uncompressed<H: CurveHooks>(point: &G2Affine<H>) -> [u8; 2 * G2_SERIALIZED_SIZE] {
+    let mut buf = [0u8; 2 * G2_SERIALIZED_SIZE];
+    // The buffer is exactly as large as the encoding.
+    let _ = Config::<H>::serialize_with_mode(point, &mut buf[..], Compress::No);
+    buf
+}
@@ -0,0 +1,153 @@
+//! Groth16 proofs over BLS12-381.
+
+use crate::{fr::Fr, g1, CurveHooks, G1Affine, G2Affine};
+
+use ark_models_ext::CurveGroup;
+
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::{
+    fmt,
+    io::{Read, Write},
+    vec::Vec,
+};
+
+/// Groth16 proof `(A, B, C)`.
+///
+/// Serialized as the three points one after the other, that is 192 bytes compressed
+/// (the layout used by `bellman`) or 384 bytes uncompressed.
+/// The three points are validated on deserialization (if requested).
+pub struct Groth16Proof<H: CurveHooks> {
+    pub a: G1Affine<H>,
+    pub b: G2Affine<H>,
+    pub c: G1Affine<H>,
+}
+
+impl<H: CurveHooks> Clone for Groth16Proof<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: CurveHooks> Copy for Groth16Proof<H> {}
+
+impl<H: CurveHooks> PartialEq for Groth16Proof<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b && self.c == other.c
+    }
+}
+
+impl<H: CurveHooks> Eq for Groth16Proof<H> {}
+
+impl<H: CurveHooks> fmt::Debug for Groth16Proof<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Groth16Proof")
+            .field("a", &self.a)
+            .field("b", &self.b)
+            .field("c", &self.c)
+            .finish()
+    }
+}
+
+impl<H: CurveHooks> CanonicalSerialize for Groth16Proof<H> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.a.serialize_with_mode(&mut writer, compress)?;
+        self.b.serialize_with_mode(&mut writer, compress)?;
+        self.c.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.a.serialized_size(compress)
+            + self.b.serialized_size(compress)
+            + self.c.serialized_size(compress)
+    }
+}
+
+impl<H: CurveHooks> Valid for Groth16Proof<H> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.a.check()?;
+        self.b.check()?;
+        self.c.check()
+    }
+}
+
+impl<H: CurveHooks> CanonicalDeserialize for Groth16Proof<H> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let a = G1Affine::<H>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let b = G2Affine::<H>::deserialize_with_mode(&mut reader, compress, validate)?;
+        let c = G1Affine::<H>::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self { a, b, c })
+    }
+}
+
+/// Groth16 verifying key.
+pub struct VerifyingKey<H: CurveHooks> {
+    pub alpha_g1: G1Affine<H>,
+    pub beta_g2: G2Affine<H>,
+    pub gamma_g2: G2Affine<H>,
+    pub delta_g2: G2Affine<H>,
+    /// Bases of the public inputs, the first one for the constant `1` input.
+    pub gamma_abc_g1: Vec<G1Affine<H>>,
+}
+
+impl<H: CurveHooks> Clone for VerifyingKey<H> {
+    fn clone(&self) -> Self {
+        Self {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            gamma_abc_g1: self.gamma_abc_g1.clone(),
+        }
+    }
+}
+
+impl<H: CurveHooks> fmt::Debug for VerifyingKey<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyingKey")
+            .field("alpha_g1", &self.alpha_g1)
+            .field("beta_g2", &self.beta_g2)
+            .field("gamma_g2", &self.gamma_g2)
+            .field("delta_g2", &self.delta_g2)
+            .field("gamma_abc_g1", &self.gamma_abc_g1)
+            .finish()
+    }
+}
+
+/// Verify `proof` for `public_inputs` against `vk`.
+///
+/// Checks `e(A, B) = e(α, β) · e(L, γ) · e(C, δ)`, with `L` the combination of the
+/// `gamma_abc_g1` bases with the inputs, as a single multi Miller loop followed by
+/// a final exponentiation. `L` is computed through the `msm_g1` hook.
+/// Returns `false` on wrong number of inputs or on any internal error, whatever the
+/// `HOOK_POLICY` of `H` but `OnError::Panic`.
+#[must_use]
+pub fn verify<H: CurveHooks>(
+    vk: &VerifyingKey<H>,
+    public_inputs: &[Fr],
+    proof: &Groth16Proof<H>,
+) -> bool {
+    let Some((base, bases)) = vk.gamma_abc_g1.split_first() else {
+        return false;
+    };
+    if bases.len() != public_inputs.len() {
+        return false;
+    }
+    let Ok(inputs) = g1::try_msm::<H>(bases, public_inputs) else {
+        return false;
+    };
+    let inputs = (inputs + base).into_affine();
+    crate::verify_pairing_eq(
+        &[proof.a, -vk.alpha_g1, -inputs, -proof.c],
+        &[proof.b, vk.beta_g2, vk.gamma_g2, vk.delta_g2],
+    )
+}
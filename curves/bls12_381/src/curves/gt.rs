@@ -0,0 +1,96 @@
+//! Target group (GT) compression and multi exponentiation.
+//!
+//! GT is a subgroup of the cyclotomic subgroup of `Fq12 = Fq6[w] / (w^2 - v)`, whose
+//! elements other than `±1` are represented on the algebraic torus `T2(Fq6)` by the
+//! single `Fq6` element `g = (1 + c0) / c1`. Decompression recovers the `Fq12` element
+//! as `(g + w) / (g - w)`.
+//!
+//! The identity is encoded as `g = 0`, which would otherwise decompress to `-1`
+//! (not a GT element).
+
+use crate::{
+    fr::Fr,
+    util::{expect_hook, trace_hook},
+    Bls12_381, CurveHooks,
+};
+
+use ark_bls12_381::{Fq12, Fq6};
+use ark_ff::{CyclotomicMultSubgroup, Field, One, PrimeField, Zero};
+use ark_models_ext::pairing::PairingOutput;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::vec::Vec;
+
+/// Size in bytes of a compressed GT element.
+pub const GT_COMPRESSED_SIZE: usize = 288;
+
+/// Compress a GT element to its torus representation.
+pub fn compress_gt<H: CurveHooks>(gt: &PairingOutput<Bls12_381<H>>) -> [u8; GT_COMPRESSED_SIZE] {
+    let f = gt.0;
+    let g = if f.is_one() {
+        Fq6::zero()
+    } else {
+        // `c1 = 0` only for `±1`, and `-1` is not in GT.
+        (Fq6::one() + f.c0) * f.c1.inverse().unwrap_or_default()
+    };
+    let mut buf = [0; GT_COMPRESSED_SIZE];
+    // Infallible: the buffer has exactly the `Fq6` serialized size.
+    let _ = g.serialize_uncompressed(&mut buf[..]);
+    buf
+}
+
+/// Decompress a GT element encoded via `compress_gt`.
+///
+/// Returns an error if `bytes` is not a canonical encoding or if the decoded
+/// element is not in GT.
+pub fn decompress_gt<H: CurveHooks>(bytes: &[u8]) -> Result<PairingOutput<Bls12_381<H>>, ()> {
+    if bytes.len() != GT_COMPRESSED_SIZE {
+        return Err(());
+    }
+    let g = Fq6::deserialize_uncompressed(bytes).map_err(|_| ())?;
+    if g.is_zero() {
+        return Ok(PairingOutput(Fq12::one()));
+    }
+    // `g - w` is never zero as `v` is not a square in `Fq6`.
+    let den = Fq12::new(g, -Fq6::one()).inverse().ok_or(())?;
+    let f = Fq12::new(g, Fq6::one()) * den;
+    // Being on the torus `f` is already in the cyclotomic subgroup, check the GT order.
+    if !f.pow(ark_bls12_381::Fr::MODULUS).is_one() {
+        return Err(());
+    }
+    Ok(PairingOutput(f))
+}
+
+/// Multi exponentiation in GT jumping into the user-defined `gt_msm` hook.
+///
+/// Computes `∏ elements[i]^scalars[i]`, i.e. the MSM of the additively written
+/// `PairingOutput`. Follows the `VariableBaseMSM` contract: on length mismatch
+/// returns the shorter length, on any internal error returns `Err(0)`.
+pub fn gt_msm<H: CurveHooks>(
+    elements: &[PairingOutput<Bls12_381<H>>],
+    scalars: &[Fr],
+) -> Result<PairingOutput<Bls12_381<H>>, usize> {
+    if elements.len() != scalars.len() {
+        return Err(elements.len().min(scalars.len()));
+    }
+    let elements: Vec<Fq12> = elements.iter().map(|e| e.0).collect();
+    trace_hook!(
+        "bls12_381_gt_msm",
+        &elements => crate::codec::DEFAULT_USAGE,
+        scalars => crate::codec::DEFAULT_USAGE,
+    );
+    expect_hook::<H, _>(H::bls12_381_gt_msm(&elements, scalars), "bls12_381_gt_msm")
+        .map(PairingOutput)
+        .map_err(|_| 0)
+}
+
+/// In-Rust GT multi exponentiation, using the cyclotomic exponentiation.
+pub(crate) fn gt_msm_default(elements: &[Fq12], scalars: &[Fr]) -> Result<Fq12, ()> {
+    if elements.len() != scalars.len() {
+        return Err(());
+    }
+    Ok(elements
+        .iter()
+        .zip(scalars)
+        .map(|(e, s)| e.cyclotomic_exp(s.into_bigint()))
+        .product())
+}
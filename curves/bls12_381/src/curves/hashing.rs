@@ -0,0 +1,91 @@
+//! Hashing to the curve groups.
+//!
+//! Implements the `BLS12381G1_XMD:SHA-256_SSWU_RO_` and `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+//! suites of [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html).
+
+use crate::{util, CurveHooks, G1Affine, G2Affine};
+
+use ark_bls12_381::{
+    g1::Config as ArkG1Config, g2::Config as ArkG2Config, G1Projective as ArkG1Projective,
+    G2Projective as ArkG2Projective,
+};
+use ark_ec::hashing::{
+    curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve,
+};
+use ark_ff::field_hashers::DefaultFieldHasher;
+use ark_models_ext::AffineRepr;
+use ark_std::{collections::BTreeSet, vec::Vec};
+use sha2::Sha256;
+
+/// Hash `msg` to G1 using the domain separation tag `dst`.
+///
+/// On any internal error returns `Err(())`.
+pub fn hash_to_g1<H: CurveHooks>(msg: &[u8], dst: &[u8]) -> Result<G1Affine<H>, ()> {
+    let hasher = MapToCurveBasedHasher::<
+        ArkG1Projective,
+        DefaultFieldHasher<Sha256, 128>,
+        WBMap<ArkG1Config>,
+    >::new(dst)
+    .map_err(|_| ())?;
+    let p = hasher.hash(msg).map_err(|_| ())?;
+    Ok(match p.xy() {
+        Some((x, y)) => G1Affine::<H>::new_unchecked(x, y),
+        None => G1Affine::<H>::zero(),
+    })
+}
+
+/// Hash `msg` to G2 using the domain separation tag `dst`.
+///
+/// Jumps into the user-defined `hash_to_g2` hook, which runs the whole suite,
+/// i.e. including the cofactor clearing. On any internal error returns `Err(())`,
+/// even with the `OnError::Default` policy: the point at infinity is no hash.
+pub fn hash_to_g2<H: CurveHooks>(msg: &[u8], dst: &[u8]) -> Result<G2Affine<H>, ()> {
+    util::trace_hook!(
+        "bls12_381_hash_to_g2",
+        msg => crate::codec::DEFAULT_USAGE,
+        dst => crate::codec::DEFAULT_USAGE,
+    );
+    util::strict_hook::<H, _>(H::bls12_381_hash_to_g2(msg, dst), "bls12_381_hash_to_g2")
+}
+
+/// In-Rust hash to G2.
+pub(crate) fn hash_to_g2_default<H: CurveHooks>(msg: &[u8], dst: &[u8]) -> Result<G2Affine<H>, ()> {
+    let hasher = MapToCurveBasedHasher::<
+        ArkG2Projective,
+        DefaultFieldHasher<Sha256, 128>,
+        WBMap<ArkG2Config>,
+    >::new(dst)
+    .map_err(|_| ())?;
+    let p = hasher.hash(msg).map_err(|_| ())?;
+    Ok(match p.xy() {
+        Some((x, y)) => G2Affine::<H>::new_unchecked(x, y),
+        None => G2Affine::<H>::zero(),
+    })
+}
+
+/// Derive `count` independent G1 generators, e.g. for Pedersen commitments.
+///
+/// The `i`-th candidate is the hash to G1 of the big-endian `u64` index `i` using
+/// the domain separation tag `dst`, so that nobody knows the discrete logarithms
+/// between the generators. Candidates which are the identity or which repeat a
+/// previous generator are skipped, thus the returned generators are distinct and,
+/// as any hash to G1 output, in the prime order subgroup. Repeats are looked up in
+/// a sorted set of the affine coordinates, i.e. in `O(log count)` per candidate.
+///
+/// On any internal error returns `Err(())`.
+pub fn pedersen_generators_g1<H: CurveHooks>(
+    count: usize,
+    dst: &[u8],
+) -> Result<Vec<G1Affine<H>>, ()> {
+    let mut generators = Vec::with_capacity(count);
+    let mut seen = BTreeSet::new();
+    let mut index = 0u64;
+    while generators.len() < count {
+        let p = hash_to_g1::<H>(&index.to_be_bytes(), dst)?;
+        if !p.is_zero() && seen.insert((p.x, p.y)) {
+            generators.push(p);
+        }
+        index += 1;
+    }
+    Ok(generators)
+}
@@ -0,0 +1,82 @@
+//! Deterministic BLS key generation.
+//!
+//! Implements the `KeyGen` procedure of the
+//! [BLS signature draft](https://datatracker.ietf.org/doc/draft-irtf-cfrg-bls-signature/),
+//! which is also the `HKDF_mod_r` of [EIP-2333](https://eips.ethereum.org/EIPS/eip-2333).
+//! HKDF ([RFC 5869](https://www.rfc-editor.org/rfc/rfc5869)) is instantiated with SHA-256.
+
+use crate::{fr::Fr, g1, CurveHooks, G1Affine};
+
+use ark_ff::{PrimeField, Zero};
+use ark_models_ext::{short_weierstrass::SWCurveConfig, AffineRepr};
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Initial HKDF salt, hashed before every attempt.
+const SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+
+/// Length of the HKDF output, i.e. `ceil((3 * ceil(log2(r))) / 16)`.
+const L: usize = 48;
+
+/// SHA-256 block size.
+const BLOCK_SIZE: usize = 64;
+
+/// Derive a secret key from the input keying material `ikm`.
+///
+/// The draft requires `ikm` to be at least 32 bytes of secret randomness, which is
+/// not checked here.
+/// `key_info` binds the key to an application and may be empty.
+pub fn derive_secret_key(ikm: &[u8], key_info: &[u8]) -> Fr {
+    let mut salt: [u8; 32] = Sha256::digest(SALT).into();
+    let mut ikm = ikm.to_vec();
+    ikm.push(0);
+    let mut info = key_info.to_vec();
+    info.extend_from_slice(&(L as u16).to_be_bytes());
+    loop {
+        let prk = hmac(&salt, &ikm);
+        let okm = hkdf_expand(&prk, &info);
+        let sk = Fr::from_be_bytes_mod_order(&okm);
+        if !sk.is_zero() {
+            return sk;
+        }
+        salt = Sha256::digest(salt).into();
+    }
+}
+
+/// Public key of the secret key `sk`, i.e. `[sk]G` with `G` the G1 generator.
+///
+/// The multiplication jumps into the user-defined `mul_projective_g1` hook.
+pub fn sk_to_pk<H: CurveHooks>(sk: &Fr) -> G1Affine<H> {
+    g1::Config::<H>::mul_affine(&G1Affine::<H>::generator(), sk.into_bigint().as_ref()).into()
+}
+
+/// HKDF-Expand to `L` bytes.
+fn hkdf_expand(prk: &[u8; 32], info: &[u8]) -> [u8; L] {
+    let mut okm = [0u8; L];
+    let mut t = Vec::new();
+    for (i, chunk) in okm.chunks_mut(32).enumerate() {
+        let mut msg = t;
+        msg.extend_from_slice(info);
+        msg.push(i as u8 + 1);
+        let block = hmac(prk, &msg);
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        t = block.to_vec();
+    }
+    okm
+}
+
+/// HMAC-SHA-256 of `msg` with `key`, which is at most one block long here.
+fn hmac(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    block[..key.len()].copy_from_slice(key);
+    let pad = |b: u8| block.iter().map(move |k| k ^ b).collect::<Vec<u8>>();
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(msg)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
@@ -0,0 +1,61 @@
+//! KZG polynomial commitment openings over BLS12-381.
+
+use crate::{fr::Fr, g1, g2, CurveHooks, G1Affine, G1Projective, G2Affine, G2Projective};
+
+use ark_ff::PrimeField;
+use ark_models_ext::CurveGroup;
+use ark_std::fmt;
+
+/// KZG verifying key, i.e. the generators and `[τ]G2` from the trusted setup.
+pub struct KzgVerifyingKey<H: CurveHooks> {
+    /// G1 generator the commitments are computed with.
+    pub g1: G1Affine<H>,
+    /// G2 generator.
+    pub g2: G2Affine<H>,
+    /// `[τ]G2`, with `τ` the secret of the setup.
+    pub tau_g2: G2Affine<H>,
+}
+
+impl<H: CurveHooks> Clone for KzgVerifyingKey<H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H: CurveHooks> Copy for KzgVerifyingKey<H> {}
+
+impl<H: CurveHooks> fmt::Debug for KzgVerifyingKey<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KzgVerifyingKey")
+            .field("g1", &self.g1)
+            .field("g2", &self.g2)
+            .field("tau_g2", &self.tau_g2)
+            .finish()
+    }
+}
+
+/// Verify that the polynomial committed to by `commitment` evaluates to `v` at `z`.
+///
+/// Checks `e(C - [v]G1, G2) = e(π, [τ]G2 - [z]G2)` as a single multi Miller loop
+/// followed by a final exponentiation. `[v]G1` and `[z]G2` are computed through the
+/// `mul_projective_g1` and `mul_projective_g2` hooks, a failure of which would turn
+/// the check into an opening at `z = 0`, `v = 0`.
+/// Returns `false` on any internal error.
+#[must_use]
+pub fn verify_opening<H: CurveHooks>(
+    commitment: G1Affine<H>,
+    proof: G1Affine<H>,
+    z: Fr,
+    v: Fr,
+    vk: &KzgVerifyingKey<H>,
+) -> bool {
+    let Ok(v_g1) = g1::try_mul_projective::<H>(&vk.g1.into(), v.into_bigint().as_ref()) else {
+        return false;
+    };
+    let Ok(z_g2) = g2::try_mul_projective::<H>(&vk.g2.into(), z.into_bigint().as_ref()) else {
+        return false;
+    };
+    let lhs = (G1Projective::<H>::from(commitment) - v_g1).into_affine();
+    let rhs = (G2Projective::<H>::from(vk.tau_g2) - z_g2).into_affine();
+    crate::verify_pairing_eq(&[lhs, -proof], &[vk.g2, rhs])
+}
@@ -1,60 +1,545 @@
-use ark_bls12_381::Config as ArkConfig;
+use ark_bls12_381::{Config as ArkConfig, Fq, Fq12, Fr};
 use ark_ec::bls12::Bls12Config as ArkBls12Config;
+use ark_ff::PrimeField;
 use ark_models_ext::{
     bls12::{Bls12, Bls12Config, G1Prepared, G2Prepared, TwistType},
     pairing::{MillerLoopOutput, Pairing, PairingOutput},
-    CurveConfig,
+    AffineRepr, CurveConfig, CurveGroup,
 };
-use ark_std::marker::PhantomData;
+use ark_std::{marker::PhantomData, rand::Rng, vec::Vec, One, UniformRand, Zero};
+use util::expect_hook;
 
+pub mod codec;
+pub mod cost;
+pub mod field;
 pub mod g1;
 pub mod g2;
-pub(crate) mod util;
+pub mod groth16;
+pub mod gt;
+pub mod hashing;
+pub mod keygen;
+pub mod kzg;
+pub mod msm;
+#[cfg(feature = "native-fallback")]
+mod native;
+pub mod prepared;
+pub mod scalar;
+#[cfg(feature = "zeroize")]
+pub mod secret;
+pub mod signatures;
+pub mod util;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
+    field::{base_field_modulus, fr_from_str, fr_from_u64, scalar_field_modulus},
     g1::{G1Affine, G1Projective},
-    g2::{G2Affine, G2Projective},
+    g2::{g2_cofactor, G2Affine, G2Projective},
+    prepared::{pairing_with_prepared, prepare_g2_lines, CachedG2Prepared},
 };
 
 /// Hooks for *BLS12-381* curve.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the BLS12-381 `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README",
+    note = "or enable the `native-fallback` feature, which defaults every hook to the in-Rust implementation"
+)]
 pub trait CurveHooks: 'static + Sized {
+    /// Default codec policy of the `codec` helpers for G1, e.g. `encode_projective_g1`.
+    ///
+    /// Exposed as `g1::Config::<Self>::USAGE`, also used for the sizes reported by the
+    /// `trace` feature. The hooks take typed values, thus this doesn't change how any
+    /// hook is invoked: only a hook implementation encoding its inputs through the
+    /// helpers follows it.
+    const G1_USAGE: codec::Usage = codec::DEFAULT_USAGE;
+
+    /// Default codec policy of the `codec` helpers for G2, e.g. `encode_projective_g2`.
+    ///
+    /// Exposed as `g2::Config::<Self>::USAGE`, see `G1_USAGE`.
+    const G2_USAGE: codec::Usage = codec::DEFAULT_USAGE;
+
+    /// Behavior of the hooked operations on hook failure.
+    ///
+    /// Defaults to `OnError::Propagate`, i.e. the error values documented by every
+    /// operation.
+    const HOOK_POLICY: util::OnError = util::OnError::Propagate;
+
+    /// Window size (in bits) of the in-Rust MSM defaults of the `native-fallback` feature.
+    ///
+    /// Defaults to `None`, i.e. the upstream MSM with its size dependent heuristic
+    /// (see `ark_models_ext::pippenger::msm_window_bits`). Set it to tune the bucket
+    /// method for a known workload, up to `ark_models_ext::pippenger::MAX_WINDOW_BITS`.
+    /// Hooks forwarding to the host ignore it.
+    const MSM_WINDOW_BITS: Option<usize> = None;
+
     /// Pairing multi Miller loop.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_multi_miller_loop(
         g1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
         g2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
     ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()>;
 
+    /// Pairing multi Miller loop.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()> {
+        native::multi_miller_loop::<Self>(g1, g2)
+    }
+
+    /// Two independent pairing multi Miller loops.
+    ///
+    /// Defaults to two `bls12_381_multi_miller_loop` calls.
+    fn bls12_381_multi_miller_loop_2(
+        a1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        b1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+        a2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        b2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<(Fq12, Fq12), ()> {
+        let res1 = Self::bls12_381_multi_miller_loop(a1, b1)?;
+        let res2 = Self::bls12_381_multi_miller_loop(a2, b2)?;
+        Ok((res1, res2))
+    }
+
+    /// Pairing multi Miller loop over pairs with the G1 point negated if the flag is set.
+    ///
+    /// Lets the host negate the G1 points of checks like `e(A, B) · e(-C, D) == 1`.
+    /// Defaults to the in-Rust negation followed by `bls12_381_multi_miller_loop`.
+    fn bls12_381_multi_miller_loop_signed(
+        pairs: &[(G1Affine<Self>, G2Affine<Self>, bool)],
+    ) -> Result<Fq12, ()> {
+        let g1 = pairs
+            .iter()
+            .map(|(p, _, neg)| G1Prepared::from(if *neg { -*p } else { *p }));
+        let g2 = pairs.iter().map(|(_, q, _)| G2Prepared::from(*q));
+        Self::bls12_381_multi_miller_loop(g1, g2)
+    }
+
+    /// Pairing multi Miller loop over G2 points with cached line coefficients.
+    ///
+    /// Defaults to the in-Rust Miller loop over the given coefficients.
+    fn bls12_381_multi_miller_loop_prepared(
+        g1: &[G1Affine<Self>],
+        g2: &[CachedG2Prepared<Self>],
+    ) -> Result<Fq12, ()> {
+        prepared::multi_miller_loop_prepared(g1, g2)
+    }
+
+    /// Miller loop line coefficients of G2 points.
+    ///
+    /// Returns one preparation per point, in order, to be evaluated against varying G1
+    /// points by `bls12_381_multi_miller_loop_prepared`. Host computed coefficients are
+    /// wrapped with `CachedG2Prepared::from_lines`. Defaults to the in-Rust preparation.
+    fn bls12_381_miller_lines(g2: &[G2Affine<Self>]) -> Result<Vec<CachedG2Prepared<Self>>, ()> {
+        Ok(g2.iter().map(CachedG2Prepared::new).collect())
+    }
+
     /// Pairing final exponentiation.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_final_exponentiation(
         target: <Bls12_381<Self> as Pairing>::TargetField,
     ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()>;
 
+    /// Pairing final exponentiation.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_final_exponentiation(
+        target: <Bls12_381<Self> as Pairing>::TargetField,
+    ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()> {
+        native::final_exponentiation(target)
+    }
+
+    /// Pairing final exponentiation of the product of many Miller loop outputs.
+    ///
+    /// Defaults to the in-Rust product followed by `bls12_381_final_exponentiation`.
+    fn bls12_381_final_exponentiation_batch(targets: &[Fq12]) -> Result<Fq12, ()> {
+        Self::bls12_381_final_exponentiation(targets.iter().product())
+    }
+
+    /// Multi exponentiation in the target group, i.e. `∏ elements[i]^scalars[i]`.
+    ///
+    /// Invoked with elements and scalars of the same length.
+    /// Defaults to the in-Rust product of cyclotomic exponentiations.
+    fn bls12_381_gt_msm(elements: &[Fq12], scalars: &[Fr]) -> Result<Fq12, ()> {
+        gt::gt_msm_default(elements, scalars)
+    }
+
+    /// Batch inversion of scalars.
+    ///
+    /// Returns one inverse per scalar, zero for a zero scalar.
+    /// Defaults to the in-Rust batch inversion (Montgomery's trick).
+    fn bls12_381_fr_batch_inverse(scalars: &[Fr]) -> Result<Vec<Fr>, ()> {
+        Ok(field::fr_batch_inverse_default(scalars))
+    }
+
+    /// Square root of a base field element.
+    ///
+    /// Returns `None` for a non-residue. The root must be `x^((q+1)/4)`, the one
+    /// computed by the in-Rust default, which is used as the canonical root.
+    fn bls12_381_fq_sqrt(x: &Fq) -> Result<Option<Fq>, ()> {
+        Ok(field::fq_sqrt_default(x))
+    }
+
+    /// Frobenius map `x^(q^power)` on the target field.
+    ///
+    /// Defaults to the in-Rust `Field::frobenius_map`.
+    fn bls12_381_fq12_frobenius(x: &Fq12, power: usize) -> Result<Fq12, ()> {
+        Ok(field::fq12_frobenius_default(x, power))
+    }
+
+    /// Hash to G2 following the `BLS12381G2_XMD:SHA-256_SSWU_RO_` suite of RFC 9380.
+    ///
+    /// Lets a host with a native hash to curve run the whole suite, cofactor
+    /// clearing included. Defaults to the in-Rust implementation.
+    fn bls12_381_hash_to_g2(msg: &[u8], dst: &[u8]) -> Result<G2Affine<Self>, ()> {
+        hashing::hash_to_g2_default(msg, dst)
+    }
+
     /// Multi scalar multiplication on G1.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_msm_g1(
         bases: &[g1::G1Affine<Self>],
         scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
     ) -> Result<G1Projective<Self>, ()>;
 
+    /// Multi scalar multiplication on G1.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_msm_g1(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, ()> {
+        native::msm_g1(bases, scalars)
+    }
+
+    /// Multi scalar multiplication on G1 returning an affine point.
+    ///
+    /// Allows the host to fuse the final inversion into the MSM.
+    /// Defaults to `bls12_381_msm_g1` followed by the in-Rust affine conversion.
+    fn bls12_381_msm_g1_affine(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Affine<Self>, ()> {
+        Self::bls12_381_msm_g1(bases, scalars).map(|res| res.into_affine())
+    }
+
+    /// Multi scalar multiplication on G1 encoding through a caller provided buffer.
+    ///
+    /// Lets a host forwarding implementation encode `bases` and `scalars` into
+    /// `scratch` instead of allocating on every call.
+    /// Defaults to `bls12_381_msm_g1`, ignoring `scratch`.
+    fn bls12_381_msm_g1_with_scratch(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+        _scratch: &mut codec::SerializeScratch,
+    ) -> Result<G1Projective<Self>, ()> {
+        Self::bls12_381_msm_g1(bases, scalars)
+    }
+
+    /// Subgroup membership check of many G1 points, assumed to be on the curve.
+    ///
+    /// Returns one flag per point. Defaults to the in-Rust per point check.
+    fn bls12_381_g1_in_subgroup_batch(points: &[G1Affine<Self>]) -> Result<Vec<bool>, ()> {
+        Ok(g1::subgroup_check_g1_batch(points))
+    }
+
     /// Multi scalar multiplication on G2.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_msm_g2(
         bases: &[g2::G2Affine<Self>],
         scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
     ) -> Result<G2Projective<Self>, ()>;
 
+    /// Multi scalar multiplication on G2.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_msm_g2(
+        bases: &[g2::G2Affine<Self>],
+        scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective<Self>, ()> {
+        native::msm_g2(bases, scalars)
+    }
+
+    /// Subgroup membership check of many G2 points, assumed to be on the curve.
+    ///
+    /// Returns one flag per point. Defaults to the in-Rust per point check.
+    fn bls12_381_g2_in_subgroup_batch(points: &[G2Affine<Self>]) -> Result<Vec<bool>, ()> {
+        Ok(g2::subgroup_check_g2_batch(points))
+    }
+
     /// Projective multiplication on G1.
+    ///
+    /// `scalar` holds little-endian `u64` limbs, i.e. the least significant limb first
+    /// as in the `BigInt` representation. Use `scalar::Scalar` to build it.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_mul_projective_g1(
         base: &G1Projective<Self>,
         scalar: &[u64],
     ) -> Result<G1Projective<Self>, ()>;
 
+    /// Projective multiplication on G1.
+    ///
+    /// `scalar` holds little-endian `u64` limbs, i.e. the least significant limb first
+    /// as in the `BigInt` representation. Use `scalar::Scalar` to build it.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_mul_projective_g1(
+        base: &G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G1Projective<Self>, ()> {
+        native::mul_projective_g1(base, scalar)
+    }
+
+    /// Projective multiplication on G1 encoding through a caller provided buffer.
+    ///
+    /// Defaults to `bls12_381_mul_projective_g1`, ignoring `scratch`.
+    fn bls12_381_mul_projective_g1_with_scratch(
+        base: &G1Projective<Self>,
+        scalar: &[u64],
+        _scratch: &mut codec::SerializeScratch,
+    ) -> Result<G1Projective<Self>, ()> {
+        Self::bls12_381_mul_projective_g1(base, scalar)
+    }
+
     /// Projective multiplication on G2.
+    ///
+    /// `scalar` holds little-endian `u64` limbs, i.e. the least significant limb first
+    /// as in the `BigInt` representation. Use `scalar::Scalar` to build it.
+    #[cfg(not(feature = "native-fallback"))]
     fn bls12_381_mul_projective_g2(
         base: &G2Projective<Self>,
         scalar: &[u64],
     ) -> Result<G2Projective<Self>, ()>;
+
+    /// Projective multiplication on G2.
+    ///
+    /// `scalar` holds little-endian `u64` limbs, i.e. the least significant limb first
+    /// as in the `BigInt` representation. Use `scalar::Scalar` to build it.
+    ///
+    /// Defaults to the in-Rust upstream implementation.
+    #[cfg(feature = "native-fallback")]
+    fn bls12_381_mul_projective_g2(
+        base: &G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G2Projective<Self>, ()> {
+        native::mul_projective_g2(base, scalar)
+    }
+
+    /// Fixed-base multi scalar multiplication table preparation on G1.
+    ///
+    /// Returns an opaque table which is later given back to `bls12_381_fixed_base_msm_g1`.
+    /// Defaults to the in-Rust precomputation.
+    fn bls12_381_fixed_base_msm_g1_table(bases: &[g1::G1Affine<Self>]) -> Result<Vec<u8>, ()> {
+        msm::fixed_base_msm_g1_table(bases)
+    }
+
+    /// Fixed-base multi scalar multiplication on G1.
+    ///
+    /// Defaults to the in-Rust evaluation of a table built by the default
+    /// `bls12_381_fixed_base_msm_g1_table`.
+    fn bls12_381_fixed_base_msm_g1(
+        table: &[u8],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, ()> {
+        msm::fixed_base_msm_g1(table, scalars)
+    }
+
+    /// Batch conversion to affine on G1.
+    ///
+    /// Defaults to the in-Rust batch inversion (Montgomery's trick).
+    fn bls12_381_normalize_batch_g1(
+        points: &[G1Projective<Self>],
+    ) -> Result<Vec<G1Affine<Self>>, ()> {
+        Ok(G1Projective::<Self>::normalize_batch(points))
+    }
+
+    /// Batch conversion to affine on G1 of a whole vector of points.
+    ///
+    /// Same as `bls12_381_normalize_batch_g1`, meant to hand over the results of many
+    /// MSMs at once. Defaults to the in-Rust batch inversion (Montgomery's trick).
+    fn bls12_381_batch_to_affine_g1(
+        points: &[G1Projective<Self>],
+    ) -> Result<Vec<G1Affine<Self>>, ()> {
+        Ok(G1Projective::<Self>::normalize_batch(points))
+    }
+
+    /// Sum of G1 points, e.g. to aggregate public keys.
+    ///
+    /// Defaults to the in-Rust mixed additions, one point after the other.
+    fn bls12_381_sum_g1(points: &[G1Affine<Self>]) -> Result<G1Projective<Self>, ()> {
+        Ok(g1::sum_g1_default(points))
+    }
+}
+
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
+
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn bls12_381_multi_miller_loop(
+        _g1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        _g2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bls12_381_multi_miller_loop_2(
+        _a1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        _b1: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+        _a2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G1Prepared>,
+        _b2: impl Iterator<Item = <Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<(Fq12, Fq12), ()> {
+        Err(())
+    }
+
+    fn bls12_381_multi_miller_loop_signed(
+        _pairs: &[(G1Affine<Self>, G2Affine<Self>, bool)],
+    ) -> Result<Fq12, ()> {
+        Err(())
+    }
+
+    fn bls12_381_multi_miller_loop_prepared(
+        _g1: &[G1Affine<Self>],
+        _g2: &[CachedG2Prepared<Self>],
+    ) -> Result<Fq12, ()> {
+        Err(())
+    }
+
+    fn bls12_381_miller_lines(_g2: &[G2Affine<Self>]) -> Result<Vec<CachedG2Prepared<Self>>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_final_exponentiation(
+        _target: <Bls12_381<Self> as Pairing>::TargetField,
+    ) -> Result<<Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bls12_381_final_exponentiation_batch(_targets: &[Fq12]) -> Result<Fq12, ()> {
+        Err(())
+    }
+
+    fn bls12_381_gt_msm(_elements: &[Fq12], _scalars: &[Fr]) -> Result<Fq12, ()> {
+        Err(())
+    }
+
+    fn bls12_381_fr_batch_inverse(_scalars: &[Fr]) -> Result<Vec<Fr>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_fq_sqrt(_x: &Fq) -> Result<Option<Fq>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_fq12_frobenius(_x: &Fq12, _power: usize) -> Result<Fq12, ()> {
+        Err(())
+    }
+
+    fn bls12_381_hash_to_g2(_msg: &[u8], _dst: &[u8]) -> Result<G2Affine<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_msm_g1(
+        _bases: &[g1::G1Affine<Self>],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_msm_g1_affine(
+        _bases: &[g1::G1Affine<Self>],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Affine<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_msm_g1_with_scratch(
+        _bases: &[g1::G1Affine<Self>],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+        _scratch: &mut codec::SerializeScratch,
+    ) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_g1_in_subgroup_batch(_points: &[G1Affine<Self>]) -> Result<Vec<bool>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_msm_g2(
+        _bases: &[g2::G2Affine<Self>],
+        _scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_g2_in_subgroup_batch(_points: &[G2Affine<Self>]) -> Result<Vec<bool>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_mul_projective_g1(
+        _base: &G1Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_mul_projective_g1_with_scratch(
+        _base: &G1Projective<Self>,
+        _scalar: &[u64],
+        _scratch: &mut codec::SerializeScratch,
+    ) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_mul_projective_g2(
+        _base: &G2Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<G2Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_fixed_base_msm_g1_table(_bases: &[g1::G1Affine<Self>]) -> Result<Vec<u8>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_fixed_base_msm_g1(
+        _table: &[u8],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_normalize_batch_g1(
+        _points: &[G1Projective<Self>],
+    ) -> Result<Vec<G1Affine<Self>>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_batch_to_affine_g1(
+        _points: &[G1Projective<Self>],
+    ) -> Result<Vec<G1Affine<Self>>, ()> {
+        Err(())
+    }
+
+    fn bls12_381_sum_g1(_points: &[G1Affine<Self>]) -> Result<G1Projective<Self>, ()> {
+        Err(())
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -62,6 +547,29 @@ pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
 
 pub type Bls12_381<H> = Bls12<Config<H>>;
 
+impl<H: CurveHooks> Config<H> {
+    /// Final exponentiation of the product of `targets` jumping into the user-defined
+    /// `final_exponentiation_batch` hook.
+    ///
+    /// Equals the product of the individual final exponentiations, with a single hook call.
+    /// For any internal error returns `None`.
+    #[must_use]
+    pub fn final_exponentiation_batch(
+        targets: &[MillerLoopOutput<Bls12_381<H>>],
+    ) -> Option<PairingOutput<Bls12_381<H>>> {
+        let targets: Vec<Fq12> = targets.iter().map(|target| target.0).collect();
+        util::trace_hook!(
+            "bls12_381_final_exponentiation_batch",
+            &targets => codec::DEFAULT_USAGE,
+        );
+        let res = expect_hook::<H, _>(
+            H::bls12_381_final_exponentiation_batch(&targets),
+            "bls12_381_final_exponentiation_batch",
+        );
+        res.map(PairingOutput).ok()
+    }
+}
+
 impl<H: CurveHooks> Bls12Config for Config<H> {
     const X: &'static [u64] = <ArkConfig as ArkBls12Config>::X;
     const X_IS_NEGATIVE: bool = <ArkConfig as ArkBls12Config>::X_IS_NEGATIVE;
@@ -77,16 +585,14 @@ impl<H: CurveHooks> Bls12Config for Config<H> {
 
     /// Multi Miller loop jumping into the user-defined `multi_miller_loop` hook.
     ///
-    /// For any internal error returns `TargetField::zero()`.
+    /// Pairs with a point at infinity contribute the identity, thus they are dropped
+    /// before reaching the hook. For any internal error returns `TargetField::zero()`.
     #[inline(always)]
     fn multi_miller_loop(
         g1: impl IntoIterator<Item = impl Into<G1Prepared<Self>>>,
         g2: impl IntoIterator<Item = impl Into<G2Prepared<Self>>>,
     ) -> MillerLoopOutput<Bls12<Self>> {
-        let g1 = g1.into_iter().map(|item| item.into());
-        let g2 = g2.into_iter().map(|item| item.into());
-        let res = H::bls12_381_multi_miller_loop(g1, g2);
-        MillerLoopOutput(res.unwrap_or_default())
+        MillerLoopOutput(try_multi_miller_loop::<H>(g1, g2).unwrap_or_default())
     }
 
     /// Final exponentiation jumping into the user-defined `final_exponentiation` hook.
@@ -96,7 +602,211 @@ impl<H: CurveHooks> Bls12Config for Config<H> {
     fn final_exponentiation(
         target: MillerLoopOutput<Bls12<Self>>,
     ) -> Option<PairingOutput<Bls12<Self>>> {
-        let res = H::bls12_381_final_exponentiation(target.0);
+        util::trace_hook!(
+            "bls12_381_final_exponentiation",
+            &target.0 => codec::DEFAULT_USAGE,
+        );
+        let res = expect_hook::<H, _>(
+            H::bls12_381_final_exponentiation(target.0),
+            "bls12_381_final_exponentiation",
+        );
         res.map(PairingOutput).ok()
     }
 }
+
+/// Multi Miller loop jumping into the user-defined `multi_miller_loop` hook.
+///
+/// Same as `Bls12_381::multi_miller_loop`, but a failure is returned as `Err(())`
+/// instead of `TargetField::zero()`, even with the `OnError::Default` policy.
+pub(crate) fn try_multi_miller_loop<H: CurveHooks>(
+    g1: impl IntoIterator<Item = impl Into<G1Prepared<Config<H>>>>,
+    g2: impl IntoIterator<Item = impl Into<G2Prepared<Config<H>>>>,
+) -> Result<Fq12, ()> {
+    let (g1, g2): (Vec<_>, Vec<_>) = g1
+        .into_iter()
+        .map(Into::<G1Prepared<Config<H>>>::into)
+        .zip(g2.into_iter().map(Into::<G2Prepared<Config<H>>>::into))
+        .filter(|(p, q)| !p.0.infinity && !q.0.infinity)
+        .unzip();
+    util::trace_hook!(
+        "bls12_381_multi_miller_loop",
+        &g1 => H::G1_USAGE,
+        &g2 => H::G2_USAGE,
+    );
+    util::strict_hook::<H, _>(
+        H::bls12_381_multi_miller_loop(g1.into_iter(), g2.into_iter()),
+        "bls12_381_multi_miller_loop",
+    )
+}
+
+/// Returns `true` if `∏ e(g1[i], g2[i]) == 1`, through the `multi_miller_loop` and
+/// `final_exponentiation` hooks.
+///
+/// A failure of either hook fails the check, even with the `OnError::Default`
+/// policy, whose zero Miller loop or final exponentiation may be taken for a result.
+pub(crate) fn check_pairing_product<H: CurveHooks>(
+    g1: impl IntoIterator<Item = impl Into<G1Prepared<Config<H>>>>,
+    g2: impl IntoIterator<Item = impl Into<G2Prepared<Config<H>>>>,
+) -> bool {
+    let Ok(target) = try_multi_miller_loop::<H>(g1, g2) else {
+        return false;
+    };
+    util::trace_hook!("bls12_381_final_exponentiation", &target => codec::DEFAULT_USAGE);
+    util::strict_hook::<H, _>(
+        H::bls12_381_final_exponentiation(target),
+        "bls12_381_final_exponentiation",
+    )
+    .is_ok_and(|out| out.is_one())
+}
+
+/// Two independent multi Miller loops jumping into the user-defined `multi_miller_loop_2` hook.
+///
+/// Allows to evaluate two distinct pairing products with a single hook call.
+/// For any internal error returns `TargetField::zero()` for both.
+pub fn multi_miller_loop_2<H: CurveHooks>(
+    a1: impl IntoIterator<Item = impl Into<G1Prepared<Config<H>>>>,
+    b1: impl IntoIterator<Item = impl Into<G2Prepared<Config<H>>>>,
+    a2: impl IntoIterator<Item = impl Into<G1Prepared<Config<H>>>>,
+    b2: impl IntoIterator<Item = impl Into<G2Prepared<Config<H>>>>,
+) -> (
+    MillerLoopOutput<Bls12_381<H>>,
+    MillerLoopOutput<Bls12_381<H>>,
+) {
+    let a1: Vec<G1Prepared<Config<H>>> = a1.into_iter().map(|item| item.into()).collect();
+    let b1: Vec<G2Prepared<Config<H>>> = b1.into_iter().map(|item| item.into()).collect();
+    let a2: Vec<G1Prepared<Config<H>>> = a2.into_iter().map(|item| item.into()).collect();
+    let b2: Vec<G2Prepared<Config<H>>> = b2.into_iter().map(|item| item.into()).collect();
+    util::trace_hook!(
+        "bls12_381_multi_miller_loop_2",
+        &a1 => H::G1_USAGE,
+        &b1 => H::G2_USAGE,
+        &a2 => H::G1_USAGE,
+        &b2 => H::G2_USAGE,
+    );
+    let (res1, res2) = expect_hook::<H, _>(
+        H::bls12_381_multi_miller_loop_2(
+            a1.into_iter(),
+            b1.into_iter(),
+            a2.into_iter(),
+            b2.into_iter(),
+        ),
+        "bls12_381_multi_miller_loop_2",
+    )
+    .unwrap_or_default();
+    (MillerLoopOutput(res1), MillerLoopOutput(res2))
+}
+
+/// Multi Miller loop over signed pairs jumping into the user-defined
+/// `multi_miller_loop_signed` hook.
+///
+/// The G1 point of a pair is negated if its flag is set. Pairs with a point at
+/// infinity are dropped before the hook call, as they don't contribute.
+/// For any internal error returns `TargetField::zero()`.
+pub fn multi_miller_loop_signed<H: CurveHooks>(
+    pairs: &[(G1Affine<H>, G2Affine<H>, bool)],
+) -> MillerLoopOutput<Bls12_381<H>> {
+    let pairs: Vec<_> = pairs
+        .iter()
+        .filter(|(p, q, _)| !p.infinity && !q.infinity)
+        .copied()
+        .collect();
+    util::trace_hook!(
+        "bls12_381_multi_miller_loop_signed",
+        &pairs.iter().map(|(p, _, _)| *p).collect::<Vec<_>>() => H::G1_USAGE,
+        &pairs.iter().map(|(_, q, _)| *q).collect::<Vec<_>>() => H::G2_USAGE,
+        &pairs.iter().map(|(_, _, neg)| *neg).collect::<Vec<_>>() => codec::DEFAULT_USAGE,
+    );
+    let res = expect_hook::<H, _>(
+        H::bls12_381_multi_miller_loop_signed(&pairs),
+        "bls12_381_multi_miller_loop_signed",
+    );
+    MillerLoopOutput(res.unwrap_or_default())
+}
+
+/// Returns `true` if `out` is the identity of the target group.
+#[inline]
+#[must_use]
+pub fn is_identity<H: CurveHooks>(out: &PairingOutput<Bls12_381<H>>) -> bool {
+    out.0.is_one()
+}
+
+/// Check the pairing equation `∏ e(a[i], b[i]) == 1`.
+///
+/// Runs the multi Miller loop and the final exponentiation through the hooks.
+/// Returns `false` on length mismatch or on any internal error, whatever the
+/// `HOOK_POLICY` of `H` but `OnError::Panic`.
+///
+/// The result must be used, as for every verification:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// # #![allow(deprecated)]
+/// use ark_bls12_381_ext::{verify_pairing_eq, PhantomHooks};
+///
+/// verify_pairing_eq::<PhantomHooks>(&[], &[]);
+/// ```
+#[must_use]
+pub fn verify_pairing_eq<H: CurveHooks>(a: &[G1Affine<H>], b: &[G2Affine<H>]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    check_pairing_product::<H>(a, b)
+}
+
+/// Pairing equation `∏ e(a[i], b[i]) == 1`, given as the `(a, b)` pair.
+pub type PairingEquation<H> = (Vec<G1Affine<H>>, Vec<G2Affine<H>>);
+
+/// Check independent pairing equations `∏ e(a[i], b[i]) == 1`, see `verify_pairing_eq`.
+///
+/// With the `parallel` feature the equations are checked concurrently, each with
+/// its own hook calls. Results are in the order of `eqs`.
+#[must_use]
+pub fn verify_pairings_batch<H: CurveHooks>(eqs: &[PairingEquation<H>]) -> Vec<bool> {
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+
+    #[cfg(feature = "parallel")]
+    let eqs = eqs.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let eqs = eqs.iter();
+    eqs.map(|(a, b)| verify_pairing_eq(a, b)).collect()
+}
+
+/// Check pairing equations at once through a random linear combination.
+///
+/// Each equation `∏ e(a[i], b[i]) == 1` is raised to a random power `r`, i.e. its
+/// G1 inputs are multiplied by `r` via the `mul_projective_g1` hook, and the product
+/// of all the equations is checked with a single multi Miller loop and final
+/// exponentiation.
+///
+/// The check is probabilistic: an invalid batch is accepted with probability about
+/// `1/r`, with `r` the order of `Fr`. Returns `false` if an equation has inputs of
+/// different lengths or on any internal error, including a G1 input other than the
+/// point at infinity scaled to it, which only a failed hook does (but for the
+/// negligible probability of a zero `r`).
+#[must_use]
+pub fn verify_pairings_aggregated<H: CurveHooks, R: Rng + ?Sized>(
+    eqs: &[PairingEquation<H>],
+    rng: &mut R,
+) -> bool {
+    let mut a = Vec::new();
+    let mut b = Vec::new();
+    for (eq_a, eq_b) in eqs {
+        if eq_a.len() != eq_b.len() {
+            return false;
+        }
+        let r = Fr::rand(rng).into_bigint();
+        for p in eq_a {
+            let Ok(scaled) = g1::try_mul_projective::<H>(&(*p).into(), r.as_ref()) else {
+                return false;
+            };
+            if scaled.is_zero() && !p.is_zero() {
+                return false;
+            }
+            a.push(scaled);
+        }
+        b.extend_from_slice(eq_b);
+    }
+    let a = G1Projective::<H>::normalize_batch(&a);
+    verify_pairing_eq(&a, &b)
+}
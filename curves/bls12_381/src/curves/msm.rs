@@ -0,0 +1,287 @@
+//! Multi scalar multiplication helpers.
+
+use crate::{
+    fq::Fq,
+    fr::Fr,
+    util::{expect_hook, trace_hook},
+    CurveHooks, G1Affine, G1Projective,
+};
+
+use ark_ff::{BigInt, BigInteger, PrimeField};
+use ark_models_ext::{short_weierstrass::SWCurveConfig, AffineRepr, CurveGroup};
+use ark_std::{cell::OnceCell, marker::PhantomData, vec, vec::Vec, Zero};
+
+/// Failure of the `checked_msm` of a group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckedMsmError {
+    /// Bases and scalars of different lengths, with the shorter length.
+    LengthMismatch(usize),
+    /// Base off the curve or out of the prime order subgroup, with its index.
+    InvalidBase(usize),
+    /// Internal error of the MSM hook.
+    Hook,
+}
+
+/// Number of pairs buffered by `MsmAccumulator` before each `msm_g1` hook call.
+pub const MSM_CHUNK_SIZE: usize = 1024;
+
+/// Window size (in bits) used by the default fixed-base tables.
+pub const FIXED_BASE_WINDOW_BITS: usize = 4;
+
+const WINDOWS: usize = (Fr::MODULUS_BIT_SIZE as usize).div_ceil(FIXED_BASE_WINDOW_BITS);
+const WINDOW_ENTRIES: usize = (1 << FIXED_BASE_WINDOW_BITS) - 1;
+
+/// Size of a default table entry, i.e. the Montgomery limbs of both coordinates.
+const ENTRY_SIZE: usize = 2 * 6 * 8;
+
+/// Fixed-base multi scalar multiplication on G1.
+///
+/// When the bases are fixed (e.g. the generators of a Pedersen commitment) and only
+/// the scalars change, the table is prepared once by the `fixed_base_msm_g1_table`
+/// hook and then reused by every `fixed_base_msm_g1` hook call.
+///
+/// The table is opaque: the default hooks store the in-Rust precomputation in the
+/// in-memory representation of the points, read back in place without any decoding,
+/// while a host is free to store anything it is able to interpret later.
+pub struct FixedBaseMsm<H: CurveHooks> {
+    len: usize,
+    table: Vec<u8>,
+    _phantom: PhantomData<fn() -> H>,
+}
+
+impl<H: CurveHooks> FixedBaseMsm<H> {
+    /// Prepare the table for `bases` jumping into the user-defined `fixed_base_msm_g1_table` hook.
+    pub fn new(bases: &[G1Affine<H>]) -> Result<Self, ()> {
+        trace_hook!("bls12_381_fixed_base_msm_g1_table", bases => H::G1_USAGE);
+        let table = expect_hook::<H, _>(
+            H::bls12_381_fixed_base_msm_g1_table(bases),
+            "bls12_381_fixed_base_msm_g1_table",
+        )?;
+        Ok(Self {
+            len: bases.len(),
+            table,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Number of bases the table was prepared for.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the table was prepared for no bases.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Opaque table bytes.
+    pub fn table(&self) -> &[u8] {
+        &self.table
+    }
+
+    /// Multi scalar multiplication jumping into the user-defined `fixed_base_msm_g1` hook.
+    ///
+    /// Follows the `VariableBaseMSM` contract: on length mismatch returns the
+    /// shorter length, on any internal error returns `Err(0)`.
+    pub fn msm(&self, scalars: &[Fr]) -> Result<G1Projective<H>, usize> {
+        if self.len != scalars.len() {
+            return Err(self.len.min(scalars.len()));
+        }
+        trace_hook!(
+            "bls12_381_fixed_base_msm_g1",
+            &self.table => H::G1_USAGE,
+            scalars => H::G1_USAGE,
+        );
+        expect_hook::<H, _>(
+            H::bls12_381_fixed_base_msm_g1(&self.table, scalars),
+            "bls12_381_fixed_base_msm_g1",
+        )
+        .map_err(|_| 0)
+    }
+}
+
+/// Incremental G1 multi scalar multiplication.
+///
+/// Folds `(base, scalar)` pairs as they come, without buffering the whole input:
+/// every `MSM_CHUNK_SIZE` pairs the buffer is flushed through the `msm_g1` hook and
+/// the result accumulated into a running sum.
+pub struct MsmAccumulator<H: CurveHooks> {
+    bases: Vec<G1Affine<H>>,
+    scalars: Vec<Fr>,
+    acc: Result<G1Projective<H>, usize>,
+}
+
+impl<H: CurveHooks> Default for MsmAccumulator<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: CurveHooks> MsmAccumulator<H> {
+    /// Empty accumulator.
+    pub fn new() -> Self {
+        Self {
+            bases: Vec::with_capacity(MSM_CHUNK_SIZE),
+            scalars: Vec::with_capacity(MSM_CHUNK_SIZE),
+            acc: Ok(G1Projective::<H>::zero()),
+        }
+    }
+
+    /// Add `[scalar]base` to the accumulated sum.
+    pub fn push(&mut self, base: G1Affine<H>, scalar: Fr) {
+        self.bases.push(base);
+        self.scalars.push(scalar);
+        if self.bases.len() == MSM_CHUNK_SIZE {
+            self.flush();
+        }
+    }
+
+    /// Accumulated sum.
+    ///
+    /// Follows the `VariableBaseMSM` contract: on any internal error of one of the
+    /// hook calls returns `Err(0)`.
+    pub fn finalize(mut self) -> Result<G1Projective<H>, usize> {
+        self.flush();
+        self.acc
+    }
+
+    fn flush(&mut self) {
+        if self.bases.is_empty() {
+            return;
+        }
+        if let Ok(acc) = &mut self.acc {
+            match crate::g1::Config::<H>::msm(&self.bases, &self.scalars) {
+                Ok(res) => *acc += res,
+                Err(err) => self.acc = Err(err),
+            }
+        }
+        self.bases.clear();
+        self.scalars.clear();
+    }
+}
+
+/// Multi scalar multiplication result, normalized to affine at most once.
+///
+/// Keeps the projective result, e.g. to add more terms to it, and computes the
+/// affine one on first use through the `batch_to_affine_g1` hook.
+pub struct MsmResult<H: CurveHooks> {
+    projective: G1Projective<H>,
+    affine: OnceCell<G1Affine<H>>,
+}
+
+impl<H: CurveHooks> MsmResult<H> {
+    /// Multi scalar multiplication jumping into the user-defined `msm_g1` hook.
+    ///
+    /// Same contract as `msm`.
+    pub fn msm(bases: &[G1Affine<H>], scalars: &[Fr]) -> Result<Self, usize> {
+        crate::g1::Config::<H>::msm(bases, scalars).map(Self::from)
+    }
+
+    /// Projective result.
+    pub fn as_projective(&self) -> &G1Projective<H> {
+        &self.projective
+    }
+
+    /// Affine result, normalized on the first call only.
+    pub fn as_affine(&self) -> &G1Affine<H> {
+        self.affine
+            .get_or_init(|| crate::g1::batch_to_affine_g1(&[self.projective])[0])
+    }
+}
+
+impl<H: CurveHooks> From<G1Projective<H>> for MsmResult<H> {
+    fn from(projective: G1Projective<H>) -> Self {
+        Self {
+            projective,
+            affine: OnceCell::new(),
+        }
+    }
+}
+
+/// In-Rust fixed-base table construction.
+///
+/// For each base `P` and window `k` stores `[d * 2^(w*k)]P` for every non-zero
+/// window digit `d`, so that the evaluation only requires additions.
+///
+/// Each entry is `ENTRY_SIZE` bytes: the little-endian Montgomery limbs of `x` then
+/// `y`, all zero for the point at infinity, which has no `(0, 0)` curve point to
+/// collide with.
+pub(crate) fn fixed_base_msm_g1_table<H: CurveHooks>(bases: &[G1Affine<H>]) -> Result<Vec<u8>, ()> {
+    let mut table = Vec::with_capacity(bases.len() * WINDOWS * WINDOW_ENTRIES);
+    for base in bases {
+        let mut window_base = G1Projective::<H>::from(*base);
+        for _ in 0..WINDOWS {
+            let mut entry = window_base;
+            for _ in 0..WINDOW_ENTRIES {
+                table.push(entry);
+                entry += window_base;
+            }
+            // `entry` is now `[2^w]window_base`.
+            window_base = entry;
+        }
+    }
+    let table = G1Projective::<H>::normalize_batch(&table);
+    let mut buf = Vec::with_capacity(table.len() * ENTRY_SIZE);
+    for entry in &table {
+        match entry.xy() {
+            Some((x, y)) => {
+                for limb in x.0 .0.iter().chain(&y.0 .0) {
+                    buf.extend_from_slice(&limb.to_le_bytes());
+                }
+            }
+            None => buf.extend_from_slice(&[0; ENTRY_SIZE]),
+        }
+    }
+    Ok(buf)
+}
+
+/// Table entry at `bytes`, `None` on limbs not reduced modulo `q`.
+fn read_table_entry<H: CurveHooks>(bytes: &[u8]) -> Option<G1Affine<H>> {
+    if bytes.iter().all(|b| *b == 0) {
+        return Some(G1Affine::<H>::zero());
+    }
+    let fq = |bytes: &[u8]| {
+        let mut limbs = [0u64; 6];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("8 bytes chunk"));
+        }
+        let limbs = BigInt(limbs);
+        (limbs < Fq::MODULUS).then(|| Fq::new_unchecked(limbs))
+    };
+    let (x, y) = bytes.split_at(ENTRY_SIZE / 2);
+    Some(G1Affine::<H>::new_unchecked(fq(x)?, fq(y)?))
+}
+
+/// In-Rust fixed-base multi scalar multiplication over a table built by
+/// `fixed_base_msm_g1_table`.
+pub(crate) fn fixed_base_msm_g1<H: CurveHooks>(
+    table: &[u8],
+    scalars: &[Fr],
+) -> Result<G1Projective<H>, ()> {
+    if table.len() != scalars.len() * WINDOWS * WINDOW_ENTRIES * ENTRY_SIZE {
+        return Err(());
+    }
+    let mut digits = vec![0; WINDOWS];
+    let mut res = G1Projective::<H>::zero();
+    for (scalar, base_table) in scalars
+        .iter()
+        .zip(table.chunks_exact(WINDOWS * WINDOW_ENTRIES * ENTRY_SIZE))
+    {
+        let scalar = scalar.into_bigint();
+        for (k, digit) in digits.iter_mut().enumerate() {
+            *digit = (0..FIXED_BASE_WINDOW_BITS)
+                .filter(|b| scalar.get_bit(k * FIXED_BASE_WINDOW_BITS + b))
+                .fold(0, |acc, b| acc | (1 << b));
+        }
+        for (digit, window) in digits
+            .iter()
+            .zip(base_table.chunks_exact(WINDOW_ENTRIES * ENTRY_SIZE))
+        {
+            if *digit != 0 {
+                let entry = &window[(digit - 1) * ENTRY_SIZE..digit * ENTRY_SIZE];
+                res += read_table_entry::<H>(entry).ok_or(())?;
+            }
+        }
+    }
+    Ok(res)
+}
@@ -0,0 +1,87 @@
+//! In-Rust defaults of the required hooks, enabled by the `native-fallback` feature.
+//!
+//! Points are converted coordinate-wise to the upstream *Arkworks* types, which
+//! share the same fields, and the upstream arithmetic is run. The MSMs use the
+//! bucket method of `ark_models_ext::pippenger` if `CurveHooks::MSM_WINDOW_BITS`
+//! is set.
+
+use crate::{Bls12_381, CurveHooks, G1Affine, G1Projective, G2Affine, G2Projective};
+
+use ark_bls12_381::{Bls12_381 as ArkBls12_381, Fq12, Fr};
+use ark_ec::{pairing::Pairing, short_weierstrass::SWCurveConfig, VariableBaseMSM};
+use ark_models_ext::{pairing::MillerLoopOutput, pippenger, AffineRepr};
+use ark_std::vec::Vec;
+
+fn ark_g1(p: &G1Affine<impl CurveHooks>) -> ark_bls12_381::G1Affine {
+    match p.xy() {
+        Some((x, y)) => ark_bls12_381::G1Affine::new_unchecked(x, y),
+        None => ark_bls12_381::G1Affine::zero(),
+    }
+}
+
+fn ark_g2(p: &G2Affine<impl CurveHooks>) -> ark_bls12_381::G2Affine {
+    match p.xy() {
+        Some((x, y)) => ark_bls12_381::G2Affine::new_unchecked(x, y),
+        None => ark_bls12_381::G2Affine::zero(),
+    }
+}
+
+pub(crate) fn multi_miller_loop<H: CurveHooks>(
+    g1: impl Iterator<Item = <Bls12_381<H> as Pairing>::G1Prepared>,
+    g2: impl Iterator<Item = <Bls12_381<H> as Pairing>::G2Prepared>,
+) -> Result<Fq12, ()> {
+    let g1: Vec<_> = g1.map(|p| ark_g1(&p.0)).collect();
+    let g2: Vec<_> = g2.map(|p| ark_g2(&p.0)).collect();
+    Ok(ArkBls12_381::multi_miller_loop(g1, g2).0)
+}
+
+pub(crate) fn final_exponentiation(target: Fq12) -> Result<Fq12, ()> {
+    ArkBls12_381::final_exponentiation(MillerLoopOutput(target))
+        .map(|res| res.0)
+        .ok_or(())
+}
+
+pub(crate) fn msm_g1<H: CurveHooks>(
+    bases: &[G1Affine<H>],
+    scalars: &[Fr],
+) -> Result<G1Projective<H>, ()> {
+    let bases: Vec<_> = bases.iter().map(ark_g1).collect();
+    let res = match H::MSM_WINDOW_BITS {
+        Some(bits) => pippenger::msm_native_sw_with_window(&bases, scalars, bits)?,
+        None => ark_bls12_381::G1Projective::msm(&bases, scalars).map_err(|_| ())?,
+    };
+    Ok(G1Projective::<H>::new_unchecked(res.x, res.y, res.z))
+}
+
+pub(crate) fn msm_g2<H: CurveHooks>(
+    bases: &[G2Affine<H>],
+    scalars: &[Fr],
+) -> Result<G2Projective<H>, ()> {
+    let bases: Vec<_> = bases.iter().map(ark_g2).collect();
+    let res = match H::MSM_WINDOW_BITS {
+        Some(bits) => pippenger::msm_native_sw_with_window(&bases, scalars, bits)?,
+        None => ark_bls12_381::G2Projective::msm(&bases, scalars).map_err(|_| ())?,
+    };
+    Ok(G2Projective::<H>::new_unchecked(res.x, res.y, res.z))
+}
+
+/// The upstream multiplication is already GLV accelerated, for any representation
+/// of `base`. `g1::mul_glv` isn't used as its decomposition differs, giving other
+/// results for points out of the subgroup.
+pub(crate) fn mul_projective_g1<H: CurveHooks>(
+    base: &G1Projective<H>,
+    scalar: &[u64],
+) -> Result<G1Projective<H>, ()> {
+    let base = ark_bls12_381::G1Projective::new_unchecked(base.x, base.y, base.z);
+    let res = ark_bls12_381::g1::Config::mul_projective(&base, scalar);
+    Ok(G1Projective::<H>::new_unchecked(res.x, res.y, res.z))
+}
+
+pub(crate) fn mul_projective_g2<H: CurveHooks>(
+    base: &G2Projective<H>,
+    scalar: &[u64],
+) -> Result<G2Projective<H>, ()> {
+    let base = ark_bls12_381::G2Projective::new_unchecked(base.x, base.y, base.z);
+    let res = ark_bls12_381::g2::Config::mul_projective(&base, scalar);
+    Ok(G2Projective::<H>::new_unchecked(res.x, res.y, res.z))
+}
@@ -0,0 +1,161 @@
+//! Pairing over cached G2 preparations.
+//!
+//! When the same G2 point takes part in many pairings (e.g. an aggregated public
+//! key checked against many signatures), its Miller loop line coefficients can be
+//! computed once and reused, instead of letting every Miller loop prepare it again.
+//! The cost is a larger representation exchanged with the host: about 12KB of
+//! coefficients in place of a 192 bytes affine point.
+//!
+//! The line coefficients are entirely determined by the G2 point, while no two
+//! coefficients of a line are related in a way which would allow to store them
+//! partially. Thus the compressed encoding of a preparation is the 96 bytes
+//! compressed point, with the coefficients recomputed while decoding.
+
+use crate::{
+    codec::{self, Usage},
+    util::{self, expect_hook},
+    Bls12_381, CurveHooks, G1Affine, G2Affine,
+};
+
+use ark_bls12_381::{Bls12_381 as ArkBls12_381, Config as ArkConfig, Fq12};
+use ark_ec::{bls12, pairing::Pairing};
+use ark_models_ext::{
+    pairing::{MillerLoopOutput, PairingOutput},
+    AffineRepr,
+};
+use ark_serialize::{Compress, Validate};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+/// G2 point with its Miller loop line coefficients precomputed in-Rust.
+pub struct CachedG2Prepared<H: CurveHooks> {
+    point: G2Affine<H>,
+    prepared: bls12::G2Prepared<ArkConfig>,
+    _phantom: PhantomData<fn() -> H>,
+}
+
+impl<H: CurveHooks> CachedG2Prepared<H> {
+    /// Prepare `point`.
+    pub fn new(point: &G2Affine<H>) -> Self {
+        let ark_point = match point.xy() {
+            Some((x, y)) => ark_bls12_381::G2Affine::new_unchecked(x, y),
+            None => ark_bls12_381::G2Affine::zero(),
+        };
+        Self {
+            point: *point,
+            prepared: ark_point.into(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Preparation of `point` from line coefficients computed elsewhere, e.g. by a
+    /// `miller_lines` hook.
+    ///
+    /// The coefficients are not checked against `point`.
+    pub fn from_lines(point: G2Affine<H>, prepared: bls12::G2Prepared<ArkConfig>) -> Self {
+        Self {
+            point,
+            prepared,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Prepared point.
+    pub fn point(&self) -> &G2Affine<H> {
+        &self.point
+    }
+
+    /// Upstream prepared representation, holding the line coefficients.
+    pub fn as_ark(&self) -> &bls12::G2Prepared<ArkConfig> {
+        &self.prepared
+    }
+}
+
+impl<H: CurveHooks> Clone for CachedG2Prepared<H> {
+    fn clone(&self) -> Self {
+        Self {
+            point: self.point,
+            prepared: self.prepared.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<H: CurveHooks> From<G2Affine<H>> for CachedG2Prepared<H> {
+    fn from(point: G2Affine<H>) -> Self {
+        Self::new(&point)
+    }
+}
+
+/// Miller loop line coefficients of `points`, jumping into the user-defined
+/// `miller_lines` hook.
+///
+/// On any internal error, or if the hook returns preparations of other points,
+/// falls back to the in-Rust preparation.
+pub fn prepare_g2_lines<H: CurveHooks>(points: &[G2Affine<H>]) -> Vec<CachedG2Prepared<H>> {
+    util::trace_hook!("bls12_381_miller_lines", points => H::G2_USAGE);
+    util::strict_hook::<H, _>(H::bls12_381_miller_lines(points), "bls12_381_miller_lines")
+        .ok()
+        .filter(|res| {
+            res.len() == points.len() && res.iter().zip(points).all(|(r, p)| r.point() == p)
+        })
+        .unwrap_or_else(|| points.iter().map(CachedG2Prepared::new).collect())
+}
+
+/// Codec of the compressed preparations.
+const COMPRESSED_USAGE: Usage = Usage::new(Compress::Yes, Validate::Yes);
+
+/// Compressed encoding of `prepared`, i.e. the compressed encoding of the prepared point.
+pub fn serialize_g2_prepared_compressed<H: CurveHooks>(prepared: &CachedG2Prepared<H>) -> Vec<u8> {
+    codec::encode(&prepared.point, COMPRESSED_USAGE)
+}
+
+/// Preparation of the point in the compressed encoding `bytes`.
+///
+/// The point is validated, then prepared again. Trailing bytes are rejected.
+pub fn deserialize_g2_prepared_compressed<H: CurveHooks>(
+    bytes: &[u8],
+) -> Result<CachedG2Prepared<H>, ()> {
+    codec::decode_exact(bytes, COMPRESSED_USAGE).map(|point| CachedG2Prepared::new(&point))
+}
+
+/// Pairing product jumping into the user-defined `multi_miller_loop_prepared` hook.
+///
+/// The final exponentiation goes through the `final_exponentiation` hook.
+/// For a Miller loop internal error uses `TargetField::zero()`, for a final
+/// exponentiation internal error returns `None`.
+#[must_use]
+pub fn pairing_with_prepared<H: CurveHooks>(
+    a: &[G1Affine<H>],
+    b: &[CachedG2Prepared<H>],
+) -> Option<PairingOutput<Bls12_381<H>>> {
+    util::trace_hook!(
+        "bls12_381_multi_miller_loop_prepared",
+        a => H::G1_USAGE,
+        &b.iter().map(|p| p.prepared.clone()).collect::<Vec<_>>() => H::G2_USAGE,
+    );
+    let res = expect_hook::<H, _>(
+        H::bls12_381_multi_miller_loop_prepared(a, b),
+        "bls12_381_multi_miller_loop_prepared",
+    )
+    .unwrap_or_default();
+    Bls12_381::<H>::final_exponentiation(MillerLoopOutput(res))
+}
+
+/// In-Rust multi Miller loop over cached G2 preparations.
+pub(crate) fn multi_miller_loop_prepared<H: CurveHooks>(
+    a: &[G1Affine<H>],
+    b: &[CachedG2Prepared<H>],
+) -> Result<Fq12, ()> {
+    if a.len() != b.len() {
+        return Err(());
+    }
+    let a: Vec<_> = a
+        .iter()
+        .map(|p| match p.xy() {
+            Some((x, y)) => ark_bls12_381::G1Affine::new_unchecked(x, y),
+            None => ark_bls12_381::G1Affine::zero(),
+        })
+        .collect();
+    let b = b.iter().map(|p| p.prepared.clone());
+    Ok(ArkBls12_381::multi_miller_loop(a, b).0)
+}
@@ -0,0 +1,56 @@
+//! Typed scalars for the projective multiplication hooks.
+
+use crate::{fr::Fr, g1, g2, CurveHooks, G1Projective, G2Projective};
+
+use ark_ff::{BigInt, PrimeField};
+use ark_models_ext::short_weierstrass::SWCurveConfig;
+
+/// Scalar in the limbs order expected by the `mul_projective` hooks.
+///
+/// Internally holds little-endian `u64` limbs (least significant limb first),
+/// whatever the order of the limbs it was built from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scalar([u64; 4]);
+
+impl Scalar {
+    /// Build from little-endian limbs, i.e. least significant limb first.
+    pub const fn from_le_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    /// Build from big-endian limbs, i.e. most significant limb first.
+    pub const fn from_be_limbs(limbs: [u64; 4]) -> Self {
+        Self([limbs[3], limbs[2], limbs[1], limbs[0]])
+    }
+
+    /// Little-endian limbs as given to the `mul_projective` hooks.
+    pub fn limbs(&self) -> &[u64] {
+        &self.0
+    }
+}
+
+impl From<Fr> for Scalar {
+    fn from(scalar: Fr) -> Self {
+        scalar.into_bigint().into()
+    }
+}
+
+impl From<BigInt<4>> for Scalar {
+    fn from(scalar: BigInt<4>) -> Self {
+        Self(scalar.0)
+    }
+}
+
+/// Multiplication on G1 jumping into the user-defined `mul_projective_g1` hook.
+///
+/// On any internal error returns `Projective::zero()`.
+pub fn mul_g1<H: CurveHooks>(base: &G1Projective<H>, scalar: Scalar) -> G1Projective<H> {
+    g1::Config::<H>::mul_projective(base, scalar.limbs())
+}
+
+/// Multiplication on G2 jumping into the user-defined `mul_projective_g2` hook.
+///
+/// On any internal error returns `Projective::zero()`.
+pub fn mul_g2<H: CurveHooks>(base: &G2Projective<H>, scalar: Scalar) -> G2Projective<H> {
+    g2::Config::<H>::mul_projective(base, scalar.limbs())
+}
@@ -0,0 +1,66 @@
+//! Secret scalars wiped from memory after use.
+
+use crate::{fr::Fr, g1, g2, CurveHooks, G1Projective, G2Projective};
+
+use ark_ff::PrimeField;
+use ark_models_ext::short_weierstrass::SWCurveConfig;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{marker::PhantomData, vec::Vec};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Scalar which may be a secret, e.g. a signing key or a blinding factor.
+///
+/// The limbs handed over to the `mul_projective` hooks are wiped as soon as the
+/// hook returns, the scalar itself is wiped on drop.
+pub struct SecretScalar<H: CurveHooks> {
+    scalar: Fr,
+    limbs: [u64; 4],
+    _phantom: PhantomData<fn() -> H>,
+}
+
+impl<H: CurveHooks> SecretScalar<H> {
+    pub fn new(scalar: Fr) -> Self {
+        Self {
+            scalar,
+            limbs: [0; 4],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Multiplication on G1 jumping into the user-defined `mul_projective_g1` hook.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    pub fn mul_g1(&mut self, base: &G1Projective<H>) -> G1Projective<H> {
+        self.limbs = self.scalar.into_bigint().0;
+        let res = g1::Config::<H>::mul_projective(base, &self.limbs);
+        self.limbs.zeroize();
+        res
+    }
+
+    /// Multiplication on G2 jumping into the user-defined `mul_projective_g2` hook.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    pub fn mul_g2(&mut self, base: &G2Projective<H>) -> G2Projective<H> {
+        self.limbs = self.scalar.into_bigint().0;
+        let res = g2::Config::<H>::mul_projective(base, &self.limbs);
+        self.limbs.zeroize();
+        res
+    }
+
+    /// Compressed encoding of the scalar, wiped on drop.
+    pub fn encode(&self) -> Zeroizing<Vec<u8>> {
+        let mut buf = Zeroizing::new(Vec::with_capacity(self.scalar.compressed_size()));
+        // Writing into a `Vec` can't fail.
+        let _ = self.scalar.serialize_compressed(&mut *buf);
+        buf
+    }
+}
+
+impl<H: CurveHooks> Drop for SecretScalar<H> {
+    fn drop(&mut self) {
+        self.scalar.zeroize();
+        self.limbs.zeroize();
+    }
+}
+
+impl<H: CurveHooks> ZeroizeOnDrop for SecretScalar<H> {}
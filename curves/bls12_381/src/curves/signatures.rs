@@ -0,0 +1,247 @@
+//! BLS signatures verification.
+//!
+//! Supports both variants of the
+//! [BLS signature scheme](https://datatracker.ietf.org/doc/draft-irtf-cfrg-bls-signature/):
+//! *minimal-pubkey-size* ([`MinPk`]), with public keys in G1 and signatures in G2, and
+//! *minimal-signature-size* ([`MinSig`]), with public keys in G2 and signatures in G1.
+//! The pairings are evaluated with a single call to the `multi_miller_loop` and
+//! `final_exponentiation` hooks. The [`Scheme`] is given explicitly, independently
+//! of the domain separation tag.
+
+use crate::{
+    hashing::{hash_to_g1, hash_to_g2},
+    CurveHooks, G1Affine, G2Affine,
+};
+
+use ark_models_ext::AffineRepr;
+use ark_serialize::CanonicalDeserialize;
+use ark_std::vec::Vec;
+
+/// Scheme of the BLS signatures, i.e. how aggregates are protected against rogue
+/// key attacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// *Basic* scheme: the messages of an aggregate must be distinct.
+    Basic,
+    /// *Message augmentation* scheme: each message is prefixed with the compressed
+    /// encoding of its signer public key before being hashed.
+    MessageAugmentation,
+    /// *Proof of possession* scheme: each public key comes with a proof of
+    /// possession, checked by the caller when the key is registered.
+    ProofOfPossession,
+}
+
+/// Selects the groups of the public keys and signatures.
+pub trait BlsVariant<H: CurveHooks> {
+    /// Public keys group.
+    type PublicKey: AffineRepr;
+    /// Signatures group, i.e. the group messages are hashed to.
+    type Signature: AffineRepr;
+
+    /// Hash `msg` to the signatures group using the domain separation tag `dst`.
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> Result<Self::Signature, ()>;
+
+    /// Returns `true` if `pubkey` is on the curve and in the prime order subgroup.
+    fn is_valid_public_key(pubkey: &Self::PublicKey) -> bool;
+
+    /// Returns `true` if `sig` is on the curve and in the prime order subgroup.
+    fn is_valid_signature(sig: &Self::Signature) -> bool;
+
+    /// Returns `true` if `e(pubkey_1, hash_1) * ... * e(pubkey_n, hash_n) == e(G, sig)`,
+    /// where `G` is the generator of the public keys group.
+    fn check_pairings(
+        pubkeys: &[Self::PublicKey],
+        hashes: Vec<Self::Signature>,
+        sig: Self::Signature,
+    ) -> bool;
+}
+
+/// *Minimal-pubkey-size* variant: public keys in G1, signatures in G2.
+pub struct MinPk;
+
+impl MinPk {
+    /// Domain separation tag of the *basic* scheme ciphersuite.
+    pub const DST_BASIC: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+    /// Domain separation tag of the *message augmentation* scheme ciphersuite.
+    pub const DST_AUG: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_AUG_";
+
+    /// Domain separation tag of the *proof of possession* scheme ciphersuite.
+    pub const DST_POP: &'static [u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+}
+
+impl<H: CurveHooks> BlsVariant<H> for MinPk {
+    type PublicKey = G1Affine<H>;
+    type Signature = G2Affine<H>;
+
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> Result<G2Affine<H>, ()> {
+        hash_to_g2(msg, dst)
+    }
+
+    fn is_valid_public_key(pubkey: &G1Affine<H>) -> bool {
+        pubkey.is_on_curve() && pubkey.is_in_correct_subgroup_assuming_on_curve()
+    }
+
+    fn is_valid_signature(sig: &G2Affine<H>) -> bool {
+        sig.is_on_curve() && sig.is_in_correct_subgroup_assuming_on_curve()
+    }
+
+    fn check_pairings(
+        pubkeys: &[G1Affine<H>],
+        mut hashes: Vec<G2Affine<H>>,
+        sig: G2Affine<H>,
+    ) -> bool {
+        let g1 = pubkeys.iter().copied().chain([-G1Affine::<H>::generator()]);
+        hashes.push(sig);
+        crate::check_pairing_product::<H>(g1, hashes)
+    }
+}
+
+/// *Minimal-signature-size* variant: public keys in G2, signatures in G1.
+pub struct MinSig;
+
+impl MinSig {
+    /// Domain separation tag of the *basic* scheme ciphersuite.
+    pub const DST_BASIC: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+    /// Domain separation tag of the *message augmentation* scheme ciphersuite.
+    pub const DST_AUG: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_AUG_";
+
+    /// Domain separation tag of the *proof of possession* scheme ciphersuite.
+    pub const DST_POP: &'static [u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+}
+
+impl<H: CurveHooks> BlsVariant<H> for MinSig {
+    type PublicKey = G2Affine<H>;
+    type Signature = G1Affine<H>;
+
+    fn hash_to_signature_group(msg: &[u8], dst: &[u8]) -> Result<G1Affine<H>, ()> {
+        hash_to_g1(msg, dst)
+    }
+
+    fn is_valid_public_key(pubkey: &G2Affine<H>) -> bool {
+        pubkey.is_on_curve() && pubkey.is_in_correct_subgroup_assuming_on_curve()
+    }
+
+    fn is_valid_signature(sig: &G1Affine<H>) -> bool {
+        sig.is_on_curve() && sig.is_in_correct_subgroup_assuming_on_curve()
+    }
+
+    fn check_pairings(
+        pubkeys: &[G2Affine<H>],
+        mut hashes: Vec<G1Affine<H>>,
+        sig: G1Affine<H>,
+    ) -> bool {
+        let g2 = pubkeys.iter().copied().chain([-G2Affine::<H>::generator()]);
+        hashes.push(sig);
+        crate::check_pairing_product::<H>(hashes, g2)
+    }
+}
+
+/// Verify the signature `sig` of `msg` for `pubkey` under `scheme`.
+///
+/// Checks that `e(pubkey, H(msg)) == e(G, sig)`, where `H` hashes to the signatures
+/// group with `dst` and `G` is the generator of the public keys group. With
+/// `Scheme::MessageAugmentation` the hashed message is prefixed with `pubkey`.
+/// Returns `false` on identity or off-subgroup inputs and on any internal error.
+#[must_use]
+pub fn verify<V: BlsVariant<H>, H: CurveHooks>(
+    pubkey: V::PublicKey,
+    msg: &[u8],
+    sig: V::Signature,
+    dst: &[u8],
+    scheme: Scheme,
+) -> bool {
+    verify_aggregate::<V, H>(&[pubkey], &[msg], sig, dst, scheme)
+}
+
+/// Verify the aggregate signature `agg_sig` of the `msgs`, each signed by the
+/// public key at the same position in `pubkeys`.
+///
+/// Checks that `e(G, agg_sig) == e(pubkey_1, H(msg_1)) * ... * e(pubkey_n, H(msg_n))`,
+/// where `H` hashes to the signatures group with `dst` and `G` is the generator of
+/// the public keys group. The equation only binds each signer to its own message as
+/// required by `scheme`: with `Scheme::Basic` the messages must be distinct, with
+/// `Scheme::MessageAugmentation` each hashed message is prefixed with its public key,
+/// with `Scheme::ProofOfPossession` the proofs are checked by the caller.
+///
+/// Returns `false` on empty or length mismatched inputs, on identity or off-subgroup
+/// points, messages hashed to the identity included, on duplicated messages with
+/// `Scheme::Basic` and on any internal error.
+#[must_use]
+pub fn verify_aggregate<V: BlsVariant<H>, H: CurveHooks>(
+    pubkeys: &[V::PublicKey],
+    msgs: &[&[u8]],
+    agg_sig: V::Signature,
+    dst: &[u8],
+    scheme: Scheme,
+) -> bool {
+    if pubkeys.is_empty() || pubkeys.len() != msgs.len() {
+        return false;
+    }
+    if scheme == Scheme::Basic && has_duplicates(msgs) {
+        return false;
+    }
+    let valid_pubkeys = pubkeys
+        .iter()
+        .all(|pk| !pk.is_zero() && V::is_valid_public_key(pk));
+    if agg_sig.is_zero() || !V::is_valid_signature(&agg_sig) || !valid_pubkeys {
+        return false;
+    }
+    let Ok(hashes) = pubkeys
+        .iter()
+        .zip(msgs)
+        .map(|(pubkey, msg)| match scheme {
+            Scheme::MessageAugmentation => V::hash_to_signature_group(&augment(pubkey, msg), dst),
+            Scheme::Basic | Scheme::ProofOfPossession => V::hash_to_signature_group(msg, dst),
+        })
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return false;
+    };
+    // Pairs with the identity are dropped from the pairing product, an identity hash
+    // (e.g. from a failed hook) would drop its signer from the check.
+    if hashes.iter().any(|hash| hash.is_zero()) {
+        return false;
+    }
+    V::check_pairings(pubkeys, hashes, agg_sig)
+}
+
+/// Message `pubkey || msg` hashed by the message augmentation scheme, with the
+/// compressed encoding of `pubkey`.
+fn augment<P: AffineRepr>(pubkey: &P, msg: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(pubkey.compressed_size() + msg.len());
+    // Writing into a `Vec` can't fail.
+    let _ = pubkey.serialize_compressed(&mut buf);
+    buf.extend_from_slice(msg);
+    buf
+}
+
+/// Returns `true` if two of the `msgs` are equal.
+fn has_duplicates(msgs: &[&[u8]]) -> bool {
+    let mut sorted = msgs.to_vec();
+    sorted.sort_unstable();
+    sorted.windows(2).any(|w| w[0] == w[1])
+}
+
+/// Decode a public key in the Ethereum consensus layer encoding, i.e. the
+/// `BLSPubkey` SSZ type, which is the compressed G1 encoding.
+///
+/// As by the `KeyValidate` procedure of the consensus specification, the point must
+/// be in the prime order subgroup and must not be the point at infinity.
+pub fn from_ssz_pubkey<H: CurveHooks>(bytes: &[u8; 48]) -> Result<G1Affine<H>, ()> {
+    let pubkey = G1Affine::<H>::deserialize_compressed(&bytes[..]).map_err(|_| ())?;
+    if pubkey.is_zero() {
+        return Err(());
+    }
+    Ok(pubkey)
+}
+
+/// Decode a signature in the Ethereum consensus layer encoding, i.e. the
+/// `BLSSignature` SSZ type, which is the compressed G2 encoding.
+///
+/// The point must be in the prime order subgroup. The point at infinity is a valid
+/// encoding, rejected by the verification itself.
+pub fn from_ssz_signature<H: CurveHooks>(bytes: &[u8; 96]) -> Result<G2Affine<H>, ()> {
+    G2Affine::<H>::deserialize_compressed(&bytes[..]).map_err(|_| ())
+}
@@ -44,6 +44,26 @@ impl CurveHooks for TestHooks {
         test_utils::msm_sw_generic::<G1Config, ArkG1Config>(bases, scalars)
     }
 
+    fn bls12_381_msm_g1_affine(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<G1Affine, ()> {
+        test_utils::msm_sw_affine_generic::<G1Config, ArkG1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g1_with_scratch(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+        scratch: &mut crate::codec::SerializeScratch,
+    ) -> Result<G1Projective, ()> {
+        // Round trip through the encoding, as a host forwarding implementation does.
+        let usage = G1Config::USAGE;
+        let (bases, scalars) = scratch.encode_pair(bases, scalars, usage);
+        let bases: Vec<G1Affine> = crate::codec::decode(bases, usage)?;
+        let scalars: Vec<Fr> = crate::codec::decode(scalars, usage)?;
+        test_utils::msm_sw_generic::<G1Config, ArkG1Config>(&bases, &scalars)
+    }
+
     fn bls12_381_msm_g2(
         bases: &[G2Affine],
         scalars: &[<G2Config as CurveConfig>::ScalarField],
@@ -51,6 +71,23 @@ impl CurveHooks for TestHooks {
         test_utils::msm_sw_generic::<G2Config, ArkG2Config>(bases, scalars)
     }
 
+    fn bls12_381_hash_to_g2(msg: &[u8], dst: &[u8]) -> Result<G2Affine, ()> {
+        // Host side only knows about the upstream types, the point crosses compressed.
+        use ark_ec::hashing::{
+            curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve,
+        };
+        let hasher = MapToCurveBasedHasher::<
+            ark_bls12_381::G2Projective,
+            ark_ff::field_hashers::DefaultFieldHasher<sha2::Sha256, 128>,
+            WBMap<ArkG2Config>,
+        >::new(dst)
+        .map_err(|_| ())?;
+        let p = hasher.hash(msg).map_err(|_| ())?;
+        let mut buf = vec![];
+        p.serialize_compressed(&mut buf).map_err(|_| ())?;
+        G2Affine::deserialize_compressed(&buf[..]).map_err(|_| ())
+    }
+
     fn bls12_381_mul_projective_g1(
         base: &G1Projective,
         scalar: &[u64],
@@ -58,6 +95,18 @@ impl CurveHooks for TestHooks {
         test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(base, scalar)
     }
 
+    fn bls12_381_mul_projective_g1_with_scratch(
+        base: &G1Projective,
+        scalar: &[u64],
+        scratch: &mut crate::codec::SerializeScratch,
+    ) -> Result<G1Projective, ()> {
+        let usage = G1Config::USAGE;
+        let (base, scalar) = scratch.encode_pair(base, scalar, usage);
+        let base: G1Projective = crate::codec::decode(base, usage)?;
+        let scalar: Vec<u64> = crate::codec::decode(scalar, usage)?;
+        test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(&base, &scalar)
+    }
+
     fn bls12_381_mul_projective_g2(
         base: &G2Projective,
         scalar: &[u64],
@@ -66,6 +115,67 @@ impl CurveHooks for TestHooks {
     }
 }
 
+type CountingHooks = test_utils::CountingHooks<TestHooks>;
+
+impl CurveHooks for CountingHooks {
+    const G1_USAGE: crate::codec::Usage = TestHooks::G1_USAGE;
+    const G2_USAGE: crate::codec::Usage = TestHooks::G2_USAGE;
+
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Self::record("bls12_381_multi_miller_loop");
+        test_utils::multi_miller_loop_generic::<crate::Bls12_381<Self>, Bls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <crate::Bls12_381<Self> as Pairing>::TargetField,
+    ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Self::record("bls12_381_final_exponentiation");
+        test_utils::final_exponentiation_generic::<crate::Bls12_381<Self>, Bls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[crate::G1Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        Self::record("bls12_381_msm_g1");
+        test_utils::msm_sw_generic::<crate::g1::Config<Self>, G1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[crate::G2Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        Self::record("bls12_381_msm_g2");
+        test_utils::msm_sw_generic::<crate::g2::Config<Self>, G2Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &crate::G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        Self::record("bls12_381_mul_projective_g1");
+        test_utils::mul_projective_sw_generic::<crate::g1::Config<Self>, G1Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &crate::G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        Self::record("bls12_381_mul_projective_g2");
+        test_utils::mul_projective_sw_generic::<crate::g2::Config<Self>, G2Config>(base, scalar)
+    }
+
+    fn bls12_381_batch_to_affine_g1(
+        points: &[crate::G1Projective<Self>],
+    ) -> Result<Vec<crate::G1Affine<Self>>, ()> {
+        Self::record("bls12_381_batch_to_affine_g1");
+        Ok(crate::G1Projective::<Self>::normalize_batch(points))
+    }
+}
+
 test_group!(g1; G1Projective; sw);
 test_group!(g2; G2Projective; sw);
 test_group!(pairing_output; PairingOutput<Bls12_381>; msm);
@@ -190,6 +300,33 @@ fn g2_uncompressed_valid_test_vectors() {
     test_vectors!(G2Projective, G2Affine, Compress::No, bytes);
 }
 
+#[test]
+fn generated_test_vectors_match_committed_ones() {
+    use test_utils::gen_sw_test_vectors;
+
+    let vectors: [(&[u8], Vec<u8>); 4] = [
+        (
+            include_bytes!("g1_compressed_valid_test_vectors.dat"),
+            gen_sw_test_vectors::<G1Config>(1000, Compress::Yes),
+        ),
+        (
+            include_bytes!("g1_uncompressed_valid_test_vectors.dat"),
+            gen_sw_test_vectors::<G1Config>(1000, Compress::No),
+        ),
+        (
+            include_bytes!("g2_compressed_valid_test_vectors.dat"),
+            gen_sw_test_vectors::<G2Config>(1000, Compress::Yes),
+        ),
+        (
+            include_bytes!("g2_uncompressed_valid_test_vectors.dat"),
+            gen_sw_test_vectors::<G2Config>(1000, Compress::No),
+        ),
+    ];
+    for (expected, generated) in vectors {
+        assert_eq!(generated, expected);
+    }
+}
+
 #[test]
 fn test_cofactor_clearing_g1() {
     let sample_unchecked = || {
@@ -239,3 +376,3233 @@ fn test_cofactor_clearing_g2() {
         assert_eq!(optimised, naive);
     }
 }
+
+#[test]
+#[allow(deprecated)]
+fn phantom_hooks_fail_every_operation() {
+    use ark_models_ext::short_weierstrass::SWCurveConfig;
+    type Hooks = crate::PhantomHooks;
+
+    let g1 = crate::G1Affine::<Hooks>::generator();
+    let g2 = crate::G2Affine::<Hooks>::generator();
+    let one = <crate::g1::Config<Hooks> as CurveConfig>::ScalarField::from(1);
+    assert_eq!(crate::g1::Config::<Hooks>::msm(&[g1], &[one]), Err(0));
+    assert_eq!(crate::g1::msm_into_affine::<Hooks>(&[g1], &[one]), Err(0));
+    assert!(crate::g1::Config::<Hooks>::mul_affine(&g1, &[2]).is_zero());
+    assert!(crate::g2::Config::<Hooks>::mul_affine(&g2, &[2]).is_zero());
+    let target = crate::Bls12_381::<Hooks>::multi_miller_loop([g1], [g2]);
+    assert!(target.0.is_zero());
+    assert!(crate::Bls12_381::<Hooks>::final_exponentiation(target).is_none());
+    let prepared = crate::CachedG2Prepared::<Hooks>::new(&g2);
+    assert!(crate::pairing_with_prepared::<Hooks>(&[g1], &[prepared]).is_none());
+    assert!(crate::Config::<Hooks>::final_exponentiation_batch(&[target]).is_none());
+    let gt = PairingOutput::<crate::Bls12_381<Hooks>>::zero();
+    assert_eq!(crate::gt::gt_msm::<Hooks>(&[gt], &[one]), Err(0));
+    assert!(crate::hashing::hash_to_g2::<Hooks>(b"msg", b"dst").is_err());
+}
+
+#[test]
+fn fixed_base_msm_matches_variable_base_msm() {
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..8).map(|_| G1Affine::rand(&mut rng)).collect();
+    let table = crate::msm::FixedBaseMsm::<TestHooks>::new(&bases).unwrap();
+    assert_eq!(table.len(), bases.len());
+
+    for _ in 0..4 {
+        let scalars: Vec<Fr> = (0..8).map(|_| Fr::rand(&mut rng)).collect();
+        let expected = G1Config::msm(&bases, &scalars).unwrap();
+        assert_eq!(table.msm(&scalars).unwrap(), expected);
+    }
+
+    // Edge case scalars.
+    let scalars = vec![
+        Fr::zero(),
+        Fr::one(),
+        -Fr::one(),
+        Fr::from(16),
+        Fr::zero(),
+        Fr::one(),
+        Fr::from(15),
+        -Fr::from(2),
+    ];
+    let expected = G1Config::msm(&bases, &scalars).unwrap();
+    assert_eq!(table.msm(&scalars).unwrap(), expected);
+
+    assert_eq!(table.msm(&scalars[..3]), Err(3));
+}
+
+#[test]
+fn fixed_base_msm_default_table_entries() {
+    let mut rng = test_rng();
+    let bases = [G1Affine::rand(&mut rng), G1Affine::zero()];
+    let scalars = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+    let table = crate::msm::FixedBaseMsm::<TestHooks>::new(&bases).unwrap();
+    assert_eq!(table.msm(&scalars), Ok(bases[0] * scalars[0]));
+
+    // Limbs not reduced modulo `q` are rejected.
+    let mut bytes = table.table().to_vec();
+    bytes[..48].fill(0xff);
+    assert!(TestHooks::bls12_381_fixed_base_msm_g1(&bytes, &[Fr::one(), Fr::zero()]).is_err());
+}
+
+#[test]
+fn streamed_points_serialization_matches_slice_serialization() {
+    use crate::codec::serialize_points_into;
+
+    let mut rng = test_rng();
+    let mut g1: Vec<G1Affine> = (0..16).map(|_| G1Affine::rand(&mut rng)).collect();
+    g1.push(G1Affine::zero());
+    let g2: Vec<G2Affine> = (0..4).map(|_| G2Affine::rand(&mut rng)).collect();
+
+    for compress in [Compress::Yes, Compress::No] {
+        let mut expected = Vec::new();
+        g1.serialize_with_mode(&mut expected, compress).unwrap();
+        let mut streamed = Vec::new();
+        serialize_points_into(&g1, &mut streamed, compress).unwrap();
+        assert_eq!(streamed, expected);
+
+        let mut expected = Vec::new();
+        g2.serialize_with_mode(&mut expected, compress).unwrap();
+        let mut streamed = Vec::new();
+        serialize_points_into(&g2, &mut streamed, compress).unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    // A bounded buffer too small for the encoding is reported as an error.
+    let mut buf = [0u8; 64];
+    assert!(serialize_points_into(&g1, &mut buf[..], Compress::Yes).is_err());
+}
+
+#[test]
+#[allow(deprecated)]
+fn normalize_batch_g1_matches_upstream() {
+    use ark_bls12_381::G1Projective as ArkG1Projective;
+
+    let mut rng = test_rng();
+    let mut points: Vec<G1Projective> = (0..16).map(|_| G1Projective::rand(&mut rng)).collect();
+    points.push(G1Projective::zero());
+
+    let mut buf = Vec::new();
+    points.serialize_uncompressed(&mut buf).unwrap();
+    let ark_points = Vec::<ArkG1Projective>::deserialize_uncompressed(&buf[..]).unwrap();
+    let mut expected = Vec::new();
+    ArkG1Projective::normalize_batch(&ark_points)
+        .serialize_uncompressed(&mut expected)
+        .unwrap();
+
+    let mut res = Vec::new();
+    crate::g1::normalize_batch_g1(&points)
+        .serialize_uncompressed(&mut res)
+        .unwrap();
+    assert_eq!(res, expected);
+
+    // A failing hook falls back to the in-Rust conversion.
+    let points: Vec<crate::G1Projective<crate::PhantomHooks>> =
+        Vec::deserialize_uncompressed_unchecked(&buf[..]).unwrap();
+    let mut res = Vec::new();
+    crate::g1::normalize_batch_g1(&points)
+        .serialize_uncompressed(&mut res)
+        .unwrap();
+    assert_eq!(res, expected);
+}
+
+fn fq_from_hex(s: &str) -> Fq {
+    use ark_ff::PrimeField;
+    Fq::from_be_bytes_mod_order(&decode(s).unwrap())
+}
+
+#[test]
+fn hash_to_curve_matches_rfc_vectors() {
+    use crate::hashing::{hash_to_g1, hash_to_g2};
+
+    // From RFC 9380 appendix J.9.1 and J.10.1, `msg = "abc"`.
+    let p = hash_to_g1::<TestHooks>(
+        b"abc",
+        b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_",
+    )
+    .unwrap();
+    let expected = G1Affine::new(
+        fq_from_hex("03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903"),
+        fq_from_hex("0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d"),
+    );
+    assert_eq!(p, expected);
+
+    let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+    // Through the hook, then in-Rust.
+    let p = hash_to_g2::<TestHooks>(b"abc", dst).unwrap();
+    assert_eq!(
+        p,
+        crate::hashing::hash_to_g2_default::<TestHooks>(b"abc", dst).unwrap()
+    );
+    let expected = G2Affine::new(
+        Fq2::new(
+            fq_from_hex("02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6"),
+            fq_from_hex("139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8"),
+        ),
+        Fq2::new(
+            fq_from_hex("1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48"),
+            fq_from_hex("00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16"),
+        ),
+    );
+    assert_eq!(p, expected);
+}
+
+// Test vectors from the Ethereum consensus specs BLS tests.
+const PUBKEYS: [&str; 3] = [
+    "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a",
+    "b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81",
+    "b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f",
+];
+const MESSAGES: [[u8; 32]; 3] = [[0x00; 32], [0x56; 32], [0xab; 32]];
+
+fn g1_from_hex(s: &str) -> G1Affine {
+    G1Affine::deserialize_compressed(&decode(s).unwrap()[..]).unwrap()
+}
+
+fn g2_from_hex(s: &str) -> G2Affine {
+    G2Affine::deserialize_compressed(&decode(s).unwrap()[..]).unwrap()
+}
+
+#[test]
+fn bls_signature_verify_min_pk() {
+    use crate::signatures::{verify, MinPk, Scheme};
+
+    let pubkey = g1_from_hex(PUBKEYS[2]);
+    let sig = g2_from_hex("ae82747ddeefe4fd64cf9cedb9b04ae3e8a43420cd255e3c7cd06a8d88b7c7f8638543719981c5d16fa3527c468c25f0026704a6951bde891360c7e8d12ddee0559004ccdbe6046b55bae1b257ee97f7cdb955773d7cf29adf3ccbb9975e4eb9");
+    assert!(verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[2],
+        sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+    assert!(!verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[2],
+        sig,
+        MinPk::DST_BASIC,
+        Scheme::Basic
+    ));
+    assert!(!verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[1],
+        sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Signature of the same message by another key.
+    let sig = g2_from_hex("9674e2228034527f4c083206032b020310face156d4a4685e2fcaec2f6f3665aa635d90347b6ce124eb879266b1e801d185de36a0a289b85e9039662634f2eea1e02e670bc7ab849d006a70b2f93b84597558a05b879c8d445f387a5d5b653df");
+    assert!(!verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[2],
+        sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Identity public key and signature.
+    assert!(!verify::<MinPk, _>(
+        G1Affine::zero(),
+        &MESSAGES[2],
+        G2Affine::zero(),
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Identity signature alone.
+    assert!(!verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[2],
+        G2Affine::zero(),
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+}
+
+#[test]
+fn bls_signature_verify_aggregate_min_pk() {
+    use crate::signatures::{verify_aggregate, MinPk, Scheme};
+
+    let pubkeys: Vec<G1Affine> = PUBKEYS.iter().map(|pk| g1_from_hex(pk)).collect();
+    let msgs: Vec<&[u8]> = MESSAGES.iter().map(|msg| &msg[..]).collect();
+    let agg_sig = g2_from_hex("9104e74b9dfd3ad502f25d6a5ef57db0ed7d9a0e00f3500586d8ce44231212542fcfaf87840539b398bf07626705cf1105d246ca1062c6c2e1a53029a0f790ed5e3cb1f52f8234dc5144c45fc847c0cd37a92d68e7c5ba7c648a8a339f171244");
+    assert!(verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &msgs,
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Swapped messages.
+    let swapped = [msgs[1], msgs[0], msgs[2]];
+    assert!(!verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &swapped,
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Identity public key.
+    let mut with_identity = pubkeys.clone();
+    with_identity.push(G1Affine::zero());
+    let mut more_msgs = msgs.clone();
+    more_msgs.push(&[0x12; 32]);
+    assert!(!verify_aggregate::<MinPk, _>(
+        &with_identity,
+        &more_msgs,
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Empty and length mismatched inputs.
+    assert!(!verify_aggregate::<MinPk, _>(
+        &[],
+        &[],
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+    assert!(!verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &msgs[..2],
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+}
+
+#[test]
+fn from_x_enforces_subgroup_membership() {
+    let g1 = G1Affine::generator();
+    let greatest = g1.y > -g1.y;
+    assert_eq!(crate::g1::from_x::<TestHooks>(g1.x, greatest), Some(g1));
+    assert_eq!(crate::g1::from_x::<TestHooks>(g1.x, !greatest), Some(-g1));
+
+    let g2 = G2Affine::generator();
+    let greatest = g2.y > -g2.y;
+    assert_eq!(crate::g2::from_x::<TestHooks>(g2.x, greatest), Some(g2));
+    assert_eq!(crate::g2::from_x::<TestHooks>(g2.x, !greatest), Some(-g2));
+
+    // The G1 points with `x = 0` are on the curve but have order 3.
+    assert!(G1Affine::get_point_from_x_unchecked(Fq::zero(), true).is_some());
+    assert_eq!(crate::g1::from_x::<TestHooks>(Fq::zero(), true), None);
+    // The G2 points with `x = 4` are on the curve but not in the subgroup.
+    assert!(G2Affine::get_point_from_x_unchecked(Fq2::from(4), true).is_some());
+    assert_eq!(crate::g2::from_x::<TestHooks>(Fq2::from(4), true), None);
+}
+
+#[test]
+fn native_msm_serial_matches_parallel() {
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..1024).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..1024).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = G1Config::msm(&bases, &scalars).unwrap();
+    let serial = test_utils::msm_native_sw_serial(&bases, &scalars).unwrap();
+    assert_eq!(serial, expected);
+    // Runs on multiple threads with `--features test-utils/parallel`.
+    let parallel = test_utils::msm_native_sw(&bases, &scalars).unwrap();
+    assert_eq!(parallel, serial);
+
+    assert!(test_utils::msm_native_sw(&bases, &scalars[1..]).is_err());
+}
+
+#[test]
+fn g2_twist_type_aware_encoding() {
+    use crate::util::{read_g2_compressed, read_g2_uncompressed, serialize_fq2};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_models_ext::bls12::TwistType;
+
+    let generator = G2Affine::generator();
+
+    // The second entry of the ZCash vectors is the generator, using the `M` layout.
+    let vectors: &[u8] = include_bytes!("g2_compressed_valid_test_vectors.dat");
+    let m_bytes = &vectors[96..192];
+    let p = read_g2_compressed::<_, TestHooks>(m_bytes, &TwistType::M).unwrap();
+    assert_eq!(p, generator);
+    let vectors: &[u8] = include_bytes!("g2_uncompressed_valid_test_vectors.dat");
+    let p = read_g2_uncompressed::<_, TestHooks>(&vectors[192..384], &TwistType::M).unwrap();
+    assert_eq!(p, generator);
+
+    // The `D` layout puts `c1` first as well, as EIP-197 does for BN254.
+    let p = read_g2_compressed::<_, TestHooks>(m_bytes, &TwistType::D).unwrap();
+    assert_eq!(p, generator);
+    let m_x = serialize_fq2(generator.x, &TwistType::M);
+    assert_eq!(serialize_fq2(generator.x, &TwistType::D), m_x);
+    assert_eq!(m_x[..48], generator.x.c1.into_bigint().to_bytes_be()[..]);
+
+    let mut d_bytes = [0u8; 192];
+    d_bytes[..96].copy_from_slice(&serialize_fq2(generator.x, &TwistType::D));
+    d_bytes[96..].copy_from_slice(&serialize_fq2(generator.y, &TwistType::D));
+    let p = read_g2_uncompressed::<_, TestHooks>(&d_bytes[..], &TwistType::D).unwrap();
+    assert_eq!(p, generator);
+}
+
+#[test]
+fn decode_points_matches_per_chunk_decoding() {
+    use crate::codec::{decode_g1_points, decode_g2_points};
+
+    fn check<A, F>(points: &[A], decode: F)
+    where
+        A: AffineRepr,
+        F: Fn(&[u8], usize, Compress) -> Result<Vec<A>, ()>,
+    {
+        for compress in [Compress::Yes, Compress::No] {
+            let size = points[0].serialized_size(compress);
+            let mut bytes = Vec::new();
+            for p in points {
+                p.serialize_with_mode(&mut bytes, compress).unwrap();
+            }
+            let expected: Vec<A> = bytes
+                .chunks(size)
+                .map(|chunk| A::deserialize_with_mode(chunk, compress, Validate::Yes).unwrap())
+                .collect();
+            assert_eq!(decode(&bytes, points.len(), compress).unwrap(), expected);
+            assert_eq!(expected, points);
+
+            // Count not matching the input length.
+            assert!(decode(&bytes, points.len() - 1, compress).is_err());
+            assert!(decode(&bytes[1..], points.len(), compress).is_err());
+        }
+    }
+
+    let mut rng = test_rng();
+    let mut g1: Vec<G1Affine> = (0..8).map(|_| G1Affine::rand(&mut rng)).collect();
+    g1.push(G1Affine::zero());
+    let mut g2: Vec<G2Affine> = (0..8).map(|_| G2Affine::rand(&mut rng)).collect();
+    g2.push(G2Affine::zero());
+    check(&g1, decode_g1_points::<TestHooks>);
+    check(&g2, decode_g2_points::<TestHooks>);
+}
+
+// The IETF draft has no signature vectors for the minimal-signature-size variant,
+// these ones are generated with the `blst` reference implementation.
+const MIN_SIG_PUBKEYS: [&str; 3] = [
+    "91e7b522a7ae4b2d3e16d4987b77dfa991d631a064659e54a50530b3d80e305b64bf98ee5949cd39f91d79a50f1f61f21595a66c8b5235034c49a11fb83c71653a9c65e63dc0af38e17644a3279521a6bda2f6142737c16e5d7265940978c375",
+    "b6887cfed456ad1170ca45872a0090fc7ca110d6e962d9bef2975f9544904b086e625245939a5db15b82c6056ecea8d404e6b5437b8daefca2479f8e21e4d46ee24185cfceab99beb5564eb484f5199801e6024253b46d60cb9507367a57e72e",
+    "a1ec3954a8b3d35cc3b7812cb5bd8eacf126e7e57f5ffd53bdf897e6db7b388b1e2aa25d21c298ca2f284e37ec6e25a4041c46a2778f554b752f710c98d2f3a97f3adba221dceba9203c10e79617dabe1ae523ede259a9b0d5837684285f1d6f",
+];
+const MIN_SIG_MESSAGES: [&[u8]; 3] = [b"", b"abc", &[0x56; 32]];
+const MIN_SIG_SIGNATURES: [&str; 3] = [
+    "86c62c52efa03a940c2e0655fc4733cdab6622e9f136e300095f8116c7743c2e7ed2884f0073be52a877d7a2cd4e25cb",
+    "951c7bac88a81da4865e2c8d9fc25e4488ea6702fea5c55dcf6d100bc4f0bccd190d688d2d7b4fa5199187d42d8efae8",
+    "98191d663cf0f52b49d63dbb33a9c2b3c92ce9472129520f7c120e02519e595a15cff96254609a88ea55c18e0f07ea79",
+];
+
+#[test]
+fn bls_signature_verify_min_sig() {
+    use crate::signatures::{verify, MinSig, Scheme};
+
+    for i in 0..3 {
+        let pubkey = g2_from_hex(MIN_SIG_PUBKEYS[i]);
+        let sig = g1_from_hex(MIN_SIG_SIGNATURES[i]);
+        let msg = MIN_SIG_MESSAGES[i];
+        assert!(verify::<MinSig, _>(
+            pubkey,
+            msg,
+            sig,
+            MinSig::DST_POP,
+            Scheme::ProofOfPossession
+        ));
+        assert!(!verify::<MinSig, _>(
+            pubkey,
+            msg,
+            sig,
+            MinSig::DST_BASIC,
+            Scheme::Basic
+        ));
+        let other = MIN_SIG_MESSAGES[(i + 1) % 3];
+        assert!(!verify::<MinSig, _>(
+            pubkey,
+            other,
+            sig,
+            MinSig::DST_POP,
+            Scheme::ProofOfPossession
+        ));
+    }
+}
+
+#[test]
+fn bls_signature_verify_aggregate_min_sig() {
+    use crate::signatures::{verify_aggregate, MinSig, Scheme};
+
+    let pubkeys: Vec<G2Affine> = MIN_SIG_PUBKEYS.iter().map(|pk| g2_from_hex(pk)).collect();
+    let agg_sig = g1_from_hex("b1703fdfb19da915e2f764da6abaf71718daa1ed5444c27f0180f8afdf1ad1eece1313d8d346cb81b0150b7d2e04b347");
+    assert!(verify_aggregate::<MinSig, _>(
+        &pubkeys,
+        &MIN_SIG_MESSAGES,
+        agg_sig,
+        MinSig::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    let swapped = [
+        MIN_SIG_MESSAGES[1],
+        MIN_SIG_MESSAGES[0],
+        MIN_SIG_MESSAGES[2],
+    ];
+    assert!(!verify_aggregate::<MinSig, _>(
+        &pubkeys,
+        &swapped,
+        agg_sig,
+        MinSig::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+}
+
+#[test]
+#[allow(deprecated)]
+fn multi_miller_loop_2_matches_independent_loops() {
+    let mut rng = test_rng();
+    let a1: Vec<G1Affine> = (0..3).map(|_| G1Affine::rand(&mut rng)).collect();
+    let b1: Vec<G2Affine> = (0..3).map(|_| G2Affine::rand(&mut rng)).collect();
+    let a2: Vec<G1Affine> = (0..2).map(|_| G1Affine::rand(&mut rng)).collect();
+    let b2: Vec<G2Affine> = (0..2).map(|_| G2Affine::rand(&mut rng)).collect();
+
+    let (res1, res2) = crate::multi_miller_loop_2(a1.clone(), b1.clone(), a2.clone(), b2.clone());
+    assert_eq!(res1, Bls12_381::multi_miller_loop(a1, b1));
+    assert_eq!(res2, Bls12_381::multi_miller_loop(a2, b2));
+
+    type Hooks = crate::PhantomHooks;
+    let g1 = [crate::G1Affine::<Hooks>::generator()];
+    let g2 = [crate::G2Affine::<Hooks>::generator()];
+    let (res1, res2) = crate::multi_miller_loop_2(g1, g2, g1, g2);
+    assert!(res1.0.is_zero() && res2.0.is_zero());
+}
+
+#[test]
+fn deserialize_exact_rejects_trailing_bytes() {
+    use crate::codec::deserialize_exact;
+    use ark_serialize::SerializationError;
+
+    fn check<A: AffineRepr>(p: A) {
+        for compress in [Compress::Yes, Compress::No] {
+            let mut bytes = Vec::new();
+            p.serialize_with_mode(&mut bytes, compress).unwrap();
+            let res: A = deserialize_exact(&bytes[..], compress, Validate::Yes).unwrap();
+            assert_eq!(res, p);
+
+            bytes.push(0);
+            let res = deserialize_exact::<A, _>(&bytes[..], compress, Validate::Yes);
+            assert!(matches!(res, Err(SerializationError::InvalidData)));
+            // The non strict variant ignores the trailing byte.
+            assert_eq!(
+                A::deserialize_with_mode(&bytes[..], compress, Validate::Yes).unwrap(),
+                p
+            );
+        }
+    }
+
+    let mut rng = test_rng();
+    check(G1Affine::rand(&mut rng));
+    check(G2Affine::rand(&mut rng));
+}
+
+#[test]
+fn cost_model_is_monotonic_and_stable() {
+    use crate::cost::*;
+
+    let sizes = [0, 1, 2, 16, 31, 32, 100, 1000, 10_000, 100_000];
+    for w in sizes.windows(2) {
+        assert!(msm_g1_cost(w[0]) < msm_g1_cost(w[1]));
+        assert!(msm_g2_cost(w[0]) < msm_g2_cost(w[1]));
+        assert!(multi_miller_loop_cost(w[0]) < multi_miller_loop_cost(w[1]));
+        assert!(pairing_cost(w[0]) < pairing_cost(w[1]));
+    }
+    for n in sizes {
+        assert!(msm_g1_cost(n) <= msm_g2_cost(n));
+        assert!(multi_miller_loop_cost(n) <= pairing_cost(n));
+    }
+
+    // Reference values.
+    assert_eq!(msm_g1_cost(1), 17_425);
+    assert_eq!(msm_g1_cost(64), 92_346);
+    assert_eq!(msm_g1_cost(1024), 654_842);
+    assert_eq!(msm_g2_cost(1024), 1_964_526);
+    assert_eq!(mul_g1_cost(), 2_927);
+    assert_eq!(mul_g2_cost(), 8_781);
+    assert_eq!(pairing_cost(1), 14_898);
+    assert_eq!(pairing_cost(2), 20_028);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn secret_scalar_multiplies_and_encodes() {
+    use crate::secret::SecretScalar;
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let scalar = Fr::rand(&mut rng);
+    let mut secret = SecretScalar::<TestHooks>::new(scalar);
+
+    let g1 = G1Projective::rand(&mut rng);
+    assert_eq!(secret.mul_g1(&g1), g1 * scalar);
+    let g2 = G2Projective::rand(&mut rng);
+    assert_eq!(secret.mul_g2(&g2), g2 * scalar);
+
+    let encoded = secret.encode();
+    assert_eq!(Fr::from_le_bytes_mod_order(&encoded), scalar);
+}
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn scratch_hooks_input_is_wiped() {
+    use crate::codec::SerializeScratch;
+
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..4).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let usage = G1Config::USAGE;
+    // The test hooks encode the inputs into the scratch, as a host forwarding
+    // implementation does.
+    let encoded = bases.serialized_size(usage.compress) + scalars.serialized_size(usage.compress);
+
+    let mut scratch = SerializeScratch::new();
+    let res = G1Config::msm_with_scratch(&bases, &scalars, &mut scratch).unwrap();
+    assert_eq!(res, G1Config::msm(&bases, &scalars).unwrap());
+    assert!(scratch.capacity() >= encoded);
+    assert!(scratch.allocation(encoded).iter().all(|b| *b == 0));
+
+    let base = G1Projective::rand(&mut rng);
+    let limbs = test_utils::scalar_limbs::<G1Config>(&scalars[0]);
+    let encoded = base.serialized_size(usage.compress) + limbs.serialized_size(usage.compress);
+    let res = G1Config::mul_projective_with_scratch(&base, &limbs, &mut scratch);
+    assert_eq!(res, base * scalars[0]);
+    assert!(scratch.allocation(encoded).iter().all(|b| *b == 0));
+}
+
+#[test]
+fn msm_into_affine_matches_msm() {
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..10).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..10).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = G1Config::msm(&bases, &scalars).unwrap().into_affine();
+    assert_eq!(
+        crate::g1::msm_into_affine(&bases, &scalars).unwrap(),
+        expected
+    );
+    assert_eq!(crate::g1::msm_into_affine(&bases, &scalars[..4]), Err(4));
+}
+
+#[test]
+fn typed_scalar_limbs_order() {
+    use crate::scalar::{mul_g1, mul_g2, Scalar};
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let fr = Fr::rand(&mut rng);
+    let le = fr.into_bigint().0;
+    let be = [le[3], le[2], le[1], le[0]];
+    assert_eq!(Scalar::from(fr), Scalar::from_le_limbs(le));
+    assert_eq!(Scalar::from(fr), Scalar::from_be_limbs(be));
+    assert_eq!(Scalar::from(fr), Scalar::from(fr.into_bigint()));
+
+    let g1 = G1Projective::rand(&mut rng);
+    assert_eq!(mul_g1(&g1, fr.into()), g1 * fr);
+    assert_eq!(mul_g1(&g1, Scalar::from_be_limbs(be)), g1 * fr);
+    let g2 = G2Projective::rand(&mut rng);
+    assert_eq!(mul_g2(&g2, fr.into()), g2 * fr);
+    assert_eq!(mul_g2(&g2, Scalar::from_be_limbs(be)), g2 * fr);
+
+    // Small scalar given as manual limbs.
+    assert_eq!(
+        mul_g1(&g1, Scalar::from_be_limbs([0, 0, 0, 5])),
+        g1 * Fr::from(5)
+    );
+}
+
+#[test]
+fn gt_compression_round_trip() {
+    use crate::gt::{compress_gt, decompress_gt, GT_COMPRESSED_SIZE};
+    use ark_bls12_381::Fq6;
+
+    let mut rng = test_rng();
+    let p = G1Projective::rand(&mut rng);
+    let q = G2Projective::rand(&mut rng);
+    let gt = Bls12_381::pairing(p, q);
+    assert_eq!(gt.uncompressed_size(), 2 * GT_COMPRESSED_SIZE);
+
+    let compressed = compress_gt(&gt);
+    assert_eq!(compressed.len(), 288);
+    assert_eq!(decompress_gt::<TestHooks>(&compressed).unwrap(), gt);
+
+    let identity = PairingOutput::<Bls12_381>::zero();
+    assert_eq!(
+        decompress_gt::<TestHooks>(&compress_gt(&identity)).unwrap(),
+        identity
+    );
+    let product = gt + Bls12_381::pairing(p + p, q);
+    assert_eq!(
+        decompress_gt::<TestHooks>(&compress_gt(&product)).unwrap(),
+        product
+    );
+
+    // Torus elements outside of GT are rejected.
+    let mut bytes = [0; GT_COMPRESSED_SIZE];
+    Fq6::one().serialize_uncompressed(&mut bytes[..]).unwrap();
+    assert!(decompress_gt::<TestHooks>(&bytes).is_err());
+    assert!(decompress_gt::<TestHooks>(&compressed[1..]).is_err());
+}
+
+#[test]
+fn multi_pairing_generic_matches_two_step() {
+    let mut rng = test_rng();
+    let g1: Vec<_> = (0..3).map(|_| G1Affine::rand(&mut rng)).collect();
+    let g2: Vec<_> = (0..3).map(|_| G2Affine::rand(&mut rng)).collect();
+    let prepared = || {
+        (
+            g1.iter().map(<Bls12_381 as Pairing>::G1Prepared::from),
+            g2.iter().map(<Bls12_381 as Pairing>::G2Prepared::from),
+        )
+    };
+
+    let (a, b) = prepared();
+    let fused = test_utils::multi_pairing_generic::<Bls12_381, ArkBls12_381>(a, b).unwrap();
+    let (a, b) = prepared();
+    let f = test_utils::multi_miller_loop_generic::<Bls12_381, ArkBls12_381>(a, b).unwrap();
+    let two_step = test_utils::final_exponentiation_generic::<Bls12_381, ArkBls12_381>(f).unwrap();
+    assert_eq!(fused.0, two_step);
+    assert_eq!(fused, Bls12_381::multi_pairing(g1, g2));
+}
+
+#[test]
+fn pairing_with_prepared_matches_affine_pairing() {
+    use crate::{pairing_with_prepared, CachedG2Prepared};
+
+    let mut rng = test_rng();
+    let pubkey = G2Affine::rand(&mut rng);
+    let prepared = CachedG2Prepared::new(&pubkey);
+    let g2 = [prepared.clone(), CachedG2Prepared::new(&G2Affine::zero())];
+    for _ in 0..3 {
+        let g1 = [G1Affine::rand(&mut rng), G1Affine::rand(&mut rng)];
+        assert_eq!(
+            pairing_with_prepared(&g1, &g2).unwrap(),
+            Bls12_381::multi_pairing(g1, [pubkey, G2Affine::zero()])
+        );
+        assert_eq!(
+            pairing_with_prepared(&g1[..1], ark_std::slice::from_ref(&prepared)).unwrap(),
+            Bls12_381::pairing(g1[0], pubkey)
+        );
+    }
+    let g1 = G1Affine::rand(&mut rng);
+    assert!(TestHooks::bls12_381_multi_miller_loop_prepared(&[g1, g1], &[prepared]).is_err());
+}
+
+#[test]
+fn hex_and_base64_point_codec() {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let mut rng = test_rng();
+    let g1 = G1Affine::rand(&mut rng);
+    let g2 = G2Affine::rand(&mut rng);
+
+    let hex1 = crate::g1::to_hex(&g1);
+    assert!(hex1.starts_with("0x"));
+    assert_eq!(hex1.len(), 2 + 2 * 48);
+    assert_eq!(crate::g1::from_hex::<TestHooks>(&hex1), Ok(g1));
+    assert_eq!(crate::g1::from_hex::<TestHooks>(&hex1[2..]), Ok(g1));
+    let hex2 = crate::g2::to_hex(&g2);
+    assert_eq!(hex2.len(), 2 + 2 * 96);
+    assert_eq!(
+        crate::g2::from_hex::<TestHooks>(&hex2.to_uppercase().replace("0X", "0x")),
+        Ok(g2)
+    );
+
+    let mut bytes = Vec::new();
+    g1.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        crate::g1::from_base64::<TestHooks>(&STANDARD.encode(&bytes)),
+        Ok(g1)
+    );
+    let mut bytes = Vec::new();
+    g2.serialize_compressed(&mut bytes).unwrap();
+    assert_eq!(
+        crate::g2::from_base64::<TestHooks>(&STANDARD.encode(&bytes)),
+        Ok(g2)
+    );
+
+    // Malformed strings.
+    assert!(crate::g1::from_hex::<TestHooks>("0xzz").is_err());
+    assert!(crate::g1::from_hex::<TestHooks>(&hex1[..hex1.len() - 1]).is_err());
+    assert!(crate::g1::from_base64::<TestHooks>("not base64!").is_err());
+    // Wrong lengths.
+    assert!(crate::g1::from_hex::<TestHooks>(&hex1[..hex1.len() - 2]).is_err());
+    assert!(crate::g1::from_hex::<TestHooks>(&format!("{hex1}00")).is_err());
+    assert!(crate::g2::from_hex::<TestHooks>(&hex1).is_err());
+    assert!(crate::g1::from_base64::<TestHooks>(&STANDARD.encode(&bytes)).is_err());
+}
+
+#[test]
+fn g1_subgroup_check_methods_agree() {
+    use crate::g1::{is_in_subgroup_with, SubgroupCheck};
+
+    let mut rng = test_rng();
+    for _ in 0..4 {
+        let p = G1Affine::rand(&mut rng);
+        assert!(is_in_subgroup_with(&p, SubgroupCheck::Endomorphism));
+        assert!(is_in_subgroup_with(&p, SubgroupCheck::Exact));
+    }
+    assert!(is_in_subgroup_with(&G1Affine::zero(), SubgroupCheck::Exact));
+
+    let mut off = 0;
+    while off < 4 {
+        let x = Fq::rand(&mut rng);
+        let Some(p) = G1Affine::get_point_from_x_unchecked(x, rng.gen()) else {
+            continue;
+        };
+        let exact = is_in_subgroup_with(&p, SubgroupCheck::Exact);
+        assert_eq!(is_in_subgroup_with(&p, SubgroupCheck::Endomorphism), exact);
+        assert_eq!(p.is_in_correct_subgroup_assuming_on_curve(), exact);
+        off += !exact as usize;
+    }
+}
+
+#[test]
+fn validate_on_curve_reports_first_bad_index() {
+    use crate::{g1::validate_on_curve_g1, g2::validate_on_curve_g2};
+
+    let mut rng = test_rng();
+    let mut g1: Vec<_> = (0..5).map(|_| G1Affine::rand(&mut rng)).collect();
+    g1.push(G1Affine::zero());
+    assert_eq!(validate_on_curve_g1(&g1), Ok(()));
+    g1[3].y += Fq::one();
+    assert_eq!(validate_on_curve_g1(&g1), Err(3));
+    g1[4].x += Fq::one();
+    assert_eq!(validate_on_curve_g1(&g1), Err(3));
+
+    let mut g2: Vec<_> = (0..5).map(|_| G2Affine::rand(&mut rng)).collect();
+    g2.push(G2Affine::zero());
+    assert_eq!(validate_on_curve_g2(&g2), Ok(()));
+    g2[2] = G2Affine::new_unchecked(g2[2].x, g2[2].x);
+    assert_eq!(validate_on_curve_g2(&g2), Err(2));
+    assert_eq!(validate_on_curve_g2::<TestHooks>(&[]), Ok(()));
+}
+
+#[test]
+fn codec_projective_and_scalar_round_trip() {
+    use crate::codec::*;
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let p1 = G1Projective::rand(&mut rng);
+    let bytes = encode_projective_g1(&p1);
+    assert_eq!(bytes.len(), 96);
+    assert_eq!(decode_projective_g1::<TestHooks>(&bytes), Ok(p1));
+    assert!(decode_projective_g1::<TestHooks>(&bytes[1..]).is_err());
+    let zero = G1Projective::zero();
+    assert_eq!(decode_projective_g1(&encode_projective_g1(&zero)), Ok(zero));
+
+    let p2 = G2Projective::rand(&mut rng);
+    let bytes = encode_projective_g2(&p2);
+    assert_eq!(bytes.len(), 192);
+    assert_eq!(decode_projective_g2::<TestHooks>(&bytes), Ok(p2));
+    assert!(decode_projective_g2::<TestHooks>(&[bytes, vec![0]].concat()).is_err());
+
+    let fr = Fr::rand(&mut rng);
+    let limbs = fr.into_bigint().0;
+    let bytes = encode_scalar_limbs(&limbs);
+    assert_eq!(bytes.len(), 8 + 4 * 8);
+    assert_eq!(&bytes[8..16], &limbs[0].to_le_bytes());
+    assert_eq!(decode_scalar_limbs(&bytes), Ok(limbs.to_vec()));
+
+    let scalars: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+    let bytes = encode_scalars(&scalars);
+    assert_eq!(bytes.len(), 8 + 3 * 32);
+    assert_eq!(decode_scalars(&bytes), Ok(scalars));
+    let mut bytes = encode_scalars(&[Fr::zero()]);
+    bytes[8..].fill(0xff);
+    assert!(decode_scalars(&bytes).is_err());
+}
+
+#[test]
+fn pedersen_generators_are_stable_and_in_subgroup() {
+    use crate::hashing::pedersen_generators_g1;
+
+    const DST: &[u8] = b"ARK-EXT-PEDERSEN-TEST";
+    let generators = pedersen_generators_g1::<TestHooks>(8, DST).unwrap();
+    assert_eq!(generators.len(), 8);
+    assert_eq!(
+        pedersen_generators_g1::<TestHooks>(8, DST).unwrap(),
+        generators
+    );
+    // A shorter table is a prefix of a longer one.
+    assert_eq!(
+        pedersen_generators_g1::<TestHooks>(3, DST).unwrap(),
+        generators[..3]
+    );
+    assert_ne!(
+        pedersen_generators_g1::<TestHooks>(1, b"OTHER").unwrap()[0],
+        generators[0]
+    );
+    for (i, g) in generators.iter().enumerate() {
+        assert!(g.is_on_curve() && g.is_in_correct_subgroup_assuming_on_curve());
+        assert!(!generators[..i].contains(g));
+    }
+    assert_eq!(
+        crate::g1::to_hex(&generators[0]),
+        "0x972c09510134a2d8644913cbe5de75ff9866814063b0b71b144d4f2714b0851e3a8ab9e09bf5df39d6c663e5753b2b1a"
+    );
+}
+
+#[test]
+fn final_exponentiation_batch_matches_individual_products() {
+    type Config = crate::Config<TestHooks>;
+
+    let mut rng = test_rng();
+    let targets: Vec<_> = (0..3)
+        .map(|_| Bls12_381::miller_loop(G1Affine::rand(&mut rng), G2Affine::rand(&mut rng)))
+        .collect();
+    let expected: PairingOutput<Bls12_381> = targets
+        .iter()
+        .map(|target| Bls12_381::final_exponentiation(*target).unwrap())
+        .sum();
+    assert_eq!(Config::final_exponentiation_batch(&targets), Some(expected));
+    assert_eq!(
+        Config::final_exponentiation_batch(&[]),
+        Some(PairingOutput::zero())
+    );
+}
+
+#[test]
+fn identity_serialization_matches_upstream() {
+    fn check<T, U>(point: T, upstream: U)
+    where
+        T: CanonicalSerialize + CanonicalDeserialize + PartialEq + core::fmt::Debug,
+        U: CanonicalSerialize,
+    {
+        for compress in [Compress::Yes, Compress::No] {
+            let mut bytes = Vec::new();
+            point.serialize_with_mode(&mut bytes, compress).unwrap();
+            let mut expected = Vec::new();
+            upstream
+                .serialize_with_mode(&mut expected, compress)
+                .unwrap();
+            assert_eq!(bytes, expected);
+            assert_eq!(bytes[0] & 0x40, 0x40);
+            assert!(bytes[1..].iter().all(|b| *b == 0));
+            for validate in [Validate::Yes, Validate::No] {
+                let decoded = T::deserialize_with_mode(&bytes[..], compress, validate).unwrap();
+                assert_eq!(decoded, point);
+                // Non canonical infinity encodings are rejected.
+                for (i, bit) in [(0, 0x20), (0, 0x01), (bytes.len() - 1, 0x01)] {
+                    let mut bytes = bytes.clone();
+                    bytes[i] |= bit;
+                    assert!(T::deserialize_with_mode(&bytes[..], compress, validate).is_err());
+                }
+            }
+        }
+    }
+
+    check(G1Affine::zero(), ark_bls12_381::G1Affine::zero());
+    check(G2Affine::zero(), ark_bls12_381::G2Affine::zero());
+}
+
+#[test]
+fn counting_hooks_record_msm_calls() {
+    let _lock = CountingHooks::lock();
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..10).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..10).map(|_| Fr::rand(&mut rng)).collect();
+    let expected = G1Config::msm(&bases, &scalars).unwrap();
+
+    let counting_bases: Vec<crate::G1Affine<CountingHooks>> = bases
+        .iter()
+        .map(|p| crate::G1Affine::<CountingHooks>::new_unchecked(p.x, p.y))
+        .collect();
+    CountingHooks::reset();
+    let res = crate::g1::Config::<CountingHooks>::msm(&counting_bases, &scalars).unwrap();
+    assert_eq!(res.into_affine().xy(), expected.into_affine().xy());
+
+    let counts = CountingHooks::counts();
+    assert_eq!(counts.get("bls12_381_msm_g1"), 1);
+    assert_eq!(counts.total(), 1);
+
+    // Length mismatch is caught before reaching the host.
+    assert!(crate::g1::Config::<CountingHooks>::msm(&counting_bases, &scalars[..4]).is_err());
+    assert_eq!(CountingHooks::counts().get("bls12_381_msm_g1"), 1);
+
+    // Calls made on other threads are recorded too.
+    std::thread::scope(|s| {
+        for _ in 0..2 {
+            s.spawn(|| crate::g1::Config::<CountingHooks>::msm(&counting_bases, &scalars));
+        }
+    });
+    assert_eq!(CountingHooks::counts().get("bls12_381_msm_g1"), 3);
+}
+
+#[test]
+fn msm_rejects_length_mismatch() {
+    let _lock = CountingHooks::lock();
+    use ark_models_ext::short_weierstrass::SWCurveConfig;
+    type G1 = crate::g1::Config<CountingHooks>;
+    type G2 = crate::g2::Config<CountingHooks>;
+
+    let g1 = vec![crate::G1Affine::<CountingHooks>::generator(); 5];
+    let g2 = vec![crate::G2Affine::<CountingHooks>::generator(); 5];
+    let scalars = vec![Fr::from(3); 5];
+
+    CountingHooks::reset();
+    assert_eq!(G1::msm(&g1, &scalars[..3]), Err(3));
+    assert_eq!(G1::msm(&g1[..2], &scalars), Err(2));
+    assert_eq!(G2::msm(&g2, &scalars[..3]), Err(3));
+    assert_eq!(G2::msm(&g2[..2], &scalars), Err(2));
+    assert_eq!(crate::g1::msm_into_affine(&g1[..1], &scalars), Err(1));
+    assert_eq!(CountingHooks::counts().total(), 0);
+}
+
+#[test]
+fn msm_with_scratch_matches_msm() {
+    use crate::codec::SerializeScratch;
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let mut scratch = SerializeScratch::new();
+    for len in [10, 5, 10] {
+        let bases: Vec<G1Affine> = (0..len).map(|_| G1Affine::rand(&mut rng)).collect();
+        let scalars: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+        assert_eq!(
+            G1Config::msm_with_scratch(&bases, &scalars, &mut scratch),
+            G1Config::msm(&bases, &scalars)
+        );
+        assert_eq!(
+            G1Config::msm_with_scratch(&bases, &scalars[..2], &mut scratch),
+            Err(2)
+        );
+
+        let base = G1Projective::rand(&mut rng);
+        let scalar = scalars[0].into_bigint();
+        assert_eq!(
+            G1Config::mul_projective_with_scratch(&base, scalar.as_ref(), &mut scratch),
+            G1Config::mul_projective(&base, scalar.as_ref())
+        );
+    }
+    // The buffer grew once to fit the largest encoding.
+    let capacity = scratch.capacity();
+    let bases = vec![G1Affine::generator(); 10];
+    let _ = G1Config::msm_with_scratch(&bases, &[Fr::one(); 10], &mut scratch);
+    assert_eq!(scratch.capacity(), capacity);
+
+    let usage = G1Config::USAGE;
+    assert_eq!(
+        scratch.encode(&bases[..], usage),
+        crate::codec::encode(&bases, usage)
+    );
+}
+
+#[test]
+fn glv_mul_matches_mul_bigint() {
+    use crate::g1::mul_glv;
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let p = G1Affine::rand(&mut rng);
+    let mut scalars: Vec<Fr> = (0..20).map(|_| Fr::rand(&mut rng)).collect();
+    scalars.extend([Fr::zero(), Fr::one(), -Fr::one(), Fr::from(u64::MAX)]);
+    for k in scalars {
+        assert_eq!(mul_glv(&p, &k), p.mul_bigint(k.into_bigint()), "k = {k}");
+    }
+    assert!(mul_glv(&G1Affine::zero(), &Fr::rand(&mut rng)).is_zero());
+}
+
+#[test]
+fn gt_msm_matches_product_of_exponentiations() {
+    use crate::gt::gt_msm;
+    use ark_ff::PrimeField;
+    use ark_models_ext::VariableBaseMSM;
+
+    let mut rng = test_rng();
+    let elements: Vec<PairingOutput<Bls12_381>> = (0..6)
+        .map(|_| Bls12_381::pairing(G1Affine::rand(&mut rng), G2Affine::rand(&mut rng)))
+        .collect();
+    let scalars: Vec<Fr> = (0..6).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = elements
+        .iter()
+        .zip(&scalars)
+        .map(|(e, s)| e.0.pow(s.into_bigint()))
+        .product();
+    assert_eq!(
+        gt_msm::<TestHooks>(&elements, &scalars).unwrap(),
+        PairingOutput(expected)
+    );
+    assert_eq!(
+        gt_msm::<TestHooks>(&elements, &scalars).unwrap(),
+        PairingOutput::msm(&elements, &scalars).unwrap()
+    );
+    assert_eq!(gt_msm::<TestHooks>(&elements[..2], &scalars), Err(2));
+    assert!(gt_msm::<TestHooks>(&[], &[]).unwrap().is_zero());
+}
+
+#[test]
+fn subgroup_mul_by_x_paths_agree() {
+    let _lock = CountingHooks::lock();
+    use crate::util::{mul_by_x_hooked, mul_by_x_native};
+
+    let mut rng = test_rng();
+    let mut g1: Vec<G1Projective> = (0..8).map(|_| G1Projective::rand(&mut rng)).collect();
+    // Points on the curve outside of the subgroup.
+    g1.extend(
+        (0..8)
+            .filter_map(|_| G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), rng.gen()))
+            .map(G1Projective::from),
+    );
+    assert!(G1Projective::normalize_batch(&g1[8..])
+        .iter()
+        .any(|p| !p.is_in_correct_subgroup_assuming_on_curve()));
+    g1.push(G1Projective::zero());
+    for p in &g1 {
+        assert_eq!(
+            mul_by_x_native::<TestHooks, G1Config>(p),
+            mul_by_x_hooked::<TestHooks, G1Config>(p)
+        );
+    }
+    // The subgroup check, using whichever path is enabled, still agrees with the definition.
+    for p in G1Projective::normalize_batch(&g1) {
+        assert_eq!(
+            crate::g1::is_in_subgroup_with(&p, crate::g1::SubgroupCheck::Endomorphism),
+            crate::g1::is_in_subgroup_with(&p, crate::g1::SubgroupCheck::Exact)
+        );
+    }
+    let g2: Vec<G2Projective> = (0..8).map(|_| G2Projective::rand(&mut rng)).collect();
+    for p in &g2 {
+        assert_eq!(
+            mul_by_x_native::<TestHooks, G2Config>(p),
+            mul_by_x_hooked::<TestHooks, G2Config>(p)
+        );
+        assert!(p.into_affine().is_in_correct_subgroup_assuming_on_curve());
+    }
+
+    // Only the hooked path crosses the host.
+    let p = crate::G1Projective::<CountingHooks>::generator();
+    CountingHooks::reset();
+    let native = mul_by_x_native::<CountingHooks, crate::g1::Config<CountingHooks>>(&p);
+    assert_eq!(CountingHooks::counts().total(), 0);
+    let hooked = mul_by_x_hooked::<CountingHooks, crate::g1::Config<CountingHooks>>(&p);
+    assert_eq!(native, hooked);
+    assert_eq!(
+        CountingHooks::counts().get("bls12_381_mul_projective_g1"),
+        1
+    );
+}
+
+#[test]
+fn field_limbs_round_trip() {
+    use crate::field::{fq_from_limbs, fq_to_limbs, fr_from_limbs, fr_to_limbs};
+    use ark_ff::PrimeField;
+    use core::str::FromStr;
+
+    let mut rng = test_rng();
+    for _ in 0..10 {
+        let fr = Fr::rand(&mut rng);
+        assert_eq!(fr_from_limbs(fr_to_limbs(&fr)), fr);
+        let fq = Fq::rand(&mut rng);
+        assert_eq!(fq_from_limbs(fq_to_limbs(&fq)), fq);
+    }
+
+    // 2^64 + 5, limbs are canonical and not in Montgomery form.
+    let fr = Fr::from_str("18446744073709551621").unwrap();
+    assert_eq!(fr_to_limbs(&fr), [5, 1, 0, 0]);
+    assert_eq!(fr_from_limbs([5, 1, 0, 0]), fr);
+    let fq = Fq::from_str("18446744073709551621").unwrap();
+    assert_eq!(fq_to_limbs(&fq), [5, 1, 0, 0, 0, 0]);
+
+    // r - 1
+    let minus_one = Fr::from_str(
+        "52435875175126190479447740508185965837690552500527637822603658699938581184512",
+    )
+    .unwrap();
+    assert_eq!(minus_one, -Fr::one());
+    let mut limbs = Fr::MODULUS.0;
+    limbs[0] -= 1;
+    assert_eq!(fr_to_limbs(&minus_one), limbs);
+
+    // Values above the modulus are reduced.
+    let mut limbs = Fr::MODULUS.0;
+    limbs[0] += 7;
+    assert_eq!(fr_from_limbs(limbs), Fr::from(7));
+    assert_eq!(
+        fr_from_limbs([u64::MAX; 4]),
+        Fr::from(2).pow([256]) - Fr::one()
+    );
+}
+
+#[test]
+fn groth16_proof_serialization() {
+    use crate::groth16::Groth16Proof;
+
+    let mut rng = test_rng();
+    let proof = Groth16Proof::<TestHooks> {
+        a: G1Affine::rand(&mut rng),
+        b: G2Affine::rand(&mut rng),
+        c: G1Affine::rand(&mut rng),
+    };
+    for (compress, size) in [(Compress::Yes, 192), (Compress::No, 384)] {
+        let mut buf = vec![];
+        proof.serialize_with_mode(&mut buf, compress).unwrap();
+        assert_eq!(buf.len(), size);
+        assert_eq!(proof.serialized_size(compress), size);
+        let decoded = Groth16Proof::deserialize_with_mode(&buf[..], compress, Validate::Yes);
+        assert_eq!(decoded.unwrap(), proof);
+    }
+
+    let mut buf = vec![];
+    proof.serialize_compressed(&mut buf).unwrap();
+    // Standard layout: A, B, C.
+    let mut a = vec![];
+    proof.a.serialize_compressed(&mut a).unwrap();
+    assert_eq!(buf[..48], a[..]);
+
+    // Truncated proof.
+    assert!(Groth16Proof::<TestHooks>::deserialize_compressed(&buf[..191]).is_err());
+    // B not on the curve.
+    let mut bad = buf.clone();
+    bad[48 + 95] ^= 1;
+    assert!(Groth16Proof::<TestHooks>::deserialize_compressed(&bad[..]).is_err());
+    // C out of the prime order subgroup.
+    let c = loop {
+        if let Some(c) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            break c;
+        }
+    };
+    assert!(!c.is_in_correct_subgroup_assuming_on_curve());
+    let mut bad = buf.clone();
+    bad.truncate(144);
+    c.serialize_compressed(&mut bad).unwrap();
+    assert!(Groth16Proof::<TestHooks>::deserialize_compressed(&bad[..]).is_err());
+    assert_eq!(
+        Groth16Proof::<TestHooks>::deserialize_compressed_unchecked(&bad[..])
+            .unwrap()
+            .c,
+        c
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn g2_subgroup_batch_validation() {
+    use crate::g2::validate_subgroup_g2_batch;
+
+    let mut rng = test_rng();
+    let mut points: Vec<G2Affine> = (0..4).map(|_| G2Affine::rand(&mut rng)).collect();
+    let outside = loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!outside.is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(validate_subgroup_g2_batch(&points), Ok(()));
+    points.insert(2, outside);
+    points.push(G2Affine::zero());
+    assert_eq!(validate_subgroup_g2_batch(&points), Err(2));
+    assert_eq!(validate_subgroup_g2_batch::<TestHooks>(&[]), Ok(()));
+
+    // Batched decoding.
+    let mut buf = vec![];
+    for p in &points {
+        p.serialize_compressed(&mut buf).unwrap();
+    }
+    let decode = |buf: &[u8]| crate::codec::decode_g2_points::<TestHooks>(buf, 6, Compress::Yes);
+    assert!(decode(&buf).is_err());
+    let valid: Vec<G2Affine> = points
+        .iter()
+        .copied()
+        .filter(|p| p.is_in_correct_subgroup_assuming_on_curve())
+        .collect();
+    let mut buf = vec![];
+    for p in valid.iter().chain([&valid[0]]) {
+        p.serialize_compressed(&mut buf).unwrap();
+    }
+    assert_eq!(decode(&buf).unwrap()[..5], valid[..]);
+
+    // A failing hook falls back to the in-Rust check.
+    type Hooks = crate::PhantomHooks;
+    let p = crate::G2Affine::<Hooks>::new_unchecked(outside.x, outside.y);
+    assert_eq!(validate_subgroup_g2_batch::<Hooks>(&[p]), Err(0));
+}
+
+#[test]
+fn hex_debug_shows_compressed_encoding() {
+    use crate::codec::HexDebug;
+
+    let g1 = G1Affine::generator();
+    assert_eq!(format!("{:?}", HexDebug(&g1)), "0x97f1d3a7..db22c6bb");
+    assert_eq!(format!("{:#?}", HexDebug(&g1)), crate::g1::to_hex(&g1));
+    let g2 = G2Affine::generator();
+    assert_eq!(format!("{:?}", HexDebug(&g2)), "0x93e02b60..c121bdb8");
+    assert!(format!("{:?}", HexDebug(&G1Affine::zero())).starts_with("0xc0000000.."));
+}
+
+#[test]
+fn verify_pairing_eq_checks_identity() {
+    use crate::{is_identity, verify_pairing_eq};
+
+    let mut rng = test_rng();
+    let a = Fr::rand(&mut rng);
+    let b = Fr::rand(&mut rng);
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+
+    // e([a]G1, [b]G2) * e(-[ab]G1, G2) == 1
+    let lhs = (g1 * a).into_affine();
+    let rhs = (g2 * b).into_affine();
+    let ab = (-(g1 * (a * b))).into_affine();
+    assert!(verify_pairing_eq::<TestHooks>(&[lhs, ab], &[rhs, g2]));
+
+    let tampered = (-(g1 * (a * b + Fr::from(1)))).into_affine();
+    assert!(!verify_pairing_eq::<TestHooks>(
+        &[lhs, tampered],
+        &[rhs, g2]
+    ));
+    assert!(!verify_pairing_eq::<TestHooks>(&[lhs, ab], &[rhs]));
+
+    assert!(is_identity(&PairingOutput::<Bls12_381>::zero()));
+    assert!(!is_identity(&Bls12_381::pairing(g1, g2)));
+}
+
+#[test]
+fn msm_accumulator_matches_msm() {
+    use crate::msm::{MsmAccumulator, MSM_CHUNK_SIZE};
+
+    let mut rng = test_rng();
+    let n = 2 * MSM_CHUNK_SIZE + 7;
+    let bases: Vec<G1Affine> = (0..n).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut acc = MsmAccumulator::<TestHooks>::new();
+    bases
+        .iter()
+        .zip(&scalars)
+        .for_each(|(base, scalar)| acc.push(*base, *scalar));
+    assert_eq!(acc.finalize(), G1Config::msm(&bases, &scalars));
+
+    assert_eq!(
+        MsmAccumulator::<TestHooks>::new().finalize(),
+        Ok(G1Projective::zero())
+    );
+}
+
+#[test]
+fn unvalidated_points_require_validation() {
+    use crate::codec::{deserialize_unvalidated, validate_g2_batch, Unvalidated};
+
+    fn encode<A: AffineRepr>(p: &A) -> Vec<u8> {
+        let mut buf = Vec::new();
+        p.serialize_compressed(&mut buf).unwrap();
+        buf
+    }
+
+    let mut rng = test_rng();
+    let g1 = G1Affine::rand(&mut rng);
+    let p: Unvalidated<G1Affine> =
+        deserialize_unvalidated(&encode(&g1)[..], Compress::Yes).unwrap();
+    assert_eq!(p.validate().unwrap(), g1);
+
+    let off_subgroup = loop {
+        let x = Fq::rand(&mut rng);
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, rng.gen()) {
+            break p;
+        }
+    };
+    let p: Unvalidated<G1Affine> =
+        deserialize_unvalidated(&encode(&off_subgroup)[..], Compress::Yes).unwrap();
+    assert!(p.validate().is_err());
+
+    let mut g2: Vec<G2Affine> = (0..3).map(|_| G2Affine::rand(&mut rng)).collect();
+    let decode = |points: &[G2Affine]| -> Vec<Unvalidated<G2Affine>> {
+        points
+            .iter()
+            .map(|p| deserialize_unvalidated(&encode(p)[..], Compress::Yes).unwrap())
+            .collect()
+    };
+    assert_eq!(validate_g2_batch(decode(&g2)), Ok(g2.clone()));
+    g2[1] = loop {
+        let x = Fq2::rand(&mut rng);
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(x, rng.gen()) {
+            break p;
+        }
+    };
+    assert_eq!(validate_g2_batch(decode(&g2)), Err(1));
+}
+
+#[test]
+#[cfg(feature = "ct")]
+fn conditional_select_picks_by_choice() {
+    use subtle::Choice;
+
+    let mut rng = test_rng();
+    let (a1, b1) = (G1Affine::rand(&mut rng), G1Affine::identity());
+    assert_eq!(crate::g1::conditional_select(&a1, &b1, Choice::from(0)), a1);
+    assert_eq!(crate::g1::conditional_select(&a1, &b1, Choice::from(1)), b1);
+    assert_eq!(crate::g1::conditional_select(&b1, &a1, Choice::from(1)), a1);
+
+    let (a2, b2) = (G2Affine::rand(&mut rng), G2Affine::rand(&mut rng));
+    assert_eq!(crate::g2::conditional_select(&a2, &b2, Choice::from(0)), a2);
+    assert_eq!(crate::g2::conditional_select(&a2, &b2, Choice::from(1)), b2);
+}
+
+#[test]
+fn serialize_indexed_g1_offsets() {
+    use crate::codec::serialize_indexed_g1;
+
+    let mut rng = test_rng();
+    let mut points: Vec<G1Affine> = (0..5).map(|_| G1Affine::rand(&mut rng)).collect();
+    points[2] = G1Affine::identity();
+    for compress in [Compress::Yes, Compress::No] {
+        let (buf, offsets) = serialize_indexed_g1(&points, compress);
+        assert_eq!(offsets.len(), points.len());
+        assert_eq!(
+            buf.len(),
+            points.len() * points[0].serialized_size(compress)
+        );
+        for (i, point) in points.iter().enumerate().rev() {
+            let bytes = &buf[offsets[i]..];
+            let res = G1Affine::deserialize_with_mode(bytes, compress, Validate::Yes).unwrap();
+            assert_eq!(res, *point);
+        }
+    }
+}
+
+#[test]
+fn negate_adds_up_to_identity() {
+    use crate::{g1::negate_g1, g2::negate_g2};
+
+    let mut rng = test_rng();
+    for p in [G1Projective::rand(&mut rng), G1Projective::zero()] {
+        assert!((p + negate_g1(&p)).is_zero());
+        assert_eq!(negate_g1(&p), -p);
+    }
+    for p in [G2Projective::rand(&mut rng), G2Projective::zero()] {
+        assert!((p + negate_g2(&p)).is_zero());
+        assert_eq!(negate_g2(&p), -p);
+    }
+}
+
+#[test]
+fn random_g1_is_in_subgroup_without_host_calls() {
+    let _lock = CountingHooks::lock();
+    use crate::g1::random_g1;
+
+    let mut rng = test_rng();
+    CountingHooks::reset();
+    let points: Vec<crate::G1Projective<CountingHooks>> =
+        (0..4).map(|_| random_g1(&mut rng)).collect();
+    assert_eq!(CountingHooks::counts().total(), 0);
+
+    for p in points {
+        let p = G1Affine::new_unchecked(p.into_affine().x, p.into_affine().y);
+        assert!(!p.is_zero());
+        assert!(p.is_on_curve());
+        assert!(p.is_in_correct_subgroup_assuming_on_curve());
+    }
+}
+
+#[test]
+fn try_from_bytes_detects_compression() {
+    use ark_serialize::SerializationError;
+
+    fn check<A: AffineRepr>(p: A, try_from_bytes: fn(&[u8]) -> Result<A, SerializationError>) {
+        let mut compressed = Vec::new();
+        p.serialize_compressed(&mut compressed).unwrap();
+        let mut uncompressed = Vec::new();
+        p.serialize_uncompressed(&mut uncompressed).unwrap();
+
+        assert_eq!(try_from_bytes(&compressed).unwrap(), p);
+        assert_eq!(try_from_bytes(&uncompressed).unwrap(), p);
+        for len in [
+            0,
+            compressed.len() - 1,
+            compressed.len() + 1,
+            uncompressed.len() + 1,
+        ] {
+            let mut bytes = uncompressed.clone();
+            bytes.resize(len, 0);
+            assert!(try_from_bytes(&bytes).is_err());
+        }
+    }
+
+    let mut rng = test_rng();
+    check(G1Affine::rand(&mut rng), crate::g1::try_from_bytes);
+    check(G1Affine::identity(), crate::g1::try_from_bytes);
+    check(G2Affine::rand(&mut rng), crate::g2::try_from_bytes);
+    check(G2Affine::identity(), crate::g2::try_from_bytes);
+}
+
+#[test]
+fn groth16_verify() {
+    use crate::groth16::{verify, Groth16Proof, VerifyingKey};
+
+    let mut rng = test_rng();
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let [alpha, beta, gamma, delta, a, b] = [(); 6].map(|_| Fr::rand(&mut rng));
+    let l: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+    let inputs: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+
+    let vk = VerifyingKey::<TestHooks> {
+        alpha_g1: (g1 * alpha).into_affine(),
+        beta_g2: (g2 * beta).into_affine(),
+        gamma_g2: (g2 * gamma).into_affine(),
+        delta_g2: (g2 * delta).into_affine(),
+        gamma_abc_g1: l.iter().map(|l| (g1 * l).into_affine()).collect(),
+    };
+    // Pick `C` such that `ab = αβ + Lγ + cδ` in the exponent.
+    let l_inputs = l[0] + l[1..].iter().zip(&inputs).map(|(l, x)| *l * x).sum::<Fr>();
+    let c = (a * b - alpha * beta - l_inputs * gamma) * delta.inverse().unwrap();
+    let proof = Groth16Proof {
+        a: (g1 * a).into_affine(),
+        b: (g2 * b).into_affine(),
+        c: (g1 * c).into_affine(),
+    };
+    assert!(verify(&vk, &inputs, &proof));
+
+    let mut tampered = proof;
+    tampered.c = (tampered.c + g1).into_affine();
+    assert!(!verify(&vk, &inputs, &tampered));
+    let mut wrong_inputs = inputs.clone();
+    wrong_inputs[1] += Fr::from(1);
+    assert!(!verify(&vk, &wrong_inputs, &proof));
+    assert!(!verify(&vk, &inputs[..2], &proof));
+}
+
+#[test]
+fn target_field_codec() {
+    use crate::codec::{decode_target_field, encode_target_field};
+    use ark_bls12_381::Fq12;
+
+    let mut rng = test_rng();
+    let f = Fq12::rand(&mut rng);
+    let bytes = encode_target_field(&f);
+    assert_eq!(bytes.len(), 576);
+    assert_eq!(decode_target_field(&bytes), Ok(f));
+
+    assert!(decode_target_field(&bytes[..575]).is_err());
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert!(decode_target_field(&longer).is_err());
+    // Non canonical first coefficient.
+    let mut bad = bytes;
+    bad[..48].fill(0xff);
+    assert!(decode_target_field(&bad).is_err());
+}
+
+#[test]
+fn verify_pairings_batch_preserves_order() {
+    use crate::{verify_pairing_eq, verify_pairings_batch};
+
+    let mut rng = test_rng();
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let eqs: Vec<(Vec<G1Affine>, Vec<G2Affine>)> = (0..8u64)
+        .map(|i| {
+            let a = Fr::rand(&mut rng);
+            let b = Fr::rand(&mut rng);
+            // Every third equation is off by one.
+            let ab = a * b + Fr::from((i % 3 == 1) as u64);
+            (
+                vec![(g1 * a).into_affine(), (-(g1 * ab)).into_affine()],
+                vec![(g2 * b).into_affine(), g2],
+            )
+        })
+        .collect();
+
+    let expected: Vec<bool> = (0..8).map(|i| i % 3 != 1).collect();
+    let serial: Vec<bool> = eqs
+        .iter()
+        .map(|(a, b)| verify_pairing_eq::<TestHooks>(a, b))
+        .collect();
+    assert_eq!(serial, expected);
+    assert_eq!(verify_pairings_batch::<TestHooks>(&eqs), expected);
+    assert!(verify_pairings_batch::<TestHooks>(&[]).is_empty());
+}
+
+#[test]
+fn verify_pairings_aggregated_catches_bad_equation() {
+    use crate::verify_pairings_aggregated;
+
+    let mut rng = test_rng();
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let mut eqs: Vec<(Vec<G1Affine>, Vec<G2Affine>)> = (0..10)
+        .map(|_| {
+            let a = Fr::rand(&mut rng);
+            let b = Fr::rand(&mut rng);
+            (
+                vec![(g1 * a).into_affine(), (-(g1 * (a * b))).into_affine()],
+                vec![(g2 * b).into_affine(), g2],
+            )
+        })
+        .collect();
+    assert!(verify_pairings_aggregated::<TestHooks, _>(&eqs, &mut rng));
+    assert!(verify_pairings_aggregated::<TestHooks, _>(&[], &mut rng));
+
+    eqs[6].0[1] = (eqs[6].0[1] + g1).into_affine();
+    for _ in 0..4 {
+        assert!(!verify_pairings_aggregated::<TestHooks, _>(&eqs, &mut rng));
+    }
+    eqs[6].0.pop();
+    assert!(!verify_pairings_aggregated::<TestHooks, _>(&eqs, &mut rng));
+}
+
+#[test]
+fn multi_miller_loop_skips_infinity_pairs() {
+    let mut rng = test_rng();
+    let a: Vec<G1Affine> = (0..3).map(|_| G1Affine::rand(&mut rng)).collect();
+    let b: Vec<G2Affine> = (0..3).map(|_| G2Affine::rand(&mut rng)).collect();
+    let expected = Bls12_381::multi_miller_loop(a.clone(), b.clone());
+
+    let mut a_inf = a.clone();
+    let mut b_inf = b.clone();
+    a_inf.insert(1, G1Affine::identity());
+    b_inf.insert(1, G2Affine::rand(&mut rng));
+    a_inf.push(G1Affine::rand(&mut rng));
+    b_inf.push(G2Affine::identity());
+    assert_eq!(Bls12_381::multi_miller_loop(a_inf, b_inf), expected);
+
+    let res = Bls12_381::multi_miller_loop([G1Affine::identity()], [G2Affine::identity()]);
+    let res = Bls12_381::final_exponentiation(res).unwrap();
+    assert!(res.is_zero());
+}
+
+#[test]
+#[allow(deprecated)]
+fn fr_batch_inverse_matches_inverse() {
+    use crate::field::fr_batch_inverse;
+
+    let mut rng = test_rng();
+    let mut scalars: Vec<Fr> = (0..16).map(|_| Fr::rand(&mut rng)).collect();
+    scalars[5] = Fr::zero();
+    let expected: Vec<Fr> = scalars
+        .iter()
+        .map(|s| s.inverse().unwrap_or_default())
+        .collect();
+
+    assert_eq!(fr_batch_inverse::<TestHooks>(&scalars), expected);
+    assert!(fr_batch_inverse::<TestHooks>(&[]).is_empty());
+    // A failing hook falls back to the in-Rust inversion.
+    assert_eq!(fr_batch_inverse::<crate::PhantomHooks>(&scalars), expected);
+}
+
+#[test]
+fn msm_sw_affine_generic_matches_msm_sw_generic() {
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..20).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..20).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = test_utils::msm_sw_generic::<G1Config, ArkG1Config>(&bases, &scalars)
+        .unwrap()
+        .into_affine();
+    let res = test_utils::msm_sw_affine_generic::<G1Config, ArkG1Config>(&bases, &scalars);
+    assert_eq!(res, Ok(expected));
+    assert_eq!(crate::g1::msm_into_affine(&bases, &scalars), Ok(expected));
+    assert!(test_utils::msm_sw_affine_generic::<G1Config, ArkG1Config>(&bases, &[]).is_err());
+}
+
+#[test]
+#[allow(deprecated)]
+fn batch_to_affine_g1_matches_normalize_batch() {
+    let _lock = CountingHooks::lock();
+    let mut rng = test_rng();
+    let mut points: Vec<G1Projective> = (0..100).map(|_| G1Projective::rand(&mut rng)).collect();
+    points[42] = G1Projective::zero();
+    let expected = G1Projective::normalize_batch(&points);
+
+    CountingHooks::reset();
+    let counted: Vec<crate::G1Projective<CountingHooks>> = points
+        .iter()
+        .map(|p| crate::G1Projective::new_unchecked(p.x, p.y, p.z))
+        .collect();
+    let res = crate::g1::batch_to_affine_g1(&counted);
+    assert_eq!(
+        CountingHooks::counts().get("bls12_381_batch_to_affine_g1"),
+        1
+    );
+    assert!(res
+        .iter()
+        .zip(&expected)
+        .all(|(a, b)| a.xy() == b.xy() && a.infinity == b.infinity));
+
+    // A failing hook falls back to the in-Rust conversion.
+    let phantom: Vec<crate::G1Projective<crate::PhantomHooks>> = points
+        .iter()
+        .map(|p| crate::G1Projective::new_unchecked(p.x, p.y, p.z))
+        .collect();
+    let res = crate::g1::batch_to_affine_g1(&phantom);
+    assert!(res
+        .iter()
+        .zip(&expected)
+        .all(|(a, b)| a.xy() == b.xy() && a.infinity == b.infinity));
+}
+
+/// Hooks failing every required operation, with the given `HOOK_POLICY`.
+macro_rules! failing_hooks {
+    ($name:ident, $policy:expr $(, $hook:item)* $(,)?) => {
+        struct $name;
+
+        impl CurveHooks for $name {
+            const HOOK_POLICY: crate::util::OnError = $policy;
+
+            fn bls12_381_multi_miller_loop(
+                _g1: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G1Prepared>,
+                _g2: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G2Prepared>,
+            ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+                Err(())
+            }
+
+            fn bls12_381_final_exponentiation(
+                _target: <crate::Bls12_381<Self> as Pairing>::TargetField,
+            ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+                Err(())
+            }
+
+            fn bls12_381_msm_g1(
+                _bases: &[crate::G1Affine<Self>],
+                _scalars: &[Fr],
+            ) -> Result<crate::G1Projective<Self>, ()> {
+                Err(())
+            }
+
+            fn bls12_381_msm_g2(
+                _bases: &[crate::G2Affine<Self>],
+                _scalars: &[Fr],
+            ) -> Result<crate::G2Projective<Self>, ()> {
+                Err(())
+            }
+
+            fn bls12_381_mul_projective_g1(
+                _base: &crate::G1Projective<Self>,
+                _scalar: &[u64],
+            ) -> Result<crate::G1Projective<Self>, ()> {
+                Err(())
+            }
+
+            fn bls12_381_mul_projective_g2(
+                _base: &crate::G2Projective<Self>,
+                _scalar: &[u64],
+            ) -> Result<crate::G2Projective<Self>, ()> {
+                Err(())
+            }
+
+            $($hook)*
+        }
+    };
+}
+
+/// Hooks working as `TestHooks` but for the failing ones, selected by the `FAIL_*`
+/// bits of `FAIL`, with `OnError::Default` if `DEFAULT` is set and
+/// `OnError::Propagate` otherwise.
+struct FlakyHooks<const FAIL: u32, const DEFAULT: bool>;
+
+const FAIL_MULTI_MILLER_LOOP: u32 = 1 << 0;
+const FAIL_FINAL_EXPONENTIATION: u32 = 1 << 1;
+const FAIL_MSM_G1: u32 = 1 << 2;
+const FAIL_MSM_G2: u32 = 1 << 3;
+const FAIL_MUL_G1: u32 = 1 << 4;
+const FAIL_MUL_G2: u32 = 1 << 5;
+const FAIL_HASH_TO_G2: u32 = 1 << 6;
+
+impl<const FAIL: u32, const DEFAULT: bool> FlakyHooks<FAIL, DEFAULT> {
+    fn fails(hook: u32) -> Result<(), ()> {
+        if FAIL & hook == 0 {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<const FAIL: u32, const DEFAULT: bool> CurveHooks for FlakyHooks<FAIL, DEFAULT> {
+    const HOOK_POLICY: crate::util::OnError = if DEFAULT {
+        crate::util::OnError::Default
+    } else {
+        crate::util::OnError::Propagate
+    };
+
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <crate::Bls12_381<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Self::fails(FAIL_MULTI_MILLER_LOOP)?;
+        test_utils::multi_miller_loop_generic::<crate::Bls12_381<Self>, ArkBls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <crate::Bls12_381<Self> as Pairing>::TargetField,
+    ) -> Result<<crate::Bls12_381<Self> as Pairing>::TargetField, ()> {
+        Self::fails(FAIL_FINAL_EXPONENTIATION)?;
+        test_utils::final_exponentiation_generic::<crate::Bls12_381<Self>, ArkBls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[crate::G1Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        Self::fails(FAIL_MSM_G1)?;
+        test_utils::msm_sw_generic::<crate::g1::Config<Self>, ArkG1Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[crate::G2Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        Self::fails(FAIL_MSM_G2)?;
+        test_utils::msm_sw_generic::<crate::g2::Config<Self>, ArkG2Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &crate::G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        Self::fails(FAIL_MUL_G1)?;
+        test_utils::mul_projective_sw_generic::<crate::g1::Config<Self>, ArkG1Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &crate::G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        Self::fails(FAIL_MUL_G2)?;
+        test_utils::mul_projective_sw_generic::<crate::g2::Config<Self>, ArkG2Config>(base, scalar)
+    }
+
+    fn bls12_381_hash_to_g2(msg: &[u8], dst: &[u8]) -> Result<crate::G2Affine<Self>, ()> {
+        Self::fails(FAIL_HASH_TO_G2)?;
+        crate::hashing::hash_to_g2_default(msg, dst)
+    }
+}
+
+#[test]
+#[should_panic(expected = "bls12_381_msm_g1")]
+fn hook_policy_panic() {
+    failing_hooks!(Hooks, crate::util::OnError::Panic);
+    let g = crate::G1Affine::<Hooks>::generator();
+    let _ = crate::g1::Config::<Hooks>::msm(&[g], &[Fr::one()]);
+}
+
+#[test]
+#[should_panic(expected = "bls12_381_fq_sqrt")]
+fn hook_policy_panic_covers_hooks_with_fallback() {
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Panic,
+        fn bls12_381_fq_sqrt(_x: &Fq) -> Result<Option<Fq>, ()> {
+            Err(())
+        }
+    );
+    let _ = crate::field::fq_sqrt::<Hooks>(&Fq::from(4u64));
+}
+
+#[test]
+fn hook_policy_propagate_keeps_fallbacks() {
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Propagate,
+        fn bls12_381_fq_sqrt(_x: &Fq) -> Result<Option<Fq>, ()> {
+            Err(())
+        }
+    );
+    let root = crate::field::fq_sqrt::<Hooks>(&Fq::from(4u64)).unwrap();
+    assert_eq!(root.square(), Fq::from(4u64));
+}
+
+#[test]
+fn fq_sqrt_checks_reported_non_squares() {
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Propagate,
+        fn bls12_381_fq_sqrt(_x: &Fq) -> Result<Option<Fq>, ()> {
+            Ok(None)
+        }
+    );
+    let x = Fq::rand(&mut test_rng());
+    let root = crate::field::fq_sqrt::<Hooks>(&x.square()).unwrap();
+    assert_eq!(root.square(), x.square());
+    assert_eq!(crate::field::fq_sqrt::<Hooks>(&-x.square()), None);
+}
+
+#[test]
+fn hook_policy_default() {
+    failing_hooks!(Hooks, crate::util::OnError::Default);
+    let g = crate::G1Affine::<Hooks>::generator();
+    let res = crate::g1::Config::<Hooks>::msm(&[g], &[Fr::one()]);
+    assert_eq!(res, Ok(crate::G1Projective::<Hooks>::zero()));
+    assert!((g * Fr::from(3u64)).is_zero());
+    let target =
+        crate::Bls12_381::<Hooks>::multi_miller_loop([g], [crate::G2Affine::<Hooks>::generator()]);
+    assert!(target.0.is_zero());
+    let res = crate::Bls12_381::<Hooks>::final_exponentiation(target);
+    assert_eq!(res.map(|res| res.0), Some(Zero::zero()));
+}
+
+#[test]
+fn hook_policy_propagate() {
+    failing_hooks!(Hooks, crate::util::OnError::Propagate);
+    let g = crate::G1Affine::<Hooks>::generator();
+    assert_eq!(crate::g1::Config::<Hooks>::msm(&[g], &[Fr::one()]), Err(0));
+    assert!((g * Fr::from(3u64)).is_zero());
+    let target =
+        crate::Bls12_381::<Hooks>::multi_miller_loop([g], [crate::G2Affine::<Hooks>::generator()]);
+    assert!(target.0.is_zero());
+    assert!(crate::Bls12_381::<Hooks>::final_exponentiation(target).is_none());
+}
+
+fn cast_g1<H: CurveHooks>(p: G1Projective) -> crate::G1Affine<H> {
+    let p = p.into_affine();
+    crate::G1Affine::<H>::new_unchecked(p.x, p.y)
+}
+
+fn cast_g2<H: CurveHooks>(p: G2Projective) -> crate::G2Affine<H> {
+    let p = p.into_affine();
+    crate::G2Affine::<H>::new_unchecked(p.x, p.y)
+}
+
+/// Groth16 verifying key with a single public input and proof for the input `0`,
+/// computed with the in-Rust arithmetic of `TestHooks`.
+fn groth16_proof_for_zero<H: CurveHooks>() -> (
+    crate::groth16::VerifyingKey<H>,
+    crate::groth16::Groth16Proof<H>,
+) {
+    let rng = &mut test_rng();
+    let [x, y, alpha, beta, gamma, delta, a, b] = [(); 8].map(|_| Fr::rand(rng));
+    let c = (a * b - alpha * beta - x * gamma) / delta;
+    let (g, h) = (G1Projective::generator(), G2Projective::generator());
+    let vk = crate::groth16::VerifyingKey {
+        alpha_g1: cast_g1(g * alpha),
+        beta_g2: cast_g2(h * beta),
+        gamma_g2: cast_g2(h * gamma),
+        delta_g2: cast_g2(h * delta),
+        gamma_abc_g1: vec![cast_g1(g * x), cast_g1(g * y)],
+    };
+    let proof = crate::groth16::Groth16Proof {
+        a: cast_g1(g * a),
+        b: cast_g2(h * b),
+        c: cast_g1(g * c),
+    };
+    (vk, proof)
+}
+
+/// Returns `true` if any of the statements below, each forged for the default
+/// values of some failing hooks, is accepted by a verification helper with hooks `H`.
+fn accepts_forgery<H: CurveHooks>() -> bool {
+    use crate::{kzg, signatures};
+
+    let rng = &mut test_rng();
+    let [s, x] = [(); 2].map(|_| Fr::rand(rng));
+    let (g, h) = (G1Projective::generator(), G2Projective::generator());
+
+    // Non trivial pairing products, accepted for a zero Miller loop or final
+    // exponentiation taken for the identity.
+    let eq = (vec![cast_g1::<H>(g)], vec![cast_g2::<H>(h)]);
+    let mut accepted = crate::verify_pairing_eq(&eq.0, &eq.1)
+        || crate::verify_pairings_batch(ark_std::slice::from_ref(&eq))[0]
+        || crate::verify_pairings_aggregated(&[eq], rng);
+
+    // Wrong signature, accepted for a message hashed to the identity.
+    accepted |= signatures::verify::<signatures::MinPk, H>(
+        cast_g1(g * s),
+        b"msg",
+        cast_g2(h * x),
+        signatures::MinPk::DST_POP,
+        signatures::Scheme::ProofOfPossession,
+    );
+
+    // Proof for the input `0`, accepted for `1` if the combination of the inputs
+    // is the point at infinity.
+    let (vk, proof) = groth16_proof_for_zero::<H>();
+    accepted |= crate::groth16::verify(&vk, &[Fr::one()], &proof);
+
+    // `f(X) = X` opened to `7` at `5`, accepted as the opening to `0` at `0` if the
+    // scalings give the point at infinity.
+    let vk = kzg::KzgVerifyingKey::<H> {
+        g1: cast_g1(g),
+        g2: cast_g2(h),
+        tau_g2: cast_g2(h * s),
+    };
+    accepted |= kzg::verify_opening(cast_g1(g * s), vk.g1, Fr::from(5u64), Fr::from(7u64), &vk);
+    accepted
+}
+
+#[test]
+fn hook_policy_default_fails_verifications() {
+    let (vk, proof) = groth16_proof_for_zero::<FlakyHooks<0, true>>();
+    assert!(crate::groth16::verify(&vk, &[Fr::zero()], &proof));
+
+    assert!(!accepts_forgery::<FlakyHooks<0, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_MULTI_MILLER_LOOP, true>>());
+    assert!(!accepts_forgery::<
+        FlakyHooks<FAIL_FINAL_EXPONENTIATION, true>,
+    >());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_MSM_G1, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_MSM_G2, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_MUL_G1, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_MUL_G2, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<FAIL_HASH_TO_G2, true>>());
+    assert!(!accepts_forgery::<FlakyHooks<{ u32::MAX }, true>>());
+}
+
+#[test]
+fn g2_prepared_compressed_round_trip() {
+    use crate::prepared::{deserialize_g2_prepared_compressed, serialize_g2_prepared_compressed};
+    use crate::CachedG2Prepared;
+
+    let mut rng = test_rng();
+    let g1: Vec<G1Affine> = (0..2).map(|_| G1Affine::rand(&mut rng)).collect();
+    let g2 = [G2Affine::rand(&mut rng), G2Affine::zero()];
+    let prepared: Vec<CachedG2Prepared<TestHooks>> = g2.iter().map(CachedG2Prepared::new).collect();
+
+    let decoded: Vec<CachedG2Prepared<TestHooks>> = prepared
+        .iter()
+        .map(|p| {
+            let buf = serialize_g2_prepared_compressed(p);
+            // 96 bytes in place of the ~12KB of line coefficients.
+            assert_eq!(buf.len(), 96);
+            assert!(p.as_ark().uncompressed_size() > 100 * buf.len() || p.point().is_zero());
+            deserialize_g2_prepared_compressed(&buf).unwrap()
+        })
+        .collect();
+    assert_eq!(
+        crate::prepared::multi_miller_loop_prepared(&g1, &decoded),
+        crate::prepared::multi_miller_loop_prepared(&g1, &prepared),
+    );
+
+    let mut buf = serialize_g2_prepared_compressed(&prepared[0]);
+    buf.push(0);
+    assert!(deserialize_g2_prepared_compressed::<TestHooks>(&buf).is_err());
+}
+
+#[test]
+fn is_torsion_free_pins_subgroup_membership() {
+    use crate::g1::{is_in_subgroup_with, SubgroupCheck};
+
+    assert!(crate::g1::is_torsion_free(&G1Affine::generator()));
+    assert!(crate::g1::is_torsion_free(&G1Affine::zero()));
+    assert!(crate::g2::is_torsion_free(&G2Affine::generator()));
+    assert!(crate::g2::is_torsion_free(&G2Affine::zero()));
+
+    // Points with the smallest abscissas, which are off the subgroup.
+    let p = (0u64..)
+        .find_map(|x| G1Affine::get_point_from_x_unchecked(Fq::from(x), false))
+        .filter(|p| !is_in_subgroup_with(p, SubgroupCheck::Exact))
+        .unwrap();
+    assert!(!crate::g1::is_torsion_free(&p));
+    let q = (0u64..)
+        .find_map(|x| G2Affine::get_point_from_x_unchecked(Fq2::from(x), false))
+        .filter(|q| !q.mul_bigint(Fr::characteristic()).is_zero())
+        .unwrap();
+    assert!(!crate::g2::is_torsion_free(&q));
+}
+
+#[test]
+fn scalar_limbs_match_mul_bigint() {
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let s = Fr::rand(&mut rng);
+    let limbs = test_utils::scalar_limbs::<G1Config>(&s);
+    assert_eq!(limbs, crate::field::fr_to_limbs(&s));
+    let p = G1Projective::rand(&mut rng);
+    assert_eq!(
+        G1Config::mul_projective(&p, &limbs),
+        p.mul_bigint(s.into_bigint())
+    );
+    let q = G2Projective::rand(&mut rng);
+    assert_eq!(
+        G2Config::mul_projective(&q, &limbs),
+        q.mul_bigint(s.into_bigint())
+    );
+}
+
+#[test]
+fn hex_display_prints_full_compressed_encoding() {
+    use crate::codec::HexDisplay;
+
+    let g1 = G1Affine::generator();
+    let s = HexDisplay(&g1).to_string();
+    assert_eq!(
+        s,
+        "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+    );
+    assert_eq!(crate::g1::from_hex::<TestHooks>(&s), Ok(g1));
+
+    let g2 = G2Affine::generator();
+    let s = HexDisplay(&g2).to_string();
+    assert_eq!(
+        s,
+        "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e\
+         024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+    );
+    assert_eq!(crate::g2::from_hex::<TestHooks>(&s), Ok(g2));
+}
+
+#[test]
+fn serialization_invariants() {
+    test_utils::check_sw_serialization::<G1Config>(16);
+    test_utils::check_sw_serialization::<G2Config>(16);
+}
+
+#[test]
+#[allow(deprecated)]
+fn prepare_g2_lines_reproduce_miller_loop() {
+    use crate::prepare_g2_lines;
+
+    let mut rng = test_rng();
+    let g2 = [G2Affine::rand(&mut rng), G2Affine::rand(&mut rng)];
+    let lines = prepare_g2_lines(&g2);
+    for _ in 0..3 {
+        let g1 = [G1Affine::rand(&mut rng), G1Affine::rand(&mut rng)];
+        assert_eq!(
+            TestHooks::bls12_381_multi_miller_loop_prepared(&g1, &lines),
+            Ok(Bls12_381::multi_miller_loop(g1, g2).0)
+        );
+    }
+
+    // A failing hook falls back to the in-Rust preparation.
+    let points: Vec<crate::G2Affine<crate::PhantomHooks>> = g2
+        .iter()
+        .map(|p| crate::G2Affine::new_unchecked(p.x, p.y))
+        .collect();
+    let lines = prepare_g2_lines(&points);
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().zip(&g2).all(|(l, p)| l.point().xy() == p.xy()));
+}
+
+#[test]
+fn scalar_mul_operators_use_hooks() {
+    let _lock = CountingHooks::lock();
+    use ark_ff::PrimeField;
+
+    let mut rng = test_rng();
+    let s = Fr::rand(&mut rng);
+    let p = crate::G1Projective::<CountingHooks>::rand(&mut rng);
+    let q = crate::G2Projective::<CountingHooks>::rand(&mut rng);
+
+    CountingHooks::reset();
+    assert_eq!(p * s, p.mul_bigint(s.into_bigint()));
+    assert_eq!(p.into_affine() * s, p.mul_bigint(s.into_bigint()));
+    assert_eq!(q * s, q.mul_bigint(s.into_bigint()));
+    assert_eq!(q.into_affine() * s, q.mul_bigint(s.into_bigint()));
+    let counts = CountingHooks::counts();
+    assert_eq!(counts.get("bls12_381_mul_projective_g1"), 4);
+    assert_eq!(counts.get("bls12_381_mul_projective_g2"), 4);
+}
+
+#[test]
+#[cfg(feature = "native-fallback")]
+fn native_fallback_hooks_need_no_methods() {
+    use ark_ff::{AdditiveGroup, BigInteger, PrimeField};
+
+    struct EmptyHooks;
+    impl CurveHooks for EmptyHooks {}
+    type Native = crate::Bls12_381<EmptyHooks>;
+
+    let mut rng = test_rng();
+    let s = Fr::rand(&mut rng);
+    let p = G1Projective::rand(&mut rng);
+    let q = G2Projective::rand(&mut rng);
+    let native_p = crate::G1Projective::<EmptyHooks>::new_unchecked(p.x, p.y, p.z);
+    let native_q = crate::G2Projective::<EmptyHooks>::new_unchecked(q.x, q.y, q.z);
+
+    let res = native_p * s;
+    assert_eq!(res.into_affine().xy(), (p * s).into_affine().xy());
+    let res = native_p.into_affine() * s;
+    assert_eq!(res.into_affine().xy(), (p * s).into_affine().xy());
+    // Out of the subgroup and with scalars not smaller than `r`, the result matches
+    // upstream whatever the representation of the base.
+    let off = loop {
+        if let Some(off) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            if !off.is_in_correct_subgroup_assuming_on_curve() {
+                break off;
+            }
+        }
+    };
+    let ark_off = ark_bls12_381::G1Projective::new_unchecked(off.x, off.y, Fq::one());
+    let native_off = crate::G1Affine::<EmptyHooks>::new_unchecked(off.x, off.y);
+    let native_off_z = native_off.into_group().double() - native_off;
+    assert!(!native_off_z.z.is_one());
+    let mut scalars = vec![Fr::MODULUS];
+    for _ in 0..8 {
+        let k = Fr::rand(&mut rng).into_bigint();
+        let mut above_r = Fr::MODULUS;
+        above_r.add_with_carry(&k);
+        scalars.extend([k, above_r]);
+    }
+    for scalar in scalars {
+        let expected = ArkG1Config::mul_projective(&ark_off, scalar.as_ref());
+        for base in [native_off.into_group(), native_off_z] {
+            let res = crate::g1::Config::<EmptyHooks>::mul_projective(&base, scalar.as_ref());
+            assert_eq!(res.into_affine().xy(), expected.into_affine().xy());
+        }
+        let res = native_off.mul_bigint(scalar);
+        assert_eq!(res.into_affine().xy(), expected.into_affine().xy());
+    }
+    let res = crate::g1::Config::<EmptyHooks>::msm(&[native_p.into_affine()], &[s]).unwrap();
+    assert_eq!(res.into_affine().xy(), (p * s).into_affine().xy());
+    let res = crate::g2::Config::<EmptyHooks>::msm(&[native_q.into_affine()], &[s]).unwrap();
+    assert_eq!(res.into_affine().xy(), (q * s).into_affine().xy());
+
+    let res = Native::pairing(native_p.into_affine(), native_q.into_affine());
+    assert_eq!(res.0, Bls12_381::pairing(p, q).0);
+}
+
+#[test]
+#[cfg(feature = "native-fallback")]
+fn native_fallback_msm_honors_window_bits() {
+    struct WindowHooks<const BITS: usize>;
+    impl<const BITS: usize> CurveHooks for WindowHooks<BITS> {
+        const MSM_WINDOW_BITS: Option<usize> = Some(BITS);
+    }
+
+    fn msm<const BITS: usize>(
+        bases: &[G1Affine],
+        scalars: &[Fr],
+    ) -> Result<
+        ark_models_ext::short_weierstrass::Projective<crate::g1::Config<WindowHooks<BITS>>>,
+        (),
+    > {
+        let bases: Vec<_> = bases
+            .iter()
+            .map(|p| crate::G1Affine::<WindowHooks<BITS>>::new_unchecked(p.x, p.y))
+            .collect();
+        WindowHooks::<BITS>::bls12_381_msm_g1(&bases, scalars)
+    }
+
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..50).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..50).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = G1Config::msm(&bases, &scalars).unwrap().into_affine();
+    assert_eq!(
+        msm::<1>(&bases, &scalars).unwrap().into_affine().xy(),
+        expected.xy()
+    );
+    assert_eq!(
+        msm::<7>(&bases, &scalars).unwrap().into_affine().xy(),
+        expected.xy()
+    );
+    // Out of range window sizes are rejected instead of allocating the buckets.
+    assert!(msm::<0>(&bases, &scalars).is_err());
+    assert!(msm::<64>(&bases, &scalars).is_err());
+}
+
+/// Effective G2 cofactor `h_eff` of RFC 9380, section 8.8.2.
+///
+/// The endomorphism-based cofactor clearing multiplies by this multiple of
+/// `COFACTOR`, which is thus not comparable with `mul_bigint(COFACTOR)`.
+const G2_H_EFF: &[u64] = &[
+    0xe8020005aaa95551,
+    0x59894c0adebbf6b4,
+    0xe954cbc06689f6a3,
+    0x2ec0ec69d7477c1a,
+    0x6d82bf015d1212b0,
+    0x329c2f178731db95,
+    0x9986ff031508ffe1,
+    0x88e2a8e9145ad768,
+    0x584c6a0ea91b3528,
+    0xbc69f08f2ee75b3,
+];
+
+#[test]
+fn cofactor_clearing_g2_off_subgroup() {
+    use ark_bls12_381::g2::Config as ArkG2Config;
+    use ark_ec::hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve};
+    use ark_ff::field_hashers::{DefaultFieldHasher, HashToField};
+    use sha2::Sha256;
+
+    // Points out of the subgroup, where a wrong sign or coefficient in the
+    // endomorphisms would show up.
+    let mut rng = test_rng();
+    let mut samples = 0;
+    while samples < 10 {
+        let Some(p) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), rng.gen()) else {
+            continue;
+        };
+        assert!(!p.is_in_correct_subgroup_assuming_on_curve());
+        let naive = G2Config::mul_affine(&p, G2_H_EFF);
+        assert_eq!(p.clear_cofactor().into_group(), naive);
+        assert!(p
+            .clear_cofactor()
+            .is_in_correct_subgroup_assuming_on_curve());
+        samples += 1;
+    }
+
+    // RFC 9380 appendix J.10.1, `msg = "abc"`: `P = clear_cofactor(Q0 + Q1)`, with the
+    // sum of the mapped field elements out of the subgroup.
+    let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+    let hasher = <DefaultFieldHasher<Sha256, 128> as HashToField<Fq2>>::new(dst);
+    let [u0, u1] = hasher.hash_to_field::<2>(b"abc");
+    let q0 = WBMap::<ArkG2Config>::map_to_curve(u0).unwrap();
+    let q1 = WBMap::<ArkG2Config>::map_to_curve(u1).unwrap();
+    let r = (q0 + q1).into_affine();
+    let r = G2Affine::new_unchecked(r.x, r.y);
+    assert!(!r.is_in_correct_subgroup_assuming_on_curve());
+    let expected = G2Affine::new(
+        Fq2::new(
+            fq_from_hex("02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6"),
+            fq_from_hex("139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8"),
+        ),
+        Fq2::new(
+            fq_from_hex("1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48"),
+            fq_from_hex("00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16"),
+        ),
+    );
+    assert_eq!(r.clear_cofactor(), expected);
+}
+
+#[test]
+fn fixed_size_encodings_match_serializer() {
+    let mut rng = test_rng();
+    for p in [G1Affine::rand(&mut rng), G1Affine::zero()] {
+        let mut buf = Vec::new();
+        p.serialize_with_mode(&mut buf, Compress::Yes).unwrap();
+        assert_eq!(crate::g1::to_compressed(&p)[..], buf[..]);
+        buf.clear();
+        p.serialize_with_mode(&mut buf, Compress::No).unwrap();
+        assert_eq!(crate::g1::to_uncompressed(&p)[..], buf[..]);
+    }
+    for p in [G2Affine::rand(&mut rng), G2Affine::zero()] {
+        let mut buf = Vec::new();
+        p.serialize_with_mode(&mut buf, Compress::Yes).unwrap();
+        assert_eq!(crate::g2::to_compressed(&p)[..], buf[..]);
+        buf.clear();
+        p.serialize_with_mode(&mut buf, Compress::No).unwrap();
+        assert_eq!(crate::g2::to_uncompressed(&p)[..], buf[..]);
+    }
+    assert_eq!(crate::g1::to_uncompressed(&G1Affine::generator()).len(), 96);
+    assert_eq!(
+        crate::g2::to_uncompressed(&G2Affine::generator()).len(),
+        192
+    );
+}
+
+#[test]
+fn multi_miller_loop_signed_negates_flagged_points() {
+    let mut rng = test_rng();
+    let g1: Vec<G1Affine> = (0..3).map(|_| G1Affine::rand(&mut rng)).collect();
+    let g2: Vec<G2Affine> = (0..3).map(|_| G2Affine::rand(&mut rng)).collect();
+    let flags = [false, true, true];
+    let pairs: Vec<_> = (0..3).map(|i| (g1[i], g2[i], flags[i])).collect();
+
+    let negated = [g1[0], -g1[1], -g1[2]];
+    let expected = Bls12_381::multi_miller_loop(negated, g2.clone());
+    assert_eq!(crate::multi_miller_loop_signed(&pairs), expected);
+    assert_eq!(
+        TestHooks::bls12_381_multi_miller_loop_signed(&pairs),
+        Ok(expected.0)
+    );
+
+    // `e(A, B) · e(-A, B) == 1`, a pair at infinity not contributing.
+    let pairs = [
+        (g1[0], g2[0], false),
+        (g1[0], g2[0], true),
+        (G1Affine::zero(), g2[1], true),
+    ];
+    let res = Bls12_381::final_exponentiation(crate::multi_miller_loop_signed(&pairs)).unwrap();
+    assert!(crate::is_identity(&res));
+}
+
+#[test]
+fn keygen_matches_eip2333_vectors() {
+    use crate::keygen::{derive_secret_key, sk_to_pk};
+    use core::str::FromStr;
+
+    // Master keys of the EIP-2333 test cases, derived with an empty `key_info`.
+    let vectors = [
+        (
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+        ),
+        (
+            "3141592653589793238462643383279502884197169399375105820974944592",
+            "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+        ),
+        (
+            "0099ff991111002299dd7744ee3355bbdd8844115566cc55663355668888cc00",
+            "27580842291869792442942448775674722299803720648445448686099262467207037398656",
+        ),
+        (
+            "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+            "19022158461524446591288038168518313374041767046816487870552872741050760015818",
+        ),
+    ];
+    for (seed, sk) in vectors {
+        let sk = Fr::from_str(sk).unwrap();
+        assert_eq!(derive_secret_key(&hex::decode(seed).unwrap(), b""), sk);
+        assert_eq!(
+            sk_to_pk::<TestHooks>(&sk),
+            (G1Affine::generator() * sk).into_affine()
+        );
+    }
+    // `key_info` is bound to the key.
+    let ikm = [7u8; 32];
+    assert_ne!(
+        derive_secret_key(&ikm, b""),
+        derive_secret_key(&ikm, b"app")
+    );
+}
+
+#[test]
+fn checked_msm_rejects_off_subgroup_bases() {
+    use crate::msm::CheckedMsmError;
+
+    let mut rng = test_rng();
+    let scalars: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut g1_bases: Vec<G1Affine> = (0..4).map(|_| G1Affine::rand(&mut rng)).collect();
+    let expected = G1Config::msm(&g1_bases, &scalars).unwrap();
+    assert_eq!(crate::g1::checked_msm(&g1_bases, &scalars), Ok(expected));
+    g1_bases[2] = loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!g1_bases[2].is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(
+        crate::g1::checked_msm(&g1_bases, &scalars),
+        Err(CheckedMsmError::InvalidBase(2))
+    );
+    // Off the curve, reported before the subgroup membership.
+    g1_bases[1].y += Fq::one();
+    assert_eq!(
+        crate::g1::checked_msm(&g1_bases, &scalars),
+        Err(CheckedMsmError::InvalidBase(1))
+    );
+    // Lengths first, whatever the bases.
+    assert_eq!(
+        crate::g1::checked_msm(&g1_bases, &scalars[..3]),
+        Err(CheckedMsmError::LengthMismatch(3))
+    );
+
+    let mut g2_bases: Vec<G2Affine> = (0..4).map(|_| G2Affine::rand(&mut rng)).collect();
+    let expected = G2Config::msm(&g2_bases, &scalars).unwrap();
+    assert_eq!(crate::g2::checked_msm(&g2_bases, &scalars), Ok(expected));
+    g2_bases[1] = loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!g2_bases[1].is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(
+        crate::g2::checked_msm(&g2_bases, &scalars),
+        Err(CheckedMsmError::InvalidBase(1))
+    );
+    g2_bases[0].y += Fq2::one();
+    assert_eq!(
+        crate::g2::checked_msm(&g2_bases, &scalars),
+        Err(CheckedMsmError::InvalidBase(0))
+    );
+    assert_eq!(
+        crate::g2::checked_msm(&g2_bases[..2], &scalars),
+        Err(CheckedMsmError::LengthMismatch(2))
+    );
+}
+
+#[test]
+fn checked_msm_reports_hook_failures() {
+    use crate::msm::CheckedMsmError;
+
+    type H = FlakyHooks<{ FAIL_MSM_G1 | FAIL_MSM_G2 }, false>;
+    let g1 = crate::G1Affine::<H>::generator();
+    let g2 = crate::G2Affine::<H>::generator();
+    assert_eq!(
+        crate::g1::checked_msm(&[g1], &[Fr::one()]),
+        Err(CheckedMsmError::Hook)
+    );
+    assert_eq!(
+        crate::g2::checked_msm(&[g2], &[Fr::one()]),
+        Err(CheckedMsmError::Hook)
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn g1_subgroup_batch_validation() {
+    use crate::g1::validate_subgroup_g1_batch;
+
+    let mut rng = test_rng();
+    let mut points: Vec<G1Affine> = (0..4).map(|_| G1Affine::rand(&mut rng)).collect();
+    let outside = loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!outside.is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(validate_subgroup_g1_batch(&points), Ok(()));
+    points.insert(2, outside);
+    points.push(G1Affine::zero());
+    assert_eq!(validate_subgroup_g1_batch(&points), Err(2));
+    assert_eq!(validate_subgroup_g1_batch::<TestHooks>(&[]), Ok(()));
+
+    // A failing hook falls back to the in-Rust check.
+    type Hooks = crate::PhantomHooks;
+    let p = crate::G1Affine::<Hooks>::new_unchecked(outside.x, outside.y);
+    assert_eq!(validate_subgroup_g1_batch::<Hooks>(&[p]), Err(0));
+}
+
+#[test]
+fn is_on_curve_ignores_subgroup_membership() {
+    let mut rng = test_rng();
+
+    let off_subgroup = loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!crate::g1::is_torsion_free(&off_subgroup));
+    assert!(crate::g1::is_on_curve(&off_subgroup));
+    assert!(crate::g1::is_on_curve(&G1Affine::zero()));
+    let mut off_curve = off_subgroup;
+    off_curve.y += Fq::one();
+    assert!(!crate::g1::is_on_curve(&off_curve));
+
+    let off_subgroup = loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert!(!crate::g2::is_torsion_free(&off_subgroup));
+    assert!(crate::g2::is_on_curve(&off_subgroup));
+    assert!(crate::g2::is_on_curve(&G2Affine::zero()));
+    let mut off_curve = off_subgroup;
+    off_curve.y += Fq2::one();
+    assert!(!crate::g2::is_on_curve(&off_curve));
+}
+
+#[test]
+fn native_msm_is_window_size_independent() {
+    use test_utils::{msm_native_sw_with_window, msm_window_bits, MAX_WINDOW_BITS};
+
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..100).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..100).map(|_| Fr::rand(&mut rng)).collect();
+
+    let expected = G1Config::msm(&bases, &scalars).unwrap();
+    for bits in 1..=12 {
+        assert_eq!(
+            msm_native_sw_with_window(&bases, &scalars, bits).unwrap(),
+            expected
+        );
+    }
+    assert_eq!(msm_window_bits(16), 3);
+    assert_eq!(msm_window_bits(1 << 16), 13);
+    assert_eq!(msm_window_bits(usize::MAX), MAX_WINDOW_BITS);
+    assert!(msm_native_sw_with_window(&bases, &scalars, 0).is_err());
+    assert!(msm_native_sw_with_window(&bases, &scalars, MAX_WINDOW_BITS + 1).is_err());
+    assert!(msm_native_sw_with_window(&bases, &scalars, 64).is_err());
+}
+
+#[test]
+fn encoding_flags_round_trip() {
+    use crate::util::{decode_flags, EncodingFlags, G1_SERIALIZED_SIZE, G2_SERIALIZED_SIZE};
+
+    for bits in 0u8..8 {
+        let flags = EncodingFlags {
+            is_compressed: bits & 4 != 0,
+            is_infinity: bits & 2 != 0,
+            is_lexographically_largest: bits & 1 != 0,
+        };
+        let mut bytes = [0u8; G1_SERIALIZED_SIZE];
+        flags.encode_flags(&mut bytes);
+        assert_eq!(bytes[0] & 0b0001_1111, 0);
+        assert!(bytes[1..].iter().all(|b| *b == 0));
+        // The sort flag is only encoded for compressed points other than infinity.
+        let expected = EncodingFlags {
+            is_lexographically_largest: flags.is_lexographically_largest
+                && flags.is_compressed
+                && !flags.is_infinity,
+            ..flags
+        };
+        assert_eq!(decode_flags(&bytes), Some(expected));
+        assert_eq!(EncodingFlags::get_flags(bytes.to_vec()), expected);
+    }
+    assert_eq!(decode_flags(&[]), None);
+
+    // Compressed point at infinity with the sort flag set.
+    let mut g1 = [0u8; G1_SERIALIZED_SIZE];
+    g1[0] = 0b1110_0000;
+    let flags = decode_flags(&g1).unwrap();
+    assert!(flags.is_compressed && flags.is_infinity && flags.is_lexographically_largest);
+    assert!(G1Affine::deserialize_compressed(&g1[..]).is_err());
+    g1[0] = 0b1100_0000;
+    assert_eq!(
+        G1Affine::deserialize_compressed(&g1[..]).unwrap(),
+        G1Affine::zero()
+    );
+
+    let mut g2 = [0u8; G2_SERIALIZED_SIZE];
+    g2[0] = 0b1110_0000;
+    assert!(G2Affine::deserialize_compressed(&g2[..]).is_err());
+    g2[0] = 0b1100_0000;
+    assert_eq!(
+        G2Affine::deserialize_compressed(&g2[..]).unwrap(),
+        G2Affine::zero()
+    );
+}
+
+/// Messages logged by the hooked operations, with the `trace` feature.
+///
+/// Installs a logger capturing every record, shared by the tests as the logger can
+/// only be set once.
+#[cfg(feature = "trace")]
+fn trace_records() -> &'static std::sync::Mutex<Vec<String>> {
+    use std::sync::Mutex;
+
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            RECORDS.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    // Other tests may have installed the logger already.
+    let _ = log::set_logger(&CapturingLogger);
+    log::set_max_level(log::LevelFilter::Trace);
+    &RECORDS
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn trace_reports_hook_payload_size() {
+    let records = trace_records();
+
+    let mut rng = test_rng();
+    let bases: Vec<G1Affine> = (0..3).map(|_| G1Affine::rand(&mut rng)).collect();
+    let scalars: Vec<Fr> = (0..3).map(|_| Fr::rand(&mut rng)).collect();
+    let _ = G1Config::msm(&bases, &scalars).unwrap();
+
+    let compress = G1Config::USAGE.compress;
+    let size = bases.serialized_size(compress) + scalars.serialized_size(compress);
+    // 8 bytes for each length prefix.
+    assert_eq!(size, 8 + 3 * 96 + 8 + 3 * 32);
+    let expected = format!("bls12_381_msm_g1: {size} bytes");
+    assert!(records.lock().unwrap().contains(&expected));
+}
+
+#[test]
+fn read_g1_auto_checks_flag_against_length() {
+    use crate::util::read_g1_auto;
+    use ark_serialize::SerializationError;
+
+    let mut rng = test_rng();
+    for p in [G1Affine::rand(&mut rng), G1Affine::zero()] {
+        let compressed = crate::g1::to_compressed(&p);
+        let uncompressed = crate::g1::to_uncompressed(&p);
+        assert_eq!(read_g1_auto::<TestHooks>(&compressed).unwrap(), p);
+        assert_eq!(read_g1_auto::<TestHooks>(&uncompressed).unwrap(), p);
+
+        // Compression flag set, uncompressed length.
+        let mut bytes = uncompressed.to_vec();
+        bytes[0] |= 0x80;
+        assert!(matches!(
+            read_g1_auto::<TestHooks>(&bytes),
+            Err(SerializationError::UnexpectedFlags)
+        ));
+        // Compression flag unset, compressed length.
+        let mut bytes = compressed.to_vec();
+        bytes[0] &= 0x7f;
+        assert!(matches!(
+            read_g1_auto::<TestHooks>(&bytes),
+            Err(SerializationError::UnexpectedFlags)
+        ));
+        // Trailing byte.
+        let mut bytes = compressed.to_vec();
+        bytes.push(0);
+        assert!(read_g1_auto::<TestHooks>(&bytes).is_err());
+    }
+    assert!(read_g1_auto::<TestHooks>(&[]).is_err());
+
+    // Out of the prime order subgroup.
+    let p = loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    let mut bytes = vec![];
+    p.serialize_compressed(&mut bytes).unwrap();
+    assert!(read_g1_auto::<TestHooks>(&bytes).is_err());
+}
+
+#[test]
+fn msm_result_normalizes_once() {
+    let _lock = CountingHooks::lock();
+    use crate::msm::MsmResult;
+
+    let mut rng = test_rng();
+    let bases: Vec<crate::G1Affine<CountingHooks>> = (0..4)
+        .map(|_| {
+            let p = G1Affine::rand(&mut rng);
+            crate::G1Affine::<CountingHooks>::new_unchecked(p.x, p.y)
+        })
+        .collect();
+    let scalars: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+
+    let res = MsmResult::msm(&bases, &scalars).unwrap();
+    CountingHooks::reset();
+    let affine = *res.as_affine();
+    assert_eq!(res.as_affine(), &affine);
+    assert_eq!(res.as_projective().into_affine(), affine);
+    assert_eq!(
+        CountingHooks::counts().get("bls12_381_batch_to_affine_g1"),
+        1
+    );
+
+    assert!(MsmResult::msm(&bases, &scalars[..3]).is_err());
+}
+
+#[test]
+fn kzg_opening_verification() {
+    use crate::kzg::{verify_opening, KzgVerifyingKey};
+
+    let mut rng = test_rng();
+    let tau = Fr::rand(&mut rng);
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let vk = KzgVerifyingKey::<TestHooks> {
+        g1,
+        g2,
+        tau_g2: (g2 * tau).into_affine(),
+    };
+
+    // p(X) = 3 + 2X + X^2, committed to as [p(τ)]G1.
+    let eval = |x: Fr| Fr::from(3) + Fr::from(2) * x + x * x;
+    let commitment = (g1 * eval(tau)).into_affine();
+    let z = Fr::rand(&mut rng);
+    let v = eval(z);
+    // Quotient `(p(X) - v) / (X - z)` evaluated at `τ`.
+    let proof = (g1 * ((eval(tau) - v) / (tau - z))).into_affine();
+
+    assert!(verify_opening(commitment, proof, z, v, &vk));
+    assert!(!verify_opening(commitment, proof, z, v + Fr::from(1), &vk));
+    assert!(!verify_opening(commitment, proof, z + Fr::from(1), v, &vk));
+    assert!(!verify_opening(commitment, g1, z, v, &vk));
+}
+
+#[test]
+fn trivial_point_conversions_skip_the_host() {
+    let _lock = CountingHooks::lock();
+    type G1 = crate::G1Affine<CountingHooks>;
+    type G2 = crate::G2Affine<CountingHooks>;
+
+    CountingHooks::reset();
+    for p in [G1::zero(), G1::generator()] {
+        let q = p.into_group();
+        assert_eq!(q.into_affine(), p);
+        assert_eq!(crate::G1Projective::<CountingHooks>::from(p), q);
+        assert_eq!(G1::from(q), p);
+    }
+    for p in [G2::zero(), G2::generator()] {
+        let q = p.into_group();
+        assert_eq!(q.into_affine(), p);
+        assert_eq!(crate::G2Projective::<CountingHooks>::from(p), q);
+        assert_eq!(G2::from(q), p);
+    }
+    assert_eq!(CountingHooks::counts().total(), 0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn fq_sqrt_matches_the_canonical_root() {
+    use crate::field::fq_sqrt;
+    use ark_ff::{BigInteger, PrimeField};
+
+    // `(q + 1) / 4`, the exponent of the root as `q = 3 mod 4`.
+    let mut exp = Fq::MODULUS;
+    exp.add_with_carry(&1u64.into());
+    exp.div2();
+    exp.div2();
+
+    let mut rng = test_rng();
+    for _ in 0..16 {
+        let root = Fq::rand(&mut rng);
+        let square = root.square();
+        let res = fq_sqrt::<TestHooks>(&square).unwrap();
+        assert!(res == root || res == -root);
+        assert_eq!(res, square.pow(exp));
+    }
+    assert_eq!(fq_sqrt::<TestHooks>(&Fq::zero()), Some(Fq::zero()));
+    assert_eq!(
+        fq_sqrt::<TestHooks>(&Fq::from(4)).unwrap().square(),
+        Fq::from(4)
+    );
+    // `-1` is a non-residue, thus so are `-x^2`.
+    assert_eq!(fq_sqrt::<TestHooks>(&-Fq::one()), None);
+    let x = Fq::rand(&mut rng);
+    assert_eq!(fq_sqrt::<TestHooks>(&-x.square()), None);
+    // A failing hook falls back to the in-Rust root.
+    assert_eq!(
+        fq_sqrt::<crate::PhantomHooks>(&x.square()),
+        fq_sqrt::<TestHooks>(&x.square())
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn sum_g1_matches_fold() {
+    use crate::g1::sum_g1;
+
+    let mut rng = test_rng();
+    let mut points: Vec<G1Affine> = (0..10).map(|_| G1Affine::rand(&mut rng)).collect();
+    points.push(G1Affine::zero());
+    points.push(-points[0]);
+    let expected = points.iter().fold(G1Projective::zero(), |acc, p| acc + p);
+
+    assert_eq!(sum_g1(&points), expected);
+    assert!(sum_g1::<TestHooks>(&[]).is_zero());
+    // A failing hook falls back to the in-Rust sum.
+    let phantom: Vec<crate::G1Affine<crate::PhantomHooks>> = points
+        .iter()
+        .map(|p| match p.xy() {
+            Some((x, y)) => crate::G1Affine::new_unchecked(x, y),
+            None => crate::G1Affine::zero(),
+        })
+        .collect();
+    assert_eq!(
+        sum_g1(&phantom).into_affine().xy(),
+        expected.into_affine().xy()
+    );
+}
+
+#[test]
+fn curve_constants_match_the_documented_values() {
+    use ark_ff::BigInt;
+
+    assert_eq!(
+        crate::scalar_field_modulus().to_string(),
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+    );
+    assert_eq!(
+        crate::base_field_modulus().to_string(),
+        "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787"
+    );
+    // `(x^8 - 4x^7 + 5x^6 - 4x^4 + 6x^3 - 4x^2 - 4x + 13) / 9`, with `x = -0xd201000000010000`.
+    let cofactor = BigInt::<8>::new(crate::g2_cofactor().try_into().unwrap());
+    assert_eq!(
+        cofactor.to_string(),
+        "305502333931268344200999753193121504214466019254188142667664032982267604182971884026507427359259977847832272839041616661285803823378372096355777062779109"
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn fq12_frobenius_matches_repeated_maps() {
+    use crate::field::fq12_frobenius;
+    use ark_ff::{Field, PrimeField};
+
+    let mut rng = test_rng();
+    let x = crate::Fq12::rand(&mut rng);
+    let mut expected = x;
+    for power in 0..=12 {
+        assert_eq!(fq12_frobenius::<TestHooks>(&x, power), expected);
+        // A failing hook falls back to the in-Rust map.
+        assert_eq!(fq12_frobenius::<crate::PhantomHooks>(&x, power), expected);
+        expected = expected.frobenius_map(1);
+    }
+    // The Frobenius map is `x -> x^q`, of order 12 on `Fq12`.
+    assert_eq!(fq12_frobenius::<TestHooks>(&x, 1), x.pow(Fq::MODULUS));
+    assert_eq!(fq12_frobenius::<TestHooks>(&x, 12), x);
+}
+
+#[test]
+fn psi_endomorphism() {
+    use crate::g2::{is_on_curve, psi, psi2};
+    use ark_models_ext::bls12::Bls12Config;
+    type Config = crate::Config<TestHooks>;
+
+    let mut rng = test_rng();
+    for _ in 0..4 {
+        let p = G2Affine::rand(&mut rng);
+        assert!(is_on_curve(&psi(&p)));
+        assert_eq!(psi(&psi(&p)), psi2(&p));
+        // `ψ(p) = [x]p`, with `x` negative.
+        const { assert!(Config::X_IS_NEGATIVE) };
+        assert_eq!(psi(&p), -p.mul_bigint(Config::X).into_affine());
+        let mut q = p;
+        for _ in 0..6 {
+            q = psi2(&q);
+        }
+        assert_eq!(q, p);
+    }
+    // The order divides 12 off the subgroup too.
+    let p = loop {
+        if let Some(p) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), false) {
+            break p;
+        }
+    };
+    assert_eq!((0..12).fold(p, |q, _| psi(&q)), p);
+    assert!(psi(&G2Affine::zero()).is_zero());
+    assert!(psi2(&G2Affine::zero()).is_zero());
+}
+
+#[test]
+fn ssz_pubkey_and_signature_decoding() {
+    use crate::signatures::{from_ssz_pubkey, from_ssz_signature, verify, MinPk, Scheme};
+
+    let pubkey: [u8; 48] = decode(PUBKEYS[2]).unwrap().try_into().unwrap();
+    let sig: [u8; 96] = decode("ae82747ddeefe4fd64cf9cedb9b04ae3e8a43420cd255e3c7cd06a8d88b7c7f8638543719981c5d16fa3527c468c25f0026704a6951bde891360c7e8d12ddee0559004ccdbe6046b55bae1b257ee97f7cdb955773d7cf29adf3ccbb9975e4eb9").unwrap().try_into().unwrap();
+    let pubkey = from_ssz_pubkey::<TestHooks>(&pubkey).unwrap();
+    let sig = from_ssz_signature::<TestHooks>(&sig).unwrap();
+    assert!(verify::<MinPk, _>(
+        pubkey,
+        &MESSAGES[2],
+        sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+
+    // Infinity is rejected as a public key but is a valid signature encoding.
+    let mut infinity = [0u8; 96];
+    infinity[0] = 0xc0;
+    assert!(from_ssz_pubkey::<TestHooks>(infinity[..48].try_into().unwrap()).is_err());
+    assert_eq!(
+        from_ssz_signature::<TestHooks>(&infinity),
+        Ok(G2Affine::zero())
+    );
+
+    // Uncompressed flag.
+    let mut bytes = [0u8; 48];
+    pubkey.serialize_compressed(&mut bytes[..]).unwrap();
+    bytes[0] &= 0x7f;
+    assert!(from_ssz_pubkey::<TestHooks>(&bytes).is_err());
+
+    // Points on the curve, out of the prime order subgroup.
+    let mut rng = test_rng();
+    let p = loop {
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(Fq::rand(&mut rng), false) {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                break p;
+            }
+        }
+    };
+    p.serialize_compressed(&mut bytes[..]).unwrap();
+    assert!(from_ssz_pubkey::<TestHooks>(&bytes).is_err());
+    let q = loop {
+        if let Some(q) = G2Affine::get_point_from_x_unchecked(Fq2::rand(&mut rng), false) {
+            if !q.is_in_correct_subgroup_assuming_on_curve() {
+                break q;
+            }
+        }
+    };
+    let mut bytes = [0u8; 96];
+    q.serialize_compressed(&mut bytes[..]).unwrap();
+    assert!(from_ssz_signature::<TestHooks>(&bytes).is_err());
+}
+
+#[test]
+fn exact_subgroup_check_ignores_the_hooks() {
+    use crate::g1::{is_in_subgroup_with, SubgroupCheck};
+
+    // Every multiplication hook answers the point at infinity.
+    failing_hooks!(Hooks, crate::util::OnError::Default);
+    let mut rng = test_rng();
+    let p = loop {
+        let x = Fq::rand(&mut rng);
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+            if !p.is_in_correct_subgroup_assuming_on_curve() {
+                break crate::G1Affine::<Hooks>::new_unchecked(p.x, p.y);
+            }
+        }
+    };
+    assert!(!is_in_subgroup_with(&p, SubgroupCheck::Exact));
+    let g = crate::G1Affine::<Hooks>::generator();
+    assert!(is_in_subgroup_with(&g, SubgroupCheck::Exact));
+}
+
+#[test]
+fn prepare_g2_lines_checks_the_hook_points() {
+    use crate::{prepare_g2_lines, CachedG2Prepared};
+    use ark_ec::bls12::G2Prepared;
+
+    fn host_lines<H: CurveHooks>(g2: &[crate::G2Affine<H>]) -> Vec<CachedG2Prepared<H>> {
+        g2.iter()
+            .map(|p| {
+                let ark = ark_bls12_381::G2Affine::new_unchecked(p.x, p.y);
+                CachedG2Prepared::from_lines(*p, G2Prepared::from(ark))
+            })
+            .collect()
+    }
+
+    let mut rng = test_rng();
+    let g1 = [G1Affine::rand(&mut rng), G1Affine::rand(&mut rng)];
+    let g2 = [G2Affine::rand(&mut rng), G2Affine::rand(&mut rng)];
+    let lines = host_lines(&g2);
+    assert_eq!(
+        TestHooks::bls12_381_multi_miller_loop_prepared(&g1, &lines),
+        Ok(Bls12_381::multi_miller_loop(g1, g2).0)
+    );
+
+    // Preparations of the points in the wrong order are replaced.
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Propagate,
+        fn bls12_381_miller_lines(
+            g2: &[crate::G2Affine<Self>],
+        ) -> Result<Vec<CachedG2Prepared<Self>>, ()> {
+            let mut res = host_lines(g2);
+            res.reverse();
+            Ok(res)
+        }
+    );
+    let points: Vec<crate::G2Affine<Hooks>> = g2
+        .iter()
+        .map(|p| crate::G2Affine::new_unchecked(p.x, p.y))
+        .collect();
+    let lines = prepare_g2_lines(&points);
+    assert!(lines.iter().zip(&points).all(|(l, p)| l.point() == p));
+}
+
+#[test]
+fn verify_aggregate_basic_rejects_duplicated_messages() {
+    use crate::hashing::hash_to_g2;
+    use crate::keygen::sk_to_pk;
+    use crate::signatures::{verify_aggregate, MinPk, Scheme};
+
+    let mut rng = test_rng();
+    let sks = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+    let pubkeys = sks.map(|sk| sk_to_pk::<TestHooks>(&sk));
+    let sign = |msgs: [&[u8]; 2], dst: &[u8]| -> G2Affine {
+        msgs.iter()
+            .zip(&sks)
+            .map(|(msg, sk)| hash_to_g2::<TestHooks>(msg, dst).unwrap() * sk)
+            .sum::<G2Projective>()
+            .into_affine()
+    };
+
+    let distinct: [&[u8]; 2] = [b"msg1", b"msg2"];
+    let agg_sig = sign(distinct, MinPk::DST_BASIC);
+    assert!(verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &distinct,
+        agg_sig,
+        MinPk::DST_BASIC,
+        Scheme::Basic
+    ));
+
+    // Same message twice: the aggregate is valid, but only accepted with the
+    // proof of possession scheme, whatever the tag of the basic scheme is.
+    let duplicated: [&[u8]; 2] = [b"msg1", b"msg1"];
+    let agg_sig = sign(duplicated, MinPk::DST_BASIC);
+    assert!(!verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &duplicated,
+        agg_sig,
+        MinPk::DST_BASIC,
+        Scheme::Basic
+    ));
+    let custom_dst = b"MY_APP_BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+    let agg_sig = sign(duplicated, custom_dst);
+    assert!(!verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &duplicated,
+        agg_sig,
+        custom_dst,
+        Scheme::Basic
+    ));
+    let agg_sig = sign(duplicated, MinPk::DST_POP);
+    assert!(verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &duplicated,
+        agg_sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+}
+
+#[test]
+fn verify_aggregate_augments_messages_with_pubkeys() {
+    use crate::hashing::hash_to_g2;
+    use crate::keygen::sk_to_pk;
+    use crate::signatures::{verify, verify_aggregate, MinPk, Scheme};
+
+    let mut rng = test_rng();
+    let sks = [Fr::rand(&mut rng), Fr::rand(&mut rng)];
+    let pubkeys = sks.map(|sk| sk_to_pk::<TestHooks>(&sk));
+    let augmented = |pubkey: &G1Affine, msg: &[u8]| {
+        let mut buf = Vec::new();
+        pubkey.serialize_compressed(&mut buf).unwrap();
+        buf.extend_from_slice(msg);
+        buf
+    };
+
+    // Duplicated messages are bound to their signers by the augmentation.
+    let msgs: [&[u8]; 2] = [b"msg1", b"msg1"];
+    let sigs: Vec<G2Affine> = msgs
+        .iter()
+        .zip(&sks)
+        .zip(&pubkeys)
+        .map(|((msg, sk), pk)| {
+            (hash_to_g2::<TestHooks>(&augmented(pk, msg), MinPk::DST_AUG).unwrap() * sk)
+                .into_affine()
+        })
+        .collect();
+    assert!(verify::<MinPk, _>(
+        pubkeys[0],
+        msgs[0],
+        sigs[0],
+        MinPk::DST_AUG,
+        Scheme::MessageAugmentation
+    ));
+    assert!(!verify::<MinPk, _>(
+        pubkeys[0],
+        msgs[0],
+        sigs[0],
+        MinPk::DST_AUG,
+        Scheme::Basic
+    ));
+
+    let agg_sig = (sigs[0] + sigs[1]).into_affine();
+    assert!(verify_aggregate::<MinPk, _>(
+        &pubkeys,
+        &msgs,
+        agg_sig,
+        MinPk::DST_AUG,
+        Scheme::MessageAugmentation
+    ));
+    let swapped = [pubkeys[1], pubkeys[0]];
+    let agg_sig = (sigs[0] + sigs[0]).into_affine();
+    assert!(!verify_aggregate::<MinPk, _>(
+        &swapped,
+        &msgs,
+        agg_sig,
+        MinPk::DST_AUG,
+        Scheme::MessageAugmentation
+    ));
+}
+
+#[test]
+fn per_group_usage_drives_the_codec_helpers() {
+    use crate::codec::{
+        decode_projective_g1, decode_projective_g2, encode_projective_g1, encode_projective_g2,
+        Usage,
+    };
+
+    // Only the codec policies matter here.
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Propagate,
+        const G2_USAGE: Usage = Usage::new(Compress::Yes, Validate::Yes);
+    );
+
+    let mut rng = test_rng();
+    let p = crate::G1Projective::<Hooks>::rand(&mut rng);
+    let q = crate::G2Projective::<Hooks>::rand(&mut rng);
+
+    // G1 keeps the default uncompressed encoding, G2 is compressed.
+    let p_bytes = encode_projective_g1::<Hooks>(&p);
+    let q_bytes = encode_projective_g2::<Hooks>(&q);
+    assert_eq!(p_bytes.len(), 96);
+    assert_eq!(q_bytes.len(), 96);
+    assert_eq!(decode_projective_g1::<Hooks>(&p_bytes), Ok(p));
+    assert_eq!(decode_projective_g2::<Hooks>(&q_bytes), Ok(q));
+
+    // Hooks with the default policies don't decode the compressed G2 encoding.
+    assert_eq!(
+        encode_projective_g2::<TestHooks>(&G2Projective::generator()).len(),
+        192
+    );
+    assert!(decode_projective_g2::<TestHooks>(&q_bytes).is_err());
+}
+
+#[cfg(feature = "trace")]
+#[test]
+fn trace_sizes_signed_pairs_per_group() {
+    use crate::codec::Usage;
+
+    let records = trace_records();
+    failing_hooks!(
+        Hooks,
+        crate::util::OnError::Propagate,
+        const G2_USAGE: Usage = Usage::new(Compress::Yes, Validate::Yes);
+    );
+    let g1 = crate::G1Affine::<Hooks>::generator();
+    let g2 = crate::G2Affine::<Hooks>::generator();
+    let _ = crate::multi_miller_loop_signed(&[(g1, g2, false), (g1, g2, true)]);
+
+    // Uncompressed G1 points, compressed G2 points and the flags, each with a length prefix.
+    let size = (8 + 2 * 96) + (8 + 2 * 96) + (8 + 2);
+    let expected = format!("bls12_381_multi_miller_loop_signed: {size} bytes");
+    assert!(records.lock().unwrap().contains(&expected));
+}
+
+#[test]
+fn verify_pairings_aggregated_rejects_failed_scaling() {
+    use crate::verify_pairings_aggregated;
+
+    fn check<H: CurveHooks>() {
+        let mut rng = test_rng();
+        let g1 = crate::G1Affine::<H>::generator();
+        let g2 = crate::G2Affine::<H>::generator();
+        // `e(G1, G2) != 1`, accepted if the scaled G1 input is dropped as the identity.
+        let eqs = [(vec![g1], vec![g2])];
+        assert!(!verify_pairings_aggregated::<H, _>(&eqs, &mut rng));
+        let eqs = [(vec![g1, -g1], vec![g2, g2])];
+        assert!(!verify_pairings_aggregated::<H, _>(&eqs, &mut rng));
+    }
+    check::<FlakyHooks<FAIL_MUL_G1, false>>();
+    check::<FlakyHooks<FAIL_MUL_G1, true>>();
+
+    // The same valid equation is accepted once the hook works.
+    let mut rng = test_rng();
+    let g1 = crate::G1Affine::<FlakyHooks<0, false>>::generator();
+    let g2 = crate::G2Affine::<FlakyHooks<0, false>>::generator();
+    let eqs = [(vec![g1, -g1], vec![g2, g2])];
+    assert!(verify_pairings_aggregated::<FlakyHooks<0, false>, _>(
+        &eqs, &mut rng
+    ));
+}
+
+#[test]
+fn verify_rejects_identity_hashes() {
+    use crate::signatures::{verify, MinPk, Scheme};
+
+    // The failed hash to G2 is replaced by the identity, which must not drop the
+    // signer from the check of the identity signature.
+    type Hooks = FlakyHooks<FAIL_HASH_TO_G2, true>;
+    let mut rng = test_rng();
+    let pubkey = (crate::G1Affine::<Hooks>::generator() * Fr::rand(&mut rng)).into_affine();
+    let sig = crate::G2Affine::<Hooks>::zero();
+    assert!(!verify::<MinPk, Hooks>(
+        pubkey,
+        b"anything",
+        sig,
+        MinPk::DST_POP,
+        Scheme::ProofOfPossession
+    ));
+}
+
+#[test]
+fn kzg_opening_rejects_failed_scaling() {
+    use crate::kzg::{verify_opening, KzgVerifyingKey};
+
+    // `f(X) = X` opens to `0` at `0` with the proof `[1]G1`, setup computed with
+    // working hooks.
+    fn opens<H: CurveHooks>(z: u64, v: u64) -> bool {
+        let tau = Fr::rand(&mut test_rng());
+        let tau_g1 = (G1Affine::generator() * tau).into_affine();
+        let tau_g2 = (G2Affine::generator() * tau).into_affine();
+        let vk = KzgVerifyingKey::<H> {
+            g1: crate::G1Affine::<H>::generator(),
+            g2: crate::G2Affine::<H>::generator(),
+            tau_g2: crate::G2Affine::<H>::new_unchecked(tau_g2.x, tau_g2.y),
+        };
+        let commitment = crate::G1Affine::<H>::new_unchecked(tau_g1.x, tau_g1.y);
+        verify_opening(commitment, vk.g1, Fr::from(z), Fr::from(v), &vk)
+    }
+    assert!(opens::<FlakyHooks<0, false>>(0, 0));
+    assert!(!opens::<FlakyHooks<0, false>>(5, 7));
+    // Failed scalings don't turn the check into the opening at `0`.
+    type Hooks = FlakyHooks<{ FAIL_MUL_G1 | FAIL_MUL_G2 }, false>;
+    assert!(!opens::<Hooks>(5, 7));
+    assert!(!opens::<Hooks>(0, 0));
+}
@@ -1,5 +1,12 @@
+//! ZCash BLS12-381 point encoding helpers.
+
 use ark_ff::{BigInteger384, PrimeField};
-use ark_models_ext::{short_weierstrass::Affine, AffineRepr};
+use ark_models_ext::{
+    bls12::{Bls12Config, TwistType},
+    fp2_codec,
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    AffineRepr,
+};
 use ark_serialize::SerializationError;
 use ark_std::{io::Read, vec::Vec};
 
@@ -7,27 +14,215 @@ use crate::CurveHooks;
 use crate::{g1::Config as G1Config, g2::Config as G2Config};
 use ark_bls12_381::{fq::Fq, fq2::Fq2};
 
+/// Behavior of the hooked operations when a hook fails.
+///
+/// Selected through `CurveHooks::HOOK_POLICY`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnError {
+    /// Panic, naming the failing hook.
+    Panic,
+    /// Carry on with the default value of the hook output, e.g. the point at infinity.
+    ///
+    /// Doesn't apply where a default would be mistaken for a result: the hooks with
+    /// an in-Rust fallback fall back, the hashes and the verification helpers fail,
+    /// as with `Propagate`.
+    Default,
+    /// Report the failure through the return type of the operation (e.g. `Err(0)` for
+    /// an MSM, `None` for a final exponentiation), falling back to the default value of
+    /// the hook output where the return type can't carry an error.
+    Propagate,
+}
+
+/// Trace a hook call with the `trace` feature.
+///
+/// Logs at the trace level the hook name and the total size of its `input`s, each
+/// encoded according to its `usage`, i.e. what a host receiving the encoded inputs
+/// gets. Expands to nothing without the feature, the inputs are not even evaluated.
+macro_rules! trace_hook {
+    ($name:literal, $($input:expr => $usage:expr),+ $(,)?) => {
+        #[cfg(feature = "trace")]
+        log::trace!(
+            "{}: {} bytes",
+            $name,
+            0 $(+ ark_serialize::CanonicalSerialize::serialized_size($input, $usage.compress))+
+        );
+    };
+}
+pub(crate) use trace_hook;
+
+/// Hook call result, handled according to the `HOOK_POLICY` of `H`.
+///
+/// With `OnError::Panic` a failure panics with a message naming the failing hook
+/// and, through `#[track_caller]`, the call site. With `OnError::Default` it is
+/// replaced by the default value, with `OnError::Propagate` it is returned to the
+/// call site, which applies its usual error policy.
+#[track_caller]
+#[inline(always)]
+pub(crate) fn expect_hook<H: CurveHooks, T: Default>(
+    result: Result<T, ()>,
+    name: &'static str,
+) -> Result<T, ()> {
+    match (result, H::HOOK_POLICY) {
+        (Err(()), OnError::Panic) => panic!("hook `{name}` failed"),
+        (Err(()), OnError::Default) => Ok(T::default()),
+        (res, _) => res,
+    }
+}
+
+/// Hook call result, handled according to the `HOOK_POLICY` of `H` but for
+/// `OnError::Default`, which is handled as `OnError::Propagate`.
+///
+/// For the call sites where a default value would be taken for a result, e.g. the
+/// point at infinity or a zero Miller loop in a verification.
+#[track_caller]
+#[inline(always)]
+pub(crate) fn strict_hook<H: CurveHooks, T>(
+    result: Result<T, ()>,
+    name: &'static str,
+) -> Result<T, ()> {
+    match (result, H::HOOK_POLICY) {
+        (Err(()), OnError::Panic) => panic!("hook `{name}` failed"),
+        (res, _) => res,
+    }
+}
+
+/// Multiplication by the absolute value of the BLS parameter `x`, as done by the
+/// subgroup checks.
+///
+/// Jumps into the `mul_projective` hook of the group, as upstream `mul_bigint` does,
+/// unless the `native-subgroup-mul` feature asks to run it in-Rust. Both paths
+/// return the same point.
+#[inline(always)]
+pub(crate) fn mul_by_x<H: CurveHooks, C: SWCurveConfig>(p: &Projective<C>) -> Projective<C> {
+    #[cfg(not(feature = "native-subgroup-mul"))]
+    return mul_by_x_hooked::<H, C>(p);
+    #[cfg(feature = "native-subgroup-mul")]
+    return mul_by_x_native::<H, C>(p);
+}
+
+/// Negation of a projective point, shared by the public `negate_*` helpers and
+/// the subgroup checks.
+#[inline(always)]
+pub(crate) fn negate<C: SWCurveConfig>(p: &Projective<C>) -> Projective<C> {
+    Projective::new_unchecked(p.x, -p.y, p.z)
+}
+
+/// Multiplication by `|x|` jumping into the `mul_projective` hook of the group.
+#[cfg(any(test, not(feature = "native-subgroup-mul")))]
+pub(crate) fn mul_by_x_hooked<H: CurveHooks, C: SWCurveConfig>(p: &Projective<C>) -> Projective<C> {
+    C::mul_projective(p, crate::Config::<H>::X)
+}
+
+/// In-Rust multiplication by `|x|` with a double-and-add over the bits of `|x|`.
+#[cfg(any(test, feature = "native-subgroup-mul"))]
+pub(crate) fn mul_by_x_native<H: CurveHooks, C: SWCurveConfig>(p: &Projective<C>) -> Projective<C> {
+    use ark_ff::{AdditiveGroup, BitIteratorBE};
+    use ark_std::Zero;
+
+    let mut res = Projective::<C>::zero();
+    for bit in BitIteratorBE::without_leading_zeros(crate::Config::<H>::X) {
+        res.double_in_place();
+        if bit {
+            res += p;
+        }
+    }
+    res
+}
+
+/// Constant time selection of `b` if `choice` is set, of `a` otherwise.
+///
+/// Selects the Montgomery limbs of each coordinate and the infinity flag.
+#[cfg(feature = "ct")]
+pub(crate) fn conditional_select_affine<C: SWCurveConfig>(
+    a: &Affine<C>,
+    b: &Affine<C>,
+    choice: subtle::Choice,
+    select: fn(&C::BaseField, &C::BaseField, subtle::Choice) -> C::BaseField,
+) -> Affine<C> {
+    use subtle::ConditionallySelectable;
+
+    let infinity = u8::conditional_select(&(a.infinity as u8), &(b.infinity as u8), choice);
+    Affine {
+        x: select(&a.x, &b.x, choice),
+        y: select(&a.y, &b.y, choice),
+        infinity: infinity != 0,
+    }
+}
+
+#[cfg(feature = "ct")]
+pub(crate) fn conditional_select_fq(a: &Fq, b: &Fq, choice: subtle::Choice) -> Fq {
+    use subtle::ConditionallySelectable;
+
+    let mut limbs = [0u64; 6];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = u64::conditional_select(&a.0 .0[i], &b.0 .0[i], choice);
+    }
+    Fq::new_unchecked(BigInteger384::new(limbs))
+}
+
+#[cfg(feature = "ct")]
+pub(crate) fn conditional_select_fq2(a: &Fq2, b: &Fq2, choice: subtle::Choice) -> Fq2 {
+    Fq2::new(
+        conditional_select_fq(&a.c0, &b.c0, choice),
+        conditional_select_fq(&a.c1, &b.c1, choice),
+    )
+}
+
+/// Size of a compressed G1 point encoding.
 pub const G1_SERIALIZED_SIZE: usize = 48;
+/// Size of a compressed G2 point encoding.
 pub const G2_SERIALIZED_SIZE: usize = 96;
 
+/// Flags carried by the three most significant bits of the first byte of a ZCash
+/// BLS12-381 point encoding.
+///
+/// | bit | flag                         |
+/// |-----|------------------------------|
+/// | 7   | compression                  |
+/// | 6   | infinity                     |
+/// | 5   | sort, i.e. `y` is the largest of `y` and `-y` (compressed only) |
+///
+/// The remaining bits of the first byte belong to the big-endian `x` coordinate.
+///
+/// ```
+/// use ark_bls12_381_ext::util::{EncodingFlags, G1_SERIALIZED_SIZE};
+///
+/// let flags = EncodingFlags {
+///     is_compressed: true,
+///     is_infinity: false,
+///     is_lexographically_largest: true,
+/// };
+/// let mut bytes = [0u8; G1_SERIALIZED_SIZE];
+/// flags.encode_flags(&mut bytes);
+/// assert_eq!(bytes[0], 0b1010_0000);
+///
+/// let decoded = EncodingFlags::get_flags(bytes.to_vec());
+/// assert!(decoded.is_compressed);
+/// assert!(!decoded.is_infinity);
+/// assert!(decoded.is_lexographically_largest);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct EncodingFlags {
+    /// Compression flag (bit 7).
     pub is_compressed: bool,
+    /// Infinity flag (bit 6).
     pub is_infinity: bool,
+    /// Sort flag (bit 5).
     pub is_lexographically_largest: bool,
 }
 
 impl EncodingFlags {
+    /// Read the flags from the first byte of `bytes`.
+    ///
+    /// Panics if `bytes` is empty.
     pub fn get_flags(bytes: Vec<u8>) -> Self {
-        let compression_flag_set = (bytes[0] >> 7) & 1;
-        let infinity_flag_set = (bytes[0] >> 6) & 1;
-        let sort_flag_set = (bytes[0] >> 5) & 1;
-
-        Self {
-            is_compressed: compression_flag_set == 1,
-            is_infinity: infinity_flag_set == 1,
-            is_lexographically_largest: sort_flag_set == 1,
-        }
+        decode_flags(&bytes).expect("no flags in an empty encoding")
     }
+
+    /// Set the flags in the first byte of `bytes`, leaving the other bits untouched.
+    ///
+    /// The sort flag is only set for compressed encodings of points other than the
+    /// point at infinity. Panics if `bytes` is empty.
     pub fn encode_flags(&self, bytes: &mut [u8]) {
         if self.is_compressed {
             bytes[0] |= 1 << 7;
@@ -43,6 +238,34 @@ impl EncodingFlags {
     }
 }
 
+/// Read the flags from the first byte of `bytes`, the inverse of `encode_flags`.
+///
+/// The three bits are read as they are, without checking that the combination is
+/// valid: e.g. the sort flag of the point at infinity is reported, while decoding
+/// such a point fails. Returns `None` if `bytes` is empty.
+pub fn decode_flags(bytes: &[u8]) -> Option<EncodingFlags> {
+    let first = bytes.first()?;
+    let compression_flag_set = (first >> 7) & 1;
+    let infinity_flag_set = (first >> 6) & 1;
+    let sort_flag_set = (first >> 5) & 1;
+
+    Some(EncodingFlags {
+        is_compressed: compression_flag_set == 1,
+        is_infinity: infinity_flag_set == 1,
+        is_lexographically_largest: sort_flag_set == 1,
+    })
+}
+
+/// Check that an infinity encoding has no bit set other than the compression and
+/// infinity flags, as required by the ZCash BLS12-381 encoding.
+fn check_infinity_encoding(bytes: &[u8]) -> Result<(), SerializationError> {
+    if bytes[0] & 0b0011_1111 == 0 && bytes[1..].iter().all(|b| *b == 0) {
+        Ok(())
+    } else {
+        Err(SerializationError::InvalidData)
+    }
+}
+
 pub(crate) fn deserialize_fq(bytes: [u8; 48]) -> Option<Fq> {
     let mut tmp = BigInteger384::new([0, 0, 0, 0, 0, 0]);
 
@@ -90,6 +313,32 @@ pub(crate) fn read_fq_with_offset(
     deserialize_fq(tmp).ok_or(SerializationError::InvalidData)
 }
 
+/// Serialize an `Fq2` element as two big-endian `Fq` components, ordered by `twist`.
+pub(crate) fn serialize_fq2(field: Fq2, twist: &TwistType) -> [u8; G2_SERIALIZED_SIZE] {
+    let mut result = [0u8; G2_SERIALIZED_SIZE];
+    fp2_codec::write_fp2(&field, twist.into(), &mut result);
+    result
+}
+
+/// Read the `offset`-th `Fq2` element, its components ordered by `twist`.
+///
+/// If `mask` is set the flag bits of the first component are ignored.
+pub(crate) fn read_fq2_with_offset(
+    bytes: Vec<u8>,
+    offset: usize,
+    mask: bool,
+    twist: &TwistType,
+) -> Result<Fq2, SerializationError> {
+    let mut tmp = [0u8; G2_SERIALIZED_SIZE];
+    tmp.copy_from_slice(&bytes[offset * G2_SERIALIZED_SIZE..G2_SERIALIZED_SIZE * (offset + 1)]);
+
+    if mask {
+        // Mask away the flag bits
+        tmp[0] &= 0b0001_1111;
+    }
+    fp2_codec::read_fp2(&tmp, twist.into()).ok_or(SerializationError::InvalidData)
+}
+
 pub(crate) fn read_g1_compressed<R: Read, H: CurveHooks>(
     mut reader: R,
 ) -> Result<Affine<G1Config<H>>, SerializationError> {
@@ -108,6 +357,7 @@ pub(crate) fn read_g1_compressed<R: Read, H: CurveHooks>(
     }
 
     if flags.is_infinity {
+        check_infinity_encoding(&bytes)?;
         return Ok(Affine::<G1Config<H>>::zero());
     }
 
@@ -137,6 +387,7 @@ pub(crate) fn read_g1_uncompressed<R: Read, H: CurveHooks>(
     }
 
     if flags.is_infinity {
+        check_infinity_encoding(&bytes)?;
         return Ok(Affine::<G1Config<H>>::zero());
     }
 
@@ -150,8 +401,40 @@ pub(crate) fn read_g1_uncompressed<R: Read, H: CurveHooks>(
     Ok(p)
 }
 
+/// Decode a G1 point whose encoding is told apart by its compression flag.
+///
+/// The compressed (48 bytes) or uncompressed (96 bytes) decoding is selected
+/// by the flag in `bytes[0]`, then the length of `bytes` must match it exactly,
+/// otherwise `UnexpectedFlags` is returned. The point is validated as by
+/// `deserialize_with_mode` with `Validate::Yes`.
+pub fn read_g1_auto<H: CurveHooks>(
+    bytes: &[u8],
+) -> Result<Affine<G1Config<H>>, SerializationError> {
+    let compressed = decode_flags(bytes)
+        .ok_or(SerializationError::InvalidData)?
+        .is_compressed;
+    let len = if compressed {
+        G1_SERIALIZED_SIZE
+    } else {
+        2 * G1_SERIALIZED_SIZE
+    };
+    if bytes.len() != len {
+        return Err(SerializationError::UnexpectedFlags);
+    }
+    let p = if compressed {
+        read_g1_compressed(bytes)?
+    } else {
+        read_g1_uncompressed(bytes)?
+    };
+    if !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(p)
+}
+
 pub(crate) fn read_g2_compressed<R: Read, H: CurveHooks>(
     mut reader: R,
+    twist: &TwistType,
 ) -> Result<Affine<G2Config<H>>, SerializationError> {
     let mut bytes = [0u8; G2_SERIALIZED_SIZE];
     reader
@@ -167,14 +450,12 @@ pub(crate) fn read_g2_compressed<R: Read, H: CurveHooks>(
     }
 
     if flags.is_infinity {
+        check_infinity_encoding(&bytes)?;
         return Ok(Affine::<G2Config<H>>::zero());
     }
 
     // Attempt to obtain the x-coordinate
-    let xc1 = read_fq_with_offset(bytes.to_vec(), 0, true)?;
-    let xc0 = read_fq_with_offset(bytes.to_vec(), 1, false)?;
-
-    let x = Fq2::new(xc0, xc1);
+    let x = read_fq2_with_offset(bytes.to_vec(), 0, true, twist)?;
 
     let p = Affine::<G2Config<H>>::get_point_from_x_unchecked(x, flags.is_lexographically_largest)
         .ok_or(SerializationError::InvalidData)?;
@@ -184,6 +465,7 @@ pub(crate) fn read_g2_compressed<R: Read, H: CurveHooks>(
 
 pub(crate) fn read_g2_uncompressed<R: Read, H: CurveHooks>(
     mut reader: R,
+    twist: &TwistType,
 ) -> Result<Affine<G2Config<H>>, SerializationError> {
     let mut bytes = [0u8; 2 * G2_SERIALIZED_SIZE];
     reader
@@ -199,18 +481,14 @@ pub(crate) fn read_g2_uncompressed<R: Read, H: CurveHooks>(
     }
 
     if flags.is_infinity {
+        check_infinity_encoding(&bytes)?;
         return Ok(Affine::<G2Config<H>>::zero());
     }
 
     // Attempt to obtain the x-coordinate
-    let xc1 = read_fq_with_offset(bytes.to_vec(), 0, true)?;
-    let xc0 = read_fq_with_offset(bytes.to_vec(), 1, false)?;
-    let x = Fq2::new(xc0, xc1);
-
+    let x = read_fq2_with_offset(bytes.to_vec(), 0, true, twist)?;
     // Attempt to obtain the y-coordinate
-    let yc1 = read_fq_with_offset(bytes.to_vec(), 2, false)?;
-    let yc0 = read_fq_with_offset(bytes.to_vec(), 3, false)?;
-    let y = Fq2::new(yc0, yc1);
+    let y = read_fq2_with_offset(bytes.to_vec(), 1, false, twist)?;
 
     let p = Affine::<G2Config<H>>::new_unchecked(x, y);
 
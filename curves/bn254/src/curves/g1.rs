@@ -0,0 +1,40 @@
+use crate::CurveHooks;
+
+use ark_bn254::{g1::Config as ArkConfig, Fq, Fr};
+use ark_models_ext::{
+    bn,
+    host::{HostConfig, HostCurve, SWHostCurve},
+};
+use ark_std::marker::PhantomData;
+
+pub use ark_bn254::g1::{G1_GENERATOR_X, G1_GENERATOR_Y};
+
+pub type G1Affine<H> = bn::G1Affine<crate::Config<H>>;
+pub type G1Projective<H> = bn::G1Projective<crate::Config<H>>;
+
+pub type Config<H> = HostConfig<Host<H>>;
+
+/// Binding of G1 to the `CurveHooks` host functions.
+pub struct Host<H: CurveHooks>(PhantomData<fn() -> H>);
+
+impl<H: CurveHooks> HostCurve for Host<H> {
+    type ArkConfig = ArkConfig;
+}
+
+impl<H: CurveHooks> SWHostCurve for Host<H> {
+    const GENERATOR_X: Fq = G1_GENERATOR_X;
+    const GENERATOR_Y: Fq = G1_GENERATOR_Y;
+
+    fn msm(bases: &[G1Affine<H>], scalars: &[Fr]) -> Result<G1Projective<H>, ()> {
+        H::bn254_msm_g1(bases, scalars)
+    }
+
+    fn mul_projective(base: &G1Projective<H>, scalar: &[u64]) -> Result<G1Projective<H>, ()> {
+        H::bn254_mul_projective_g1(base, scalar)
+    }
+
+    /// G1 is the whole curve `E(Fq)`, so every point on the curve is in the subgroup.
+    fn is_in_correct_subgroup_assuming_on_curve(_p: &G1Affine<H>) -> bool {
+        true
+    }
+}
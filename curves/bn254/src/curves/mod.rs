@@ -0,0 +1,164 @@
+use ark_bn254::Config as ArkConfig;
+use ark_ec::bn::BnConfig as ArkBnConfig;
+use ark_models_ext::{
+    bn::{Bn, BnConfig, G1Prepared, G2Prepared, TwistType},
+    pairing::{MillerLoopOutput, Pairing, PairingOutput},
+    CurveConfig,
+};
+use ark_std::marker::PhantomData;
+
+pub mod g1;
+pub mod g2;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::{
+    g1::{G1Affine, G1Projective},
+    g2::{G2Affine, G2Projective},
+};
+
+/// Hooks for *BN254* curve.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the BN254 `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
+pub trait CurveHooks: 'static + Sized {
+    /// Pairing multi Miller loop.
+    fn bn254_multi_miller_loop(
+        g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()>;
+
+    /// Pairing final exponentiation.
+    fn bn254_final_exponentiation(
+        target: <Bn254<Self> as Pairing>::TargetField,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()>;
+
+    /// Multi scalar multiplication on G1.
+    fn bn254_msm_g1(
+        bases: &[g1::G1Affine<Self>],
+        scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g1::G1Projective<Self>, ()>;
+
+    /// Multi scalar multiplication on G2.
+    fn bn254_msm_g2(
+        bases: &[g2::G2Affine<Self>],
+        scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g2::G2Projective<Self>, ()>;
+
+    /// Projective multiplication on G1.
+    fn bn254_mul_projective_g1(
+        base: &g1::G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<g1::G1Projective<Self>, ()>;
+
+    /// Projective multiplication on G2.
+    fn bn254_mul_projective_g2(
+        base: &g2::G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<g2::G2Projective<Self>, ()>;
+}
+
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
+
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn bn254_multi_miller_loop(
+        _g1: impl Iterator<Item = <Bn254<Self> as Pairing>::G1Prepared>,
+        _g2: impl Iterator<Item = <Bn254<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bn254_final_exponentiation(
+        _target: <Bn254<Self> as Pairing>::TargetField,
+    ) -> Result<<Bn254<Self> as Pairing>::TargetField, ()> {
+        Err(())
+    }
+
+    fn bn254_msm_g1(
+        _bases: &[g1::G1Affine<Self>],
+        _scalars: &[<g1::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g1::G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bn254_msm_g2(
+        _bases: &[g2::G2Affine<Self>],
+        _scalars: &[<g2::Config<Self> as CurveConfig>::ScalarField],
+    ) -> Result<g2::G2Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bn254_mul_projective_g1(
+        _base: &g1::G1Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<g1::G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bn254_mul_projective_g2(
+        _base: &g2::G2Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<g2::G2Projective<Self>, ()> {
+        Err(())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Config<H: CurveHooks>(PhantomData<fn() -> H>);
+
+pub type Bn254<H> = Bn<Config<H>>;
+
+impl<H: CurveHooks> BnConfig for Config<H> {
+    const X: &'static [u64] = <ArkConfig as ArkBnConfig>::X;
+    const X_IS_NEGATIVE: bool = <ArkConfig as ArkBnConfig>::X_IS_NEGATIVE;
+    const ATE_LOOP_COUNT: &'static [i8] = <ArkConfig as ArkBnConfig>::ATE_LOOP_COUNT;
+    // Unlike BLS12-381, BN254 is a D-type twist.
+    const TWIST_TYPE: TwistType = <ArkConfig as ArkBnConfig>::TWIST_TYPE;
+    const TWIST_MUL_BY_Q_X: <Self::G2Config as CurveConfig>::BaseField =
+        <ArkConfig as ArkBnConfig>::TWIST_MUL_BY_Q_X;
+    const TWIST_MUL_BY_Q_Y: <Self::G2Config as CurveConfig>::BaseField =
+        <ArkConfig as ArkBnConfig>::TWIST_MUL_BY_Q_Y;
+
+    type Fp = <ArkConfig as ArkBnConfig>::Fp;
+    type Fp2Config = <ArkConfig as ArkBnConfig>::Fp2Config;
+    type Fp6Config = <ArkConfig as ArkBnConfig>::Fp6Config;
+    type Fp12Config = <ArkConfig as ArkBnConfig>::Fp12Config;
+
+    type G1Config = g1::Config<H>;
+    type G2Config = g2::Config<H>;
+
+    /// Multi Miller loop jumping into the user-defined `multi_miller_loop` hook.
+    ///
+    /// For any external error returns `MillerLoopOutput(TargetField::zero())`.
+    #[inline(always)]
+    fn multi_miller_loop(
+        g1: impl IntoIterator<Item = impl Into<G1Prepared<Self>>>,
+        g2: impl IntoIterator<Item = impl Into<G2Prepared<Self>>>,
+    ) -> MillerLoopOutput<Bn<Self>> {
+        let g1 = g1.into_iter().map(|item| item.into());
+        let g2 = g2.into_iter().map(|item| item.into());
+        let res = H::bn254_multi_miller_loop(g1, g2);
+        MillerLoopOutput(res.unwrap_or_default())
+    }
+
+    /// Final exponentiation jumping into the user-defined `final_exponentiation` hook.
+    ///
+    /// For any external error returns `None`.
+    #[inline(always)]
+    fn final_exponentiation(target: MillerLoopOutput<Bn<Self>>) -> Option<PairingOutput<Bn<Self>>> {
+        let res = H::bn254_final_exponentiation(target.0);
+        res.map(PairingOutput).ok()
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{CurveHooks, Fq, Fq2};
+
+use ark_algebra_test_templates::*;
+use ark_bn254::{g1::Config as ArkG1Config, g2::Config as ArkG2Config, Bn254 as ArkBn254};
+use ark_ff::{BigInteger, PrimeField};
+use ark_models_ext::{
+    pairing::{Pairing, PairingOutput},
+    AffineRepr, CurveConfig,
+};
+use ark_std::{vec::Vec, Zero};
+
+struct TestHooks;
+
+type Bn254 = crate::Bn254<TestHooks>;
+type G1Projective = crate::G1Projective<TestHooks>;
+type G2Projective = crate::G2Projective<TestHooks>;
+type G1Affine = crate::G1Affine<TestHooks>;
+type G2Affine = crate::G2Affine<TestHooks>;
+type G1Config = crate::g1::Config<TestHooks>;
+type G2Config = crate::g2::Config<TestHooks>;
+
+impl CurveHooks for TestHooks {
+    fn bn254_multi_miller_loop(
+        g1: impl Iterator<Item = <Bn254 as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bn254 as Pairing>::G2Prepared>,
+    ) -> Result<<Bn254 as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bn254, ArkBn254>(g1, g2)
+    }
+
+    fn bn254_final_exponentiation(
+        target: <Bn254 as Pairing>::TargetField,
+    ) -> Result<<Bn254 as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bn254, ArkBn254>(target)
+    }
+
+    fn bn254_msm_g1(
+        bases: &[G1Affine],
+        scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<G1Projective, ()> {
+        test_utils::msm_sw_generic::<G1Config, ArkG1Config>(bases, scalars)
+    }
+
+    fn bn254_msm_g2(
+        bases: &[G2Affine],
+        scalars: &[<G2Config as CurveConfig>::ScalarField],
+    ) -> Result<G2Projective, ()> {
+        test_utils::msm_sw_generic::<G2Config, ArkG2Config>(bases, scalars)
+    }
+
+    fn bn254_mul_projective_g1(base: &G1Projective, scalar: &[u64]) -> Result<G1Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G1Config, ArkG1Config>(base, scalar)
+    }
+
+    fn bn254_mul_projective_g2(base: &G2Projective, scalar: &[u64]) -> Result<G2Projective, ()> {
+        test_utils::mul_projective_sw_generic::<G2Config, ArkG2Config>(base, scalar)
+    }
+}
+
+test_group!(g1; G1Projective; sw);
+test_group!(g2; G2Projective; sw);
+test_group!(pairing_output; PairingOutput<Bn254>; msm);
+test_pairing!(pairing; crate::Bn254<super::TestHooks>);
+
+/// Decodes an EIP-197 pairing check input.
+///
+/// Each pair is encoded as `x, y` for G1 followed by `x.c1, x.c0, y.c1, y.c0` for G2,
+/// where every element is a 32 bytes big-endian integer.
+fn decode_eip197_input(input: &str) -> (Vec<G1Affine>, Vec<G2Affine>) {
+    let words: Vec<Fq> = input
+        .as_bytes()
+        .chunks(64)
+        .map(|word| {
+            let bytes: Vec<u8> = word
+                .chunks(2)
+                .map(|c| u8::from_str_radix(core::str::from_utf8(c).unwrap(), 16).unwrap())
+                .collect();
+            Fq::from_be_bytes_mod_order(&bytes)
+        })
+        .collect();
+    words
+        .chunks(6)
+        .map(|w| {
+            let g1 = G1Affine::new(w[0], w[1]);
+            let g2 = G2Affine::new(Fq2::new(w[3], w[2]), Fq2::new(w[5], w[4]));
+            (g1, g2)
+        })
+        .unzip()
+}
+
+/// "jeff1" vector from go-ethereum `bn256Pairing.json` precompile test data.
+const EIP197_JEFF1: &str = concat!(
+    "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59",
+    "3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41",
+    "209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7",
+    "04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678",
+    "2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d",
+    "120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550",
+    "111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c",
+    "2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411",
+    "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+    "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+    "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+    "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+);
+
+#[test]
+fn eip197_pairing_check() {
+    let (g1, g2) = decode_eip197_input(EIP197_JEFF1);
+    assert_eq!(g2[1], G2Affine::generator());
+
+    // The precompile returns success iff the product of the pairings is one.
+    assert!(Bn254::multi_pairing(&g1, &g2).is_zero());
+
+    // Tampering with one of the inputs breaks the check.
+    let tampered = [g1[0], (g1[1] + g1[1]).into()];
+    assert!(!Bn254::multi_pairing(tampered, &g2).is_zero());
+}
+
+#[test]
+fn scalar_limbs_match_mul_bigint() {
+    use ark_ff::PrimeField;
+    use ark_models_ext::{short_weierstrass::SWCurveConfig, PrimeGroup};
+    use ark_std::{test_rng, UniformRand};
+
+    let mut rng = test_rng();
+    let s = <G1Config as CurveConfig>::ScalarField::rand(&mut rng);
+    let limbs = test_utils::scalar_limbs::<G1Config>(&s);
+    let p = G1Projective::rand(&mut rng);
+    assert_eq!(
+        G1Config::mul_projective(&p, &limbs),
+        p.mul_bigint(s.into_bigint())
+    );
+    let q = G2Projective::rand(&mut rng);
+    assert_eq!(
+        G2Config::mul_projective(&q, &limbs),
+        q.mul_bigint(s.into_bigint())
+    );
+}
+
+#[test]
+fn serialization_invariants() {
+    test_utils::check_sw_serialization::<G1Config>(16);
+    test_utils::check_sw_serialization::<G2Config>(16);
+}
+
+#[test]
+fn g2_coordinates_follow_the_twist_layout() {
+    use crate::Config;
+    use ark_models_ext::{
+        bn::BnConfig,
+        fp2_codec::{fp_size, read_fp2, write_fp2, Fp2Order},
+    };
+
+    // EIP-197 puts `c1` first, the D twist notwithstanding.
+    let order = Fp2Order::from(&<Config<TestHooks> as BnConfig>::TWIST_TYPE);
+    assert_eq!(order, Fp2Order::C1First);
+    assert_eq!(fp_size::<Fq>(), 32);
+
+    // G2 abscissa of the first pair of the "jeff1" vector, i.e. its 3rd and 4th words.
+    let jeff1 = EIP197_JEFF1.as_bytes()[2 * 64..4 * 64]
+        .chunks(2)
+        .map(|c| u8::from_str_radix(core::str::from_utf8(c).unwrap(), 16).unwrap())
+        .collect::<Vec<u8>>();
+    let (_, g2) = decode_eip197_input(EIP197_JEFF1);
+    let x = g2[0].x;
+    assert_eq!(read_fp2::<ark_bn254::Fq2Config>(&jeff1, order), Some(x));
+    let mut bytes = [0u8; 64];
+    write_fp2(&x, order, &mut bytes);
+    assert_eq!(bytes[..], jeff1[..]);
+    assert_eq!(bytes[..32], x.c1.into_bigint().to_bytes_be()[..]);
+
+    // Non canonical component.
+    bytes[..32].copy_from_slice(&Fq::MODULUS.to_bytes_be());
+    assert_eq!(read_fp2::<ark_bn254::Fq2Config>(&bytes, order), None);
+    assert_eq!(read_fp2::<ark_bn254::Fq2Config>(&bytes[1..], order), None);
+}
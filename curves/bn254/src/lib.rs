@@ -0,0 +1,40 @@
+//! This library implements the BN254 curve that was sampled as part of the [\[BCTV14\]](https://eprint.iacr.org/2013/879.pdf) paper.
+//! The name denotes that it is a Barreto--Naehrig curve of embedding degree 12,
+//! defined over a 254-bit (prime) field. The scalar field is highly 2-adic.
+//!
+//! This is the same as the `alt_bn128` curve used by the Ethereum precompiles
+//! specified in [EIP-196](https://eips.ethereum.org/EIPS/eip-196) and
+//! [EIP-197](https://eips.ethereum.org/EIPS/eip-197).
+//!
+//! **This curve does not satisfy the 128-bit security level anymore.**
+//!
+//! Curve information:
+//! * Base field: q =
+//!   21888242871839275222246405745257275088696311157297823662689037894645226208583
+//! * Scalar field: r =
+//!   21888242871839275222246405745257275088548364400416034343698204186575808495617
+//! * valuation(q - 1, 2) = 1
+//! * valuation(r - 1, 2) = 28
+//! * G1 curve equation: y^2 = x^3 + 3
+//! * G2 curve equation: y^2 = x^3 + B, where
+//!    * B = 3/(u+9) where Fq2 is represented as Fq\[u\]/(u^2+1)
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(
+    warnings,
+    unused,
+    future_incompatible,
+    nonstandard_style,
+    rust_2018_idioms,
+    unsafe_code
+)]
+#![allow(clippy::result_unit_err)]
+
+mod curves;
+
+pub use ark_bn254::{fq, fq::*, fq12, fq12::*, fq2, fq2::*, fq6, fq6::*, fr, fr::*};
+
+pub use curves::*;
+
+#[cfg(feature = "r1cs")]
+pub use ark_bn254::constraints;
@@ -0,0 +1,76 @@
+//! One layer recursion over the BLS12-377 / BW6-761 cycle.
+//!
+//! The BW6-761 scalar field is the BLS12-377 base field, thus the coordinates of a
+//! BLS12-377 target group element are BW6-761 scalars as they are. This allows the
+//! outer BW6-761 side to commit to the outputs of inner BLS12-377 pairings and to
+//! check such commitments with a BW6-761 pairing equation.
+
+use crate::{CurveHooks, Fr, G1Affine, G1Projective, G2Affine, BW6_761};
+
+use ark_bls12_377_ext::Bls12_377;
+use ark_ff::Field;
+use ark_models_ext::{
+    pairing::{Pairing, PairingOutput},
+    short_weierstrass::SWCurveConfig,
+    AffineRepr,
+};
+use ark_std::{vec::Vec, Zero};
+
+/// Number of BW6-761 scalars encoding a BLS12-377 target group element.
+pub const TARGET_SCALARS: usize = 12;
+
+/// BLS12-377 pairing output.
+pub type InnerTarget<H> = PairingOutput<Bls12_377<H>>;
+
+/// BW6-761 scalars encoding the BLS12-377 target group element `target`.
+pub fn target_to_scalars<H: ark_bls12_377_ext::CurveHooks>(
+    target: &InnerTarget<H>,
+) -> [Fr; TARGET_SCALARS] {
+    let mut scalars = [Fr::zero(); TARGET_SCALARS];
+    scalars
+        .iter_mut()
+        .zip(target.0.to_base_prime_field_elements())
+        .for_each(|(s, c)| *s = c);
+    scalars
+}
+
+/// Commitment to the BLS12-377 pairing outputs `targets` over `key`.
+///
+/// `key` holds `TARGET_SCALARS` BW6-761 bases per target.
+/// On length mismatch returns the expected key length, on any internal error
+/// returns `Err(0)` as the MSM does.
+pub fn commit_inner_bls12_377<H: CurveHooks, H377: ark_bls12_377_ext::CurveHooks>(
+    targets: &[InnerTarget<H377>],
+    key: &[G1Affine<H>],
+) -> Result<G1Projective<H>, usize> {
+    if key.len() != targets.len() * TARGET_SCALARS {
+        return Err(targets.len() * TARGET_SCALARS);
+    }
+    let scalars: Vec<Fr> = targets
+        .iter()
+        .flat_map(|target| target_to_scalars::<H377>(target))
+        .collect();
+    crate::g1::Config::<H>::msm(key, &scalars).map_err(|_| 0)
+}
+
+/// Verify that `commitment` commits to the BLS12-377 pairing outputs `targets` over `key`.
+///
+/// Runs the BW6-761 pairing check `e(commitment, h) == e(commit(targets), h)`
+/// as a single multi pairing. Returns `false` on any error.
+#[must_use]
+pub fn verify_inner_bls12_377<H: CurveHooks, H377: ark_bls12_377_ext::CurveHooks>(
+    targets: &[InnerTarget<H377>],
+    key: &[G1Affine<H>],
+    commitment: &G1Affine<H>,
+    h: &G2Affine<H>,
+) -> bool {
+    if h.is_zero() {
+        return false;
+    }
+    let Ok(expected) = commit_inner_bls12_377::<H, H377>(targets, key) else {
+        return false;
+    };
+    let g1 = [commitment.into_group(), -expected];
+    let target = BW6_761::<H>::multi_miller_loop(g1, [*h, *h]);
+    BW6_761::<H>::final_exponentiation(target).is_some_and(|res| res.is_zero())
+}
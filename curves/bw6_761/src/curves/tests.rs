@@ -58,3 +58,192 @@ test_group!(g1; G1Projective; sw);
 test_group!(g2; G2Projective; sw);
 test_group!(pairing_output; PairingOutput<BW6_761>; msm);
 test_pairing!(pairing; crate::BW6_761<super::TestHooks>);
+
+struct InnerHooks;
+
+type Bls12_377 = ark_bls12_377_ext::Bls12_377<InnerHooks>;
+type InnerG1Config = ark_bls12_377_ext::g1::Config<InnerHooks>;
+type InnerG2Config = ark_bls12_377_ext::g2::Config<InnerHooks>;
+
+impl ark_bls12_377_ext::CurveHooks for InnerHooks {
+    fn bls12_377_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_377 as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_377 as Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_377 as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bls12_377, ark_bls12_377::Bls12_377>(g1, g2)
+    }
+
+    fn bls12_377_final_exponentiation(
+        target: <Bls12_377 as Pairing>::TargetField,
+    ) -> Result<<Bls12_377 as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bls12_377, ark_bls12_377::Bls12_377>(target)
+    }
+
+    fn bls12_377_msm_g1(
+        bases: &[ark_bls12_377_ext::G1Affine<Self>],
+        scalars: &[ark_bls12_377_ext::Fr],
+    ) -> Result<ark_bls12_377_ext::G1Projective<Self>, ()> {
+        test_utils::msm_sw_generic::<InnerG1Config, ark_bls12_377::g1::Config>(bases, scalars)
+    }
+
+    fn bls12_377_msm_g2(
+        bases: &[ark_bls12_377_ext::G2Affine<Self>],
+        scalars: &[ark_bls12_377_ext::Fr],
+    ) -> Result<ark_bls12_377_ext::G2Projective<Self>, ()> {
+        test_utils::msm_sw_generic::<InnerG2Config, ark_bls12_377::g2::Config>(bases, scalars)
+    }
+
+    fn bls12_377_mul_projective_g1(
+        base: &ark_bls12_377_ext::G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<ark_bls12_377_ext::G1Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<InnerG1Config, ark_bls12_377::g1::Config>(
+            base, scalar,
+        )
+    }
+
+    fn bls12_377_mul_projective_g2(
+        base: &ark_bls12_377_ext::G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<ark_bls12_377_ext::G2Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<InnerG2Config, ark_bls12_377::g2::Config>(
+            base, scalar,
+        )
+    }
+}
+
+#[test]
+fn verify_inner_bls12_377_pairing_commitment() {
+    use crate::recursion::{
+        commit_inner_bls12_377, target_to_scalars, verify_inner_bls12_377, TARGET_SCALARS,
+    };
+    use ark_models_ext::{AffineRepr, CurveGroup};
+    use ark_std::{test_rng, UniformRand};
+
+    let mut rng = test_rng();
+    let a = ark_bls12_377_ext::G1Affine::<InnerHooks>::rand(&mut rng);
+    let b = ark_bls12_377_ext::G2Affine::<InnerHooks>::rand(&mut rng);
+    let s = ark_bls12_377_ext::Fr::rand(&mut rng);
+    // e(sA, B) = e(A, sB)
+    let targets = [Bls12_377::pairing(a * s, b), Bls12_377::pairing(a, b * s)];
+    assert_eq!(targets[0], targets[1]);
+    assert_eq!(
+        target_to_scalars::<InnerHooks>(&targets[0]).len(),
+        TARGET_SCALARS
+    );
+
+    let key: Vec<G1Affine> = (0..2 * TARGET_SCALARS)
+        .map(|_| G1Affine::rand(&mut rng))
+        .collect();
+    let h = G2Affine::generator();
+    let commitment = commit_inner_bls12_377::<TestHooks, InnerHooks>(&targets, &key)
+        .unwrap()
+        .into_affine();
+    assert!(verify_inner_bls12_377::<TestHooks, InnerHooks>(
+        &targets,
+        &key,
+        &commitment,
+        &h
+    ));
+
+    let other = [targets[0], Bls12_377::pairing(a, b)];
+    assert!(!verify_inner_bls12_377::<TestHooks, InnerHooks>(
+        &other,
+        &key,
+        &commitment,
+        &h
+    ));
+    assert!(!verify_inner_bls12_377::<TestHooks, InnerHooks>(
+        &targets,
+        &key[1..],
+        &commitment,
+        &h
+    ));
+    assert_eq!(
+        commit_inner_bls12_377::<TestHooks, InnerHooks>(&targets, &key[1..]),
+        Err(2 * TARGET_SCALARS)
+    );
+
+    // A failing MSM hook is not reported as a length mismatch.
+    let key: Vec<crate::G1Affine<FailingMsmHooks>> = key
+        .iter()
+        .map(|p| crate::G1Affine::new_unchecked(p.x, p.y))
+        .collect();
+    assert_eq!(
+        commit_inner_bls12_377::<FailingMsmHooks, InnerHooks>(&targets, &key),
+        Err(0)
+    );
+}
+
+/// `TestHooks` but for the G1 MSM, which fails.
+struct FailingMsmHooks;
+
+impl CurveHooks for FailingMsmHooks {
+    fn bw6_761_multi_miller_loop(
+        g1: impl Iterator<Item = <crate::BW6_761<Self> as Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <crate::BW6_761<Self> as Pairing>::G2Prepared>,
+    ) -> Result<<crate::BW6_761<Self> as Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<crate::BW6_761<Self>, ArkBW6_761>(g1, g2)
+    }
+
+    fn bw6_761_final_exponentiation(
+        target: <crate::BW6_761<Self> as Pairing>::TargetField,
+    ) -> Result<<crate::BW6_761<Self> as Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<crate::BW6_761<Self>, ArkBW6_761>(target)
+    }
+
+    fn bw6_761_msm_g1(
+        _bases: &[crate::G1Affine<Self>],
+        _scalars: &[<G1Config as CurveConfig>::ScalarField],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn bw6_761_msm_g2(
+        bases: &[crate::G2Affine<Self>],
+        scalars: &[<G2Config as CurveConfig>::ScalarField],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        test_utils::msm_sw_generic::<crate::g2::Config<Self>, ArkG2Config>(bases, scalars)
+    }
+
+    fn bw6_761_mul_projective_g1(
+        base: &crate::G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G1Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<crate::g1::Config<Self>, ArkG1Config>(base, scalar)
+    }
+
+    fn bw6_761_mul_projective_g2(
+        base: &crate::G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<crate::G2Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<crate::g2::Config<Self>, ArkG2Config>(base, scalar)
+    }
+}
+
+#[test]
+fn scalar_limbs_match_mul_bigint() {
+    use ark_ff::PrimeField;
+    use ark_models_ext::{short_weierstrass::SWCurveConfig, PrimeGroup};
+    use ark_std::{test_rng, UniformRand};
+
+    let mut rng = test_rng();
+    let s = <G1Config as CurveConfig>::ScalarField::rand(&mut rng);
+    let limbs = test_utils::scalar_limbs::<G1Config>(&s);
+    let p = G1Projective::rand(&mut rng);
+    assert_eq!(
+        G1Config::mul_projective(&p, &limbs),
+        p.mul_bigint(s.into_bigint())
+    );
+    let q = G2Projective::rand(&mut rng);
+    assert_eq!(
+        G2Config::mul_projective(&q, &limbs),
+        q.mul_bigint(s.into_bigint())
+    );
+}
+
+#[test]
+fn serialization_invariants() {
+    test_utils::check_sw_serialization::<G1Config>(16);
+    test_utils::check_sw_serialization::<G2Config>(16);
+}
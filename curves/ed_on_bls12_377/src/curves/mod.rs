@@ -1,8 +1,8 @@
-use ark_ed_on_bls12_377::EdwardsConfig as ArkConfig;
+use ark_ed_on_bls12_377::{EdwardsConfig as ArkConfig, Fq, Fr};
 use ark_ff::MontFp;
 use ark_models_ext::{
-    twisted_edwards::{self, MontCurveConfig, TECurveConfig},
-    CurveConfig,
+    host::{HostConfig, HostCurve, TEHostCurve},
+    twisted_edwards, CurveConfig,
 };
 use ark_std::marker::PhantomData;
 
@@ -25,10 +25,13 @@ const GENERATOR_Y: <ArkConfig as CurveConfig>::BaseField =
 pub type EdwardsAffine<H> = twisted_edwards::Affine<EdwardsConfig<H>>;
 pub type EdwardsProjective<H> = twisted_edwards::Projective<EdwardsConfig<H>>;
 
-#[derive(Clone, Copy)]
-pub struct EdwardsConfig<H: CurveHooks>(PhantomData<fn() -> H>);
+pub type EdwardsConfig<H> = HostConfig<Host<H>>;
 
 /// Hooks for *Ed-on-BLS12-377*.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the Ed-on-BLS12-377 `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
 pub trait CurveHooks: 'static + Sized {
     /// Twisted Edwards multi scalar multiplication.
     fn ed_on_bls12_377_msm(
@@ -43,58 +46,53 @@ pub trait CurveHooks: 'static + Sized {
     ) -> Result<EdwardsProjective<Self>, ()>;
 }
 
-impl<H: CurveHooks> CurveConfig for EdwardsConfig<H> {
-    const COFACTOR: &'static [u64] = <ArkConfig as CurveConfig>::COFACTOR;
-    const COFACTOR_INV: Self::ScalarField = <ArkConfig as CurveConfig>::COFACTOR_INV;
-
-    type BaseField = <ArkConfig as CurveConfig>::BaseField;
-    type ScalarField = <ArkConfig as CurveConfig>::ScalarField;
-}
-
-impl<H: CurveHooks> TECurveConfig for EdwardsConfig<H> {
-    const COEFF_A: Self::BaseField = <ArkConfig as TECurveConfig>::COEFF_A;
-    const COEFF_D: Self::BaseField = <ArkConfig as TECurveConfig>::COEFF_D;
-
-    const GENERATOR: EdwardsAffine<H> = EdwardsAffine::<H>::new_unchecked(GENERATOR_X, GENERATOR_Y);
-
-    type MontCurveConfig = Self;
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
 
-    /// Multi scalar multiplication jumping into the user-defined `msm` hook.
-    ///
-    /// On any *external* error returns `Err(0)`.
-    #[inline(always)]
-    fn msm(
-        bases: &[EdwardsAffine<H>],
-        scalars: &[Self::ScalarField],
-    ) -> Result<EdwardsProjective<H>, usize> {
-        H::ed_on_bls12_377_msm(bases, scalars).map_err(|_| 0)
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn ed_on_bls12_377_msm(
+        _bases: &[EdwardsAffine<Self>],
+        _scalars: &[<EdwardsConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
     }
 
-    /// Projective multiplication jumping into the user-defined `mul_projective` hook.
-    ///
-    /// On any *external* error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_projective(base: &EdwardsProjective<H>, scalar: &[u64]) -> EdwardsProjective<H> {
-        H::ed_on_bls12_377_mul_projective(base, scalar).unwrap_or_default()
+    fn ed_on_bls12_377_mul_projective(
+        _base: &EdwardsProjective<Self>,
+        _scalar: &[u64],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
     }
+}
 
-    /// Affine multiplication jumping into the user-defined `mul_projective_g2` hook.
-    ///
-    /// On any internal error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_affine(base: &EdwardsAffine<H>, scalar: &[u64]) -> EdwardsProjective<H> {
-        Self::mul_projective(&(*base).into(), scalar)
-    }
+/// Binding of *Ed-on-BLS12-377* to the `CurveHooks` host functions.
+pub struct Host<H: CurveHooks>(PhantomData<fn() -> H>);
 
-    #[inline(always)]
-    fn mul_by_a(elem: Self::BaseField) -> Self::BaseField {
-        <ArkConfig as TECurveConfig>::mul_by_a(elem)
-    }
+impl<H: CurveHooks> HostCurve for Host<H> {
+    type ArkConfig = ArkConfig;
 }
 
-impl<H: CurveHooks> MontCurveConfig for EdwardsConfig<H> {
-    const COEFF_A: Self::BaseField = <ArkConfig as MontCurveConfig>::COEFF_A;
-    const COEFF_B: Self::BaseField = <ArkConfig as MontCurveConfig>::COEFF_B;
+impl<H: CurveHooks> TEHostCurve for Host<H> {
+    const GENERATOR_X: Fq = GENERATOR_X;
+    const GENERATOR_Y: Fq = GENERATOR_Y;
 
-    type TECurveConfig = Self;
+    fn msm(bases: &[EdwardsAffine<H>], scalars: &[Fr]) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_bls12_377_msm(bases, scalars)
+    }
+
+    fn mul_projective(
+        base: &EdwardsProjective<H>,
+        scalar: &[u64],
+    ) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_bls12_377_mul_projective(base, scalar)
+    }
 }
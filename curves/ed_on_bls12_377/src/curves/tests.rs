@@ -4,23 +4,28 @@ use ark_algebra_test_templates::*;
 use ark_ed_on_bls12_377::EdwardsConfig as ArkConfig;
 use ark_models_ext::CurveConfig;
 
-struct TestHooks;
+type TestHooks = test_utils::ArkTestHooks<ArkConfig>;
 
-type Config = crate::EdwardsConfig<TestHooks>;
-type Affine = crate::EdwardsAffine<TestHooks>;
 type Projective = crate::EdwardsProjective<TestHooks>;
 
-impl CurveHooks for TestHooks {
-    fn ed_on_bls12_377_msm(
-        bases: &[Affine],
-        scalars: &[<Config as CurveConfig>::ScalarField],
-    ) -> Result<Projective, ()> {
-        test_utils::msm_te_generic::<Config, ArkConfig>(bases, scalars)
-    }
-
-    fn ed_on_bls12_377_mul_projective(base: &Projective, scalar: &[u64]) -> Result<Projective, ()> {
-        test_utils::mul_projective_te_generic::<Config, ArkConfig>(base, scalar)
-    }
-}
+test_utils::impl_ark_test_hooks!(
+    CurveHooks => ArkConfig,
+    te: crate::EdwardsConfig,
+    ed_on_bls12_377_msm,
+    ed_on_bls12_377_mul_projective,
+);
 
 test_group!(te; Projective; te);
+
+#[test]
+#[allow(deprecated)]
+fn phantom_hooks_fail_every_operation() {
+    use ark_models_ext::{twisted_edwards::TECurveConfig, AffineRepr};
+    use ark_std::Zero;
+    type Hooks = crate::PhantomHooks;
+
+    let g = crate::EdwardsAffine::<Hooks>::generator();
+    let one = <crate::EdwardsConfig<Hooks> as CurveConfig>::ScalarField::from(1);
+    assert_eq!(crate::EdwardsConfig::<Hooks>::msm(&[g], &[one]), Err(0));
+    assert!(crate::EdwardsConfig::<Hooks>::mul_affine(&g, &[2]).is_zero());
+}
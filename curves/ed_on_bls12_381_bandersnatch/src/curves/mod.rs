@@ -1,9 +1,9 @@
-use ark_ed_on_bls12_381_bandersnatch::BandersnatchConfig as ArkConfig;
+use ark_ed_on_bls12_381_bandersnatch::{BandersnatchConfig as ArkConfig, Fq, Fr};
 use ark_ff::MontFp;
 use ark_models_ext::{
+    host::{HostConfig, HostCurve, SWHostCurve, TEHostCurve},
     models::CurveConfig,
-    short_weierstrass::{self, SWCurveConfig},
-    twisted_edwards::{self, MontCurveConfig, TECurveConfig},
+    short_weierstrass, twisted_edwards,
 };
 use ark_std::marker::PhantomData;
 
@@ -35,13 +35,16 @@ pub type EdwardsProjective<H> = twisted_edwards::Projective<BandersnatchConfig<H
 pub type SWAffine<H> = short_weierstrass::Affine<BandersnatchConfig<H>>;
 pub type SWProjective<H> = short_weierstrass::Projective<BandersnatchConfig<H>>;
 
-#[derive(Clone, Copy)]
-pub struct BandersnatchConfig<H: CurveHooks>(PhantomData<fn() -> H>);
+pub type BandersnatchConfig<H> = HostConfig<Host<H>>;
 
 pub type EdwardsConfig<H> = BandersnatchConfig<H>;
 pub type SWConfig<H> = BandersnatchConfig<H>;
 
 /// Hooks for *Ed-on-BLS12-377-Bandernatch*.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the Ed-on-BLS12-381-Bandersnatch `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
 pub trait CurveHooks: 'static + Sized {
     /// Twisted Edwards multi scalar multiplication.
     fn ed_on_bls12_381_bandersnatch_te_msm(
@@ -68,90 +71,80 @@ pub trait CurveHooks: 'static + Sized {
     ) -> Result<SWProjective<Self>, ()>;
 }
 
-impl<H: CurveHooks> CurveConfig for BandersnatchConfig<H> {
-    const COFACTOR: &'static [u64] = <ArkConfig as CurveConfig>::COFACTOR;
-    const COFACTOR_INV: Self::ScalarField = <ArkConfig as CurveConfig>::COFACTOR_INV;
-
-    type BaseField = <ArkConfig as CurveConfig>::BaseField;
-    type ScalarField = <ArkConfig as CurveConfig>::ScalarField;
-}
-
-impl<H: CurveHooks> TECurveConfig for BandersnatchConfig<H> {
-    const COEFF_A: Self::BaseField = <ArkConfig as TECurveConfig>::COEFF_A;
-    const COEFF_D: Self::BaseField = <ArkConfig as TECurveConfig>::COEFF_D;
-
-    const GENERATOR: EdwardsAffine<H> =
-        EdwardsAffine::<H>::new_unchecked(TE_GENERATOR_X, TE_GENERATOR_Y);
-
-    type MontCurveConfig = Self;
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
 
-    /// Multi scalar multiplication jumping into the user-defined `te_msm` hook.
-    ///
-    /// On any internal error returns `Err(0)`.
-    #[inline(always)]
-    fn msm(
-        bases: &[EdwardsAffine<H>],
-        scalars: &[Self::ScalarField],
-    ) -> Result<EdwardsProjective<H>, usize> {
-        H::ed_on_bls12_381_bandersnatch_te_msm(bases, scalars).map_err(|_| 0)
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn ed_on_bls12_381_bandersnatch_te_msm(
+        _bases: &[EdwardsAffine<Self>],
+        _scalars: &[<EdwardsConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
     }
 
-    /// Projective multiplication jumping into the user-defined `te_mul_projective` hook.
-    ///
-    /// On any internal error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_projective(base: &EdwardsProjective<H>, scalar: &[u64]) -> EdwardsProjective<H> {
-        H::ed_on_bls12_381_bandersnatch_te_mul_projective(base, scalar).unwrap_or_default()
+    fn ed_on_bls12_381_bandersnatch_te_mul_projective(
+        _base: &EdwardsProjective<Self>,
+        _scalar: &[u64],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
     }
 
-    /// Affine multiplication jumping into the user-defined `te_mul_projective` hook.
-    ///
-    /// On any internal error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_affine(base: &EdwardsAffine<H>, scalar: &[u64]) -> EdwardsProjective<H> {
-        <Self as TECurveConfig>::mul_projective(&(*base).into(), scalar)
+    fn ed_on_bls12_381_bandersnatch_sw_msm(
+        _bases: &[SWAffine<Self>],
+        _scalars: &[<SWConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<SWProjective<Self>, ()> {
+        Err(())
     }
 
-    #[inline(always)]
-    fn mul_by_a(elem: Self::BaseField) -> Self::BaseField {
-        <ArkConfig as TECurveConfig>::mul_by_a(elem)
+    fn ed_on_bls12_381_bandersnatch_sw_mul_projective(
+        _base: &SWProjective<Self>,
+        _scalar: &[u64],
+    ) -> Result<SWProjective<Self>, ()> {
+        Err(())
     }
 }
 
-impl<H: CurveHooks> SWCurveConfig for BandersnatchConfig<H> {
-    const COEFF_A: Self::BaseField = <ArkConfig as SWCurveConfig>::COEFF_A;
-    const COEFF_B: Self::BaseField = <ArkConfig as SWCurveConfig>::COEFF_B;
+/// Binding of *Ed-on-BLS12-381-Bandersnatch* to the `CurveHooks` host functions.
+pub struct Host<H: CurveHooks>(PhantomData<fn() -> H>);
 
-    const GENERATOR: SWAffine<H> = SWAffine::<H>::new_unchecked(SW_GENERATOR_X, SW_GENERATOR_Y);
+impl<H: CurveHooks> HostCurve for Host<H> {
+    type ArkConfig = ArkConfig;
+}
 
-    /// Multi scalar multiplication jumping into the user-defined `sw_msm` hook.
-    ///
-    /// On any internal error returns `Err(0)`.
-    #[inline(always)]
-    fn msm(bases: &[SWAffine<H>], scalars: &[Self::ScalarField]) -> Result<SWProjective<H>, usize> {
-        H::ed_on_bls12_381_bandersnatch_sw_msm(bases, scalars).map_err(|_| 0)
-    }
+impl<H: CurveHooks> TEHostCurve for Host<H> {
+    const GENERATOR_X: Fq = TE_GENERATOR_X;
+    const GENERATOR_Y: Fq = TE_GENERATOR_Y;
 
-    /// Projective multiplication jumping into the user-defined `sw_mul_projective` hook.
-    ///
-    /// On any internal error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_projective(base: &SWProjective<H>, scalar: &[u64]) -> SWProjective<H> {
-        H::ed_on_bls12_381_bandersnatch_sw_mul_projective(base, scalar).unwrap_or_default()
+    fn msm(bases: &[EdwardsAffine<H>], scalars: &[Fr]) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_bls12_381_bandersnatch_te_msm(bases, scalars)
     }
 
-    /// Affine multiplication jumping into the user-defined `sw_mul_projective` hook.
-    ///
-    /// On any internal error returns `Projective::zero()`.
-    #[inline(always)]
-    fn mul_affine(base: &SWAffine<H>, scalar: &[u64]) -> SWProjective<H> {
-        <Self as SWCurveConfig>::mul_projective(&(*base).into(), scalar)
+    fn mul_projective(
+        base: &EdwardsProjective<H>,
+        scalar: &[u64],
+    ) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_bls12_381_bandersnatch_te_mul_projective(base, scalar)
     }
 }
 
-impl<H: CurveHooks> MontCurveConfig for BandersnatchConfig<H> {
-    const COEFF_A: Self::BaseField = <ArkConfig as MontCurveConfig>::COEFF_A;
-    const COEFF_B: Self::BaseField = <ArkConfig as MontCurveConfig>::COEFF_B;
+impl<H: CurveHooks> SWHostCurve for Host<H> {
+    const GENERATOR_X: Fq = SW_GENERATOR_X;
+    const GENERATOR_Y: Fq = SW_GENERATOR_Y;
+
+    fn msm(bases: &[SWAffine<H>], scalars: &[Fr]) -> Result<SWProjective<H>, ()> {
+        H::ed_on_bls12_381_bandersnatch_sw_msm(bases, scalars)
+    }
 
-    type TECurveConfig = Self;
+    fn mul_projective(base: &SWProjective<H>, scalar: &[u64]) -> Result<SWProjective<H>, ()> {
+        H::ed_on_bls12_381_bandersnatch_sw_mul_projective(base, scalar)
+    }
 }
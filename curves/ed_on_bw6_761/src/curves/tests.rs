@@ -0,0 +1,17 @@
+use crate::CurveHooks;
+
+use ark_algebra_test_templates::*;
+use ark_ed_on_bw6_761::EdwardsConfig as ArkConfig;
+
+type TestHooks = test_utils::ArkTestHooks<ArkConfig>;
+
+type Projective = crate::EdwardsProjective<TestHooks>;
+
+test_utils::impl_ark_test_hooks!(
+    CurveHooks => ArkConfig,
+    te: crate::EdwardsConfig,
+    ed_on_bw6_761_msm,
+    ed_on_bw6_761_mul_projective,
+);
+
+test_group!(te; Projective; te);
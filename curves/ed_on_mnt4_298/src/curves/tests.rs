@@ -0,0 +1,29 @@
+use crate::CurveHooks;
+
+use ark_algebra_test_templates::*;
+use ark_ed_on_mnt4_298::EdwardsConfig as ArkConfig;
+use ark_models_ext::CurveConfig;
+
+type TestHooks = test_utils::ArkTestHooks<ArkConfig>;
+
+type Config = crate::EdwardsConfig<TestHooks>;
+type Projective = crate::EdwardsProjective<TestHooks>;
+
+test_utils::impl_ark_test_hooks!(
+    CurveHooks => ArkConfig,
+    te: crate::EdwardsConfig,
+    ed_on_mnt4_298_msm,
+    ed_on_mnt4_298_mul_projective,
+);
+
+test_group!(te; Projective; te);
+
+#[test]
+fn curve_parameters() {
+    use ark_models_ext::twisted_edwards::TECurveConfig;
+    type Fq = <Config as CurveConfig>::BaseField;
+
+    assert_eq!(<Config as CurveConfig>::COFACTOR, &[4]);
+    assert_eq!(<Config as TECurveConfig>::COEFF_A, -Fq::from(1));
+    assert_eq!(<Config as TECurveConfig>::COEFF_D, Fq::from(4212));
+}
@@ -0,0 +1,86 @@
+use ark_ed_on_mnt4_753::{EdwardsConfig as ArkConfig, Fq, Fr};
+use ark_models_ext::{
+    host::{HostConfig, HostCurve, TEHostCurve},
+    twisted_edwards, CurveConfig,
+};
+use ark_std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests;
+
+pub use ark_ed_on_mnt4_753::{GENERATOR_X, GENERATOR_Y};
+
+pub type EdwardsAffine<H> = twisted_edwards::Affine<EdwardsConfig<H>>;
+pub type EdwardsProjective<H> = twisted_edwards::Projective<EdwardsConfig<H>>;
+
+pub type EdwardsConfig<H> = HostConfig<Host<H>>;
+
+/// Hooks for *Ed-on-MNT4-753*.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the Ed-on-MNT4-753 `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
+pub trait CurveHooks: 'static + Sized {
+    /// Twisted Edwards multi scalar multiplication.
+    fn ed_on_mnt4_753_msm(
+        bases: &[EdwardsAffine<Self>],
+        scalars: &[<EdwardsConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<EdwardsProjective<Self>, ()>;
+
+    /// Twisted Edwards projective multiplication.
+    fn ed_on_mnt4_753_mul_projective(
+        base: &EdwardsProjective<Self>,
+        scalar: &[u64],
+    ) -> Result<EdwardsProjective<Self>, ()>;
+}
+
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
+
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn ed_on_mnt4_753_msm(
+        _bases: &[EdwardsAffine<Self>],
+        _scalars: &[<EdwardsConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
+    }
+
+    fn ed_on_mnt4_753_mul_projective(
+        _base: &EdwardsProjective<Self>,
+        _scalar: &[u64],
+    ) -> Result<EdwardsProjective<Self>, ()> {
+        Err(())
+    }
+}
+
+/// Binding of *Ed-on-MNT4-753* to the `CurveHooks` host functions.
+pub struct Host<H: CurveHooks>(PhantomData<fn() -> H>);
+
+impl<H: CurveHooks> HostCurve for Host<H> {
+    type ArkConfig = ArkConfig;
+}
+
+impl<H: CurveHooks> TEHostCurve for Host<H> {
+    const GENERATOR_X: Fq = GENERATOR_X;
+    const GENERATOR_Y: Fq = GENERATOR_Y;
+
+    fn msm(bases: &[EdwardsAffine<H>], scalars: &[Fr]) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_mnt4_753_msm(bases, scalars)
+    }
+
+    fn mul_projective(
+        base: &EdwardsProjective<H>,
+        scalar: &[u64],
+    ) -> Result<EdwardsProjective<H>, ()> {
+        H::ed_on_mnt4_753_mul_projective(base, scalar)
+    }
+}
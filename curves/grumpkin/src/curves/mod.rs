@@ -0,0 +1,87 @@
+use ark_grumpkin::{Fq, Fr, GrumpkinConfig as ArkConfig};
+use ark_models_ext::{
+    host::{HostConfig, HostCurve, SWHostCurve},
+    short_weierstrass, CurveConfig,
+};
+use ark_std::marker::PhantomData;
+
+#[cfg(test)]
+mod tests;
+
+pub use ark_grumpkin::{G_GENERATOR_X, G_GENERATOR_Y};
+
+pub type Affine<H> = short_weierstrass::Affine<GrumpkinConfig<H>>;
+pub type Projective<H> = short_weierstrass::Projective<GrumpkinConfig<H>>;
+
+pub type GrumpkinConfig<H> = HostConfig<Host<H>>;
+
+pub type Config<H> = GrumpkinConfig<H>;
+
+/// Hooks for *Grumpkin*.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the Grumpkin `CurveHooks`",
+    note = "implement it for a type forwarding to your host functions, see `HostHooks` in the README"
+)]
+pub trait CurveHooks: 'static + Sized {
+    /// Short Weierstrass multi scalar multiplication.
+    fn grumpkin_msm(
+        bases: &[Affine<Self>],
+        scalars: &[<GrumpkinConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<Projective<Self>, ()>;
+
+    /// Short Weierstrass projective multiplication.
+    fn grumpkin_mul_projective(
+        base: &Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<Projective<Self>, ()>;
+}
+
+/// Placeholder hooks failing every operation.
+///
+/// Allows to get a first build going, but every hooked operation fails and thus
+/// falls back to its documented error value. Replace it with a `CurveHooks`
+/// implementation forwarding to the host functions.
+#[deprecated(
+    note = "`PhantomHooks` fails every hooked operation, implement `CurveHooks` for a real host"
+)]
+#[derive(Clone, Copy)]
+pub struct PhantomHooks;
+
+#[allow(deprecated)]
+impl CurveHooks for PhantomHooks {
+    fn grumpkin_msm(
+        _bases: &[Affine<Self>],
+        _scalars: &[<GrumpkinConfig<Self> as CurveConfig>::ScalarField],
+    ) -> Result<Projective<Self>, ()> {
+        Err(())
+    }
+
+    fn grumpkin_mul_projective(
+        _base: &Projective<Self>,
+        _scalar: &[u64],
+    ) -> Result<Projective<Self>, ()> {
+        Err(())
+    }
+}
+
+/// Binding of *Grumpkin* to the `CurveHooks` host functions.
+pub struct Host<H: CurveHooks>(PhantomData<fn() -> H>);
+
+// Grumpkin swaps the BN254 fields: the base field is BN254 `Fr` while the
+// scalar field is BN254 `Fq`. Both are taken verbatim from upstream.
+impl<H: CurveHooks> HostCurve for Host<H> {
+    type ArkConfig = ArkConfig;
+}
+
+impl<H: CurveHooks> SWHostCurve for Host<H> {
+    const GENERATOR_X: Fq = G_GENERATOR_X;
+    const GENERATOR_Y: Fq = G_GENERATOR_Y;
+
+    fn msm(bases: &[Affine<H>], scalars: &[Fr]) -> Result<Projective<H>, ()> {
+        H::grumpkin_msm(bases, scalars)
+    }
+
+    fn mul_projective(base: &Projective<H>, scalar: &[u64]) -> Result<Projective<H>, ()> {
+        H::grumpkin_mul_projective(base, scalar)
+    }
+}
@@ -0,0 +1,45 @@
+use crate::CurveHooks;
+
+use ark_algebra_test_templates::*;
+use ark_grumpkin::GrumpkinConfig as ArkConfig;
+use ark_models_ext::CurveConfig;
+
+type TestHooks = test_utils::ArkTestHooks<ArkConfig>;
+
+type Config = crate::GrumpkinConfig<TestHooks>;
+type Projective = crate::Projective<TestHooks>;
+
+test_utils::impl_ark_test_hooks!(
+    CurveHooks => ArkConfig,
+    sw: crate::GrumpkinConfig,
+    grumpkin_msm,
+    grumpkin_mul_projective,
+);
+
+test_group!(sw; Projective; sw);
+
+#[test]
+fn fields_are_swapped_with_bn254() {
+    let base: <Config as CurveConfig>::BaseField = ark_bn254::Fr::from(3);
+    let scalar: <Config as CurveConfig>::ScalarField = ark_bn254::Fq::from(5);
+    assert_eq!(base, crate::Fq::from(3));
+    assert_eq!(scalar, crate::Fr::from(5));
+}
+
+#[test]
+#[allow(deprecated)]
+fn phantom_hooks_fail_every_operation() {
+    use ark_models_ext::{short_weierstrass::SWCurveConfig, AffineRepr};
+    use ark_std::Zero;
+    type Hooks = crate::PhantomHooks;
+
+    let g = crate::Affine::<Hooks>::generator();
+    let one = <crate::GrumpkinConfig<Hooks> as CurveConfig>::ScalarField::from(1);
+    assert_eq!(crate::GrumpkinConfig::<Hooks>::msm(&[g], &[one]), Err(0));
+    assert!(crate::GrumpkinConfig::<Hooks>::mul_affine(&g, &[2]).is_zero());
+}
+
+#[test]
+fn serialization_invariants() {
+    test_utils::check_sw_serialization::<Config>(16);
+}
@@ -0,0 +1,57 @@
+use crate::models::{
+    bn::BnConfig,
+    short_weierstrass::{Affine, Projective},
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::*;
+use ark_std::vec::Vec;
+use derivative::Derivative;
+
+pub type G1Affine<P> = Affine<<P as BnConfig>::G1Config>;
+pub type G1Projective<P> = Projective<<P as BnConfig>::G1Config>;
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Copy(bound = "P: BnConfig"),
+    Clone(bound = "P: BnConfig"),
+    PartialEq(bound = "P: BnConfig"),
+    Eq(bound = "P: BnConfig"),
+    Debug(bound = "P: BnConfig")
+)]
+pub struct G1Prepared<P: BnConfig>(pub G1Affine<P>);
+
+impl<P: BnConfig> From<G1Affine<P>> for G1Prepared<P> {
+    fn from(other: G1Affine<P>) -> Self {
+        G1Prepared(other)
+    }
+}
+
+impl<P: BnConfig> From<G1Projective<P>> for G1Prepared<P> {
+    fn from(q: G1Projective<P>) -> Self {
+        q.into_affine().into()
+    }
+}
+
+impl<'a, P: BnConfig> From<&'a G1Affine<P>> for G1Prepared<P> {
+    fn from(other: &'a G1Affine<P>) -> Self {
+        G1Prepared(*other)
+    }
+}
+
+impl<'a, P: BnConfig> From<&'a G1Projective<P>> for G1Prepared<P> {
+    fn from(q: &'a G1Projective<P>) -> Self {
+        q.into_affine().into()
+    }
+}
+
+impl<P: BnConfig> G1Prepared<P> {
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<P: BnConfig> Default for G1Prepared<P> {
+    fn default() -> Self {
+        G1Prepared(G1Affine::<P>::generator())
+    }
+}
@@ -0,0 +1,83 @@
+//! Big-endian encoding of quadratic extension elements for the G2 point codecs.
+//!
+//! The order of the two components of an `Fp2` element is selected from the twist
+//! type of the curve, so the G2 codecs of the BLS12 and BN curves share one layout
+//! table. The encodings in use put `c1` first whatever the twist: the ZCash one of
+//! BLS12-381 (`M` twist) as well as the EIP-197 one of BN254 (`D` twist).
+//! Flag bits are left to the caller, which masks them before decoding.
+
+use ark_ff::{BigInteger, Fp2, Fp2Config, PrimeField};
+
+use crate::{bls12, bn};
+
+/// Order of the components of an `Fp2` element in an encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fp2Order {
+    /// `c1` first, then `c0`. Selected by both twists.
+    C1First,
+    /// `c0` first, then `c1`, for the encodings asking for it explicitly.
+    C0First,
+}
+
+impl From<&bls12::TwistType> for Fp2Order {
+    fn from(twist: &bls12::TwistType) -> Self {
+        match twist {
+            bls12::TwistType::M | bls12::TwistType::D => Fp2Order::C1First,
+        }
+    }
+}
+
+impl From<&bn::TwistType> for Fp2Order {
+    fn from(twist: &bn::TwistType) -> Self {
+        match twist {
+            bn::TwistType::M | bn::TwistType::D => Fp2Order::C1First,
+        }
+    }
+}
+
+/// Size of the big-endian encoding of an element of `F`, a whole number of limbs.
+pub const fn fp_size<F: PrimeField>() -> usize {
+    F::BigInt::NUM_LIMBS * 8
+}
+
+/// Write `field` into `out` as two big-endian components laid out by `order`.
+///
+/// Panics if `out` is not `2 * fp_size::<P::Fp>()` bytes long.
+pub fn write_fp2<P: Fp2Config>(field: &Fp2<P>, order: Fp2Order, out: &mut [u8]) {
+    let size = fp_size::<P::Fp>();
+    assert_eq!(out.len(), 2 * size, "invalid Fp2 encoding length");
+    let (first, second) = match order {
+        Fp2Order::C1First => (field.c1, field.c0),
+        Fp2Order::C0First => (field.c0, field.c1),
+    };
+    out[..size].copy_from_slice(&first.into_bigint().to_bytes_be());
+    out[size..].copy_from_slice(&second.into_bigint().to_bytes_be());
+}
+
+/// Read two big-endian components laid out by `order`, the inverse of `write_fp2`.
+///
+/// Returns `None` if `bytes` is not `2 * fp_size::<P::Fp>()` bytes long or if a
+/// component is not canonical, i.e. not smaller than the modulus.
+pub fn read_fp2<P: Fp2Config>(bytes: &[u8], order: Fp2Order) -> Option<Fp2<P>> {
+    let size = fp_size::<P::Fp>();
+    if bytes.len() != 2 * size {
+        return None;
+    }
+    let first = read_fp::<P::Fp>(&bytes[..size])?;
+    let second = read_fp::<P::Fp>(&bytes[size..])?;
+    Some(match order {
+        Fp2Order::C1First => Fp2::new(second, first),
+        Fp2Order::C0First => Fp2::new(first, second),
+    })
+}
+
+/// Read a canonical big-endian element of `F` from exactly `fp_size::<F>()` bytes.
+fn read_fp<F: PrimeField>(bytes: &[u8]) -> Option<F> {
+    let mut repr = F::BigInt::default();
+    for (limb, chunk) in repr.as_mut().iter_mut().zip(bytes.rchunks_exact(8)) {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(chunk);
+        *limb = u64::from_be_bytes(buf);
+    }
+    F::from_bigint(repr)
+}
@@ -0,0 +1,214 @@
+//! Curve configurations generated from host bindings.
+//!
+//! Every hooked curve group shares the same configuration glue: constants and
+//! non hooked arithmetic are borrowed from the upstream *Arkworks* configuration,
+//! while multi scalar multiplication and projective multiplication jump into the
+//! host. A curve crate binds its hooks by implementing `SWHostCurve` (or `TEHostCurve`)
+//! for a marker type and uses `HostConfig` of that type as the group configuration.
+
+#![allow(clippy::result_unit_err)]
+
+use ark_ec::{
+    models::CurveConfig,
+    short_weierstrass::{self, SWCurveConfig},
+    twisted_edwards::{self, MontCurveConfig, TECurveConfig},
+};
+use ark_ff::Field;
+use ark_std::{marker::PhantomData, Zero};
+use derivative::Derivative;
+
+/// Base field of the curve bound by `C`.
+pub type BaseField<C> = <<C as HostCurve>::ArkConfig as CurveConfig>::BaseField;
+/// Scalar field of the curve bound by `C`.
+pub type ScalarField<C> = <<C as HostCurve>::ArkConfig as CurveConfig>::ScalarField;
+
+/// Binding of a curve group to the host.
+pub trait HostCurve: 'static + Sized {
+    /// Upstream configuration providing the curve constants and the non hooked arithmetic.
+    type ArkConfig: CurveConfig;
+}
+
+/// Host binding of a short Weierstrass curve group.
+pub trait SWHostCurve: HostCurve<ArkConfig: SWCurveConfig> {
+    /// Generator x coordinate.
+    const GENERATOR_X: BaseField<Self>;
+    /// Generator y coordinate.
+    const GENERATOR_Y: BaseField<Self>;
+
+    /// Multi scalar multiplication host function.
+    ///
+    /// Invoked with bases and scalars of the same length.
+    fn msm(
+        bases: &[short_weierstrass::Affine<HostConfig<Self>>],
+        scalars: &[ScalarField<Self>],
+    ) -> Result<short_weierstrass::Projective<HostConfig<Self>>, ()>;
+
+    /// Projective multiplication host function.
+    fn mul_projective(
+        base: &short_weierstrass::Projective<HostConfig<Self>>,
+        scalar: &[u64],
+    ) -> Result<short_weierstrass::Projective<HostConfig<Self>>, ()>;
+
+    /// Subgroup membership check.
+    ///
+    /// Defaults to the upstream generic check, i.e. multiplication by the group order.
+    fn is_in_correct_subgroup_assuming_on_curve(
+        item: &short_weierstrass::Affine<HostConfig<Self>>,
+    ) -> bool {
+        HostConfig::<Self>::cofactor_is_one()
+            || <HostConfig<Self> as SWCurveConfig>::mul_affine(
+                item,
+                ScalarField::<Self>::characteristic(),
+            )
+            .is_zero()
+    }
+}
+
+/// Host binding of a twisted Edwards curve group.
+pub trait TEHostCurve: HostCurve<ArkConfig: TECurveConfig> {
+    /// Generator x coordinate.
+    const GENERATOR_X: BaseField<Self>;
+    /// Generator y coordinate.
+    const GENERATOR_Y: BaseField<Self>;
+
+    /// Multi scalar multiplication host function.
+    ///
+    /// Invoked with bases and scalars of the same length.
+    fn msm(
+        bases: &[twisted_edwards::Affine<HostConfig<Self>>],
+        scalars: &[ScalarField<Self>],
+    ) -> Result<twisted_edwards::Projective<HostConfig<Self>>, ()>;
+
+    /// Projective multiplication host function.
+    fn mul_projective(
+        base: &twisted_edwards::Projective<HostConfig<Self>>,
+        scalar: &[u64],
+    ) -> Result<twisted_edwards::Projective<HostConfig<Self>>, ()>;
+}
+
+/// Curve group configuration jumping into the host functions bound by `C`.
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub struct HostConfig<C: HostCurve>(PhantomData<fn() -> C>);
+
+impl<C: HostCurve> CurveConfig for HostConfig<C> {
+    type BaseField = BaseField<C>;
+    type ScalarField = ScalarField<C>;
+
+    const COFACTOR: &'static [u64] = <C::ArkConfig as CurveConfig>::COFACTOR;
+    const COFACTOR_INV: Self::ScalarField = <C::ArkConfig as CurveConfig>::COFACTOR_INV;
+}
+
+impl<C: SWHostCurve> SWCurveConfig for HostConfig<C> {
+    const COEFF_A: Self::BaseField = <C::ArkConfig as SWCurveConfig>::COEFF_A;
+    const COEFF_B: Self::BaseField = <C::ArkConfig as SWCurveConfig>::COEFF_B;
+
+    const GENERATOR: short_weierstrass::Affine<Self> =
+        short_weierstrass::Affine::new_unchecked(C::GENERATOR_X, C::GENERATOR_Y);
+
+    /// Multi scalar multiplication jumping into the bound `msm` host function.
+    ///
+    /// On length mismatch returns the shorter length, on any internal error returns `Err(0)`.
+    #[inline(always)]
+    fn msm(
+        bases: &[short_weierstrass::Affine<Self>],
+        scalars: &[Self::ScalarField],
+    ) -> Result<short_weierstrass::Projective<Self>, usize> {
+        if bases.len() != scalars.len() {
+            return Err(bases.len().min(scalars.len()));
+        }
+        <C as SWHostCurve>::msm(bases, scalars).map_err(|_| 0)
+    }
+
+    /// Projective multiplication jumping into the bound `mul_projective` host function.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    #[inline(always)]
+    fn mul_projective(
+        base: &short_weierstrass::Projective<Self>,
+        scalar: &[u64],
+    ) -> short_weierstrass::Projective<Self> {
+        <C as SWHostCurve>::mul_projective(base, scalar).unwrap_or_default()
+    }
+
+    /// Affine multiplication jumping into the bound `mul_projective` host function.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    #[inline(always)]
+    fn mul_affine(
+        base: &short_weierstrass::Affine<Self>,
+        scalar: &[u64],
+    ) -> short_weierstrass::Projective<Self> {
+        <Self as SWCurveConfig>::mul_projective(&(*base).into(), scalar)
+    }
+
+    #[inline(always)]
+    fn mul_by_a(elem: Self::BaseField) -> Self::BaseField {
+        <C::ArkConfig as SWCurveConfig>::mul_by_a(elem)
+    }
+
+    #[inline(always)]
+    fn is_in_correct_subgroup_assuming_on_curve(item: &short_weierstrass::Affine<Self>) -> bool {
+        <C as SWHostCurve>::is_in_correct_subgroup_assuming_on_curve(item)
+    }
+}
+
+impl<C: TEHostCurve> TECurveConfig for HostConfig<C> {
+    const COEFF_A: Self::BaseField = <C::ArkConfig as TECurveConfig>::COEFF_A;
+    const COEFF_D: Self::BaseField = <C::ArkConfig as TECurveConfig>::COEFF_D;
+
+    const GENERATOR: twisted_edwards::Affine<Self> =
+        twisted_edwards::Affine::new_unchecked(C::GENERATOR_X, C::GENERATOR_Y);
+
+    type MontCurveConfig = Self;
+
+    /// Multi scalar multiplication jumping into the bound `msm` host function.
+    ///
+    /// On length mismatch returns the shorter length, on any internal error returns `Err(0)`.
+    #[inline(always)]
+    fn msm(
+        bases: &[twisted_edwards::Affine<Self>],
+        scalars: &[Self::ScalarField],
+    ) -> Result<twisted_edwards::Projective<Self>, usize> {
+        if bases.len() != scalars.len() {
+            return Err(bases.len().min(scalars.len()));
+        }
+        <C as TEHostCurve>::msm(bases, scalars).map_err(|_| 0)
+    }
+
+    /// Projective multiplication jumping into the bound `mul_projective` host function.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    #[inline(always)]
+    fn mul_projective(
+        base: &twisted_edwards::Projective<Self>,
+        scalar: &[u64],
+    ) -> twisted_edwards::Projective<Self> {
+        <C as TEHostCurve>::mul_projective(base, scalar).unwrap_or_default()
+    }
+
+    /// Affine multiplication jumping into the bound `mul_projective` host function.
+    ///
+    /// On any internal error returns `Projective::zero()`.
+    #[inline(always)]
+    fn mul_affine(
+        base: &twisted_edwards::Affine<Self>,
+        scalar: &[u64],
+    ) -> twisted_edwards::Projective<Self> {
+        <Self as TECurveConfig>::mul_projective(&(*base).into(), scalar)
+    }
+
+    #[inline(always)]
+    fn mul_by_a(elem: Self::BaseField) -> Self::BaseField {
+        <C::ArkConfig as TECurveConfig>::mul_by_a(elem)
+    }
+}
+
+impl<C: TEHostCurve> MontCurveConfig for HostConfig<C> {
+    const COEFF_A: Self::BaseField =
+        <<C::ArkConfig as TECurveConfig>::MontCurveConfig as MontCurveConfig>::COEFF_A;
+    const COEFF_B: Self::BaseField =
+        <<C::ArkConfig as TECurveConfig>::MontCurveConfig as MontCurveConfig>::COEFF_B;
+
+    type TECurveConfig = Self;
+}
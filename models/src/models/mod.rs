@@ -1,3 +1,7 @@
 pub mod bls12;
+pub mod bn;
 pub mod bw6;
+pub mod fp2_codec;
+pub mod host;
+pub mod pippenger;
 pub use ark_ec::{pairing, short_weierstrass, twisted_edwards, CurveConfig};
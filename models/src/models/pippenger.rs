@@ -0,0 +1,166 @@
+//! Native bucket (Pippenger) multi scalar multiplication.
+//!
+//! Purely in-Rust MSM with a caller chosen window size, used by the in-Rust hook
+//! defaults, off-chain tooling and benchmarks. With the `parallel` feature the
+//! windows are accumulated on multiple threads with `rayon`. Group additions are
+//! exact, thus the result is the same as the serial one.
+
+#![allow(clippy::result_unit_err)]
+
+use ark_ec::{
+    short_weierstrass::{Affine as SWAffine, Projective as SWProjective, SWCurveConfig},
+    AdditiveGroup,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{vec, vec::Vec};
+
+/// Native MSM, accumulating the windows in parallel if the `parallel` feature is enabled.
+///
+/// The window size is given by `msm_window_bits`. Returns `Err(())` on length mismatch.
+pub fn msm_native_sw<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+) -> Result<SWProjective<C>, ()> {
+    msm_native_sw_with_window(bases, scalars, msm_window_bits(bases.len()))
+}
+
+/// Largest window size (in bits) accepted by `msm_native_sw_with_window`.
+///
+/// Each window allocates `2^window_bits - 1` buckets. The default heuristic reaches
+/// this size only above `2^27` pairs.
+pub const MAX_WINDOW_BITS: usize = 20;
+
+/// Same as `msm_native_sw`, with windows of `window_bits` bits.
+///
+/// The result doesn't depend on the window size, only the speed does.
+/// Returns `Err(())` on length mismatch, if `window_bits` is zero or if it is larger
+/// than `MAX_WINDOW_BITS` or than the scalar field modulus bit size.
+pub fn msm_native_sw_with_window<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+    window_bits: usize,
+) -> Result<SWProjective<C>, ()> {
+    #[cfg(feature = "parallel")]
+    return parallel(bases, scalars, window_bits);
+    #[cfg(not(feature = "parallel"))]
+    return serial(bases, scalars, window_bits);
+}
+
+/// Native MSM accumulating the windows one after the other.
+///
+/// Returns `Err(())` on length mismatch.
+pub fn msm_native_sw_serial<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+) -> Result<SWProjective<C>, ()> {
+    serial(bases, scalars, msm_window_bits(bases.len()))
+}
+
+/// Native MSM accumulating the windows on the `rayon` thread pool.
+///
+/// Returns `Err(())` on length mismatch.
+#[cfg(feature = "parallel")]
+pub fn msm_native_sw_parallel<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+) -> Result<SWProjective<C>, ()> {
+    parallel(bases, scalars, msm_window_bits(bases.len()))
+}
+
+/// Default window size (in bits) for an MSM of `len` pairs.
+///
+/// Same heuristic as the upstream *Arkworks* MSM: 3 bits below 32 pairs,
+/// `ln(len) + 2` bits otherwise, at most `MAX_WINDOW_BITS`.
+pub fn msm_window_bits(len: usize) -> usize {
+    if len < 32 {
+        3
+    } else {
+        // Approximation of `ln(len) + 2`.
+        (ark_std::log2(len) as usize * 69 / 100 + 2).min(MAX_WINDOW_BITS)
+    }
+}
+
+type BigInt<C> = <<C as ark_ec::CurveConfig>::ScalarField as PrimeField>::BigInt;
+
+fn serial<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+    c: usize,
+) -> Result<SWProjective<C>, ()> {
+    let scalars = prepare::<C>(bases, scalars, c)?;
+    let sums = window_starts::<C>(c)
+        .map(|start| window_sum(bases, &scalars, start, c))
+        .collect();
+    Ok(combine(sums, c))
+}
+
+#[cfg(feature = "parallel")]
+fn parallel<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+    c: usize,
+) -> Result<SWProjective<C>, ()> {
+    use rayon::prelude::*;
+
+    let scalars = prepare::<C>(bases, scalars, c)?;
+    let sums = window_starts::<C>(c)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .map(|start| window_sum(bases, &scalars, start, c))
+        .collect();
+    Ok(combine(sums, c))
+}
+
+fn prepare<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[C::ScalarField],
+    c: usize,
+) -> Result<Vec<BigInt<C>>, ()> {
+    let max_bits = MAX_WINDOW_BITS.min(C::ScalarField::MODULUS_BIT_SIZE as usize);
+    if bases.len() != scalars.len() || c == 0 || c > max_bits || bucket_count(c).is_none() {
+        return Err(());
+    }
+    Ok(scalars.iter().map(|s| s.into_bigint()).collect())
+}
+
+/// Number of buckets of a window of `c` bits, `None` if not addressable.
+fn bucket_count(c: usize) -> Option<usize> {
+    1usize.checked_shl(c as u32).map(|n| n - 1)
+}
+
+fn window_starts<C: SWCurveConfig>(c: usize) -> impl Iterator<Item = usize> {
+    (0..C::ScalarField::MODULUS_BIT_SIZE as usize).step_by(c)
+}
+
+fn window_sum<C: SWCurveConfig>(
+    bases: &[SWAffine<C>],
+    scalars: &[BigInt<C>],
+    start: usize,
+    c: usize,
+) -> SWProjective<C> {
+    let mut buckets = vec![SWProjective::<C>::ZERO; bucket_count(c).unwrap_or_default()];
+    for (base, scalar) in bases.iter().zip(scalars) {
+        let digit = (0..c)
+            .filter(|b| scalar.get_bit(start + b))
+            .fold(0, |acc, b| acc | (1 << b));
+        if digit != 0 {
+            buckets[digit - 1] += base;
+        }
+    }
+    // `sum_i i * bucket_i` via running sums.
+    let mut running = SWProjective::<C>::ZERO;
+    let mut res = SWProjective::<C>::ZERO;
+    for bucket in buckets.into_iter().rev() {
+        running += bucket;
+        res += running;
+    }
+    res
+}
+
+fn combine<C: SWCurveConfig>(sums: Vec<SWProjective<C>>, c: usize) -> SWProjective<C> {
+    sums.into_iter()
+        .rev()
+        .fold(SWProjective::<C>::ZERO, |acc, sum| {
+            (0..c).fold(acc, |acc, _| acc.double()) + sum
+        })
+}
@@ -0,0 +1,124 @@
+//! Checks the curves on `no_std` targets with an allocator.
+//!
+//! Every dependency is compiled without the `std` feature. CI builds this crate for
+//! `wasm32-unknown-unknown` and runs its tests natively, so both the build and the
+//! behavior of the MSM, serialization and `ct` selection code paths are checked
+//! without `std`.
+
+#![no_std]
+#![allow(clippy::result_unit_err)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ark_bls12_381_ext::{CurveHooks, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::vec;
+
+type Bls12_381 = ark_bls12_381_ext::Bls12_381<Hooks>;
+type G1Config = ark_bls12_381_ext::g1::Config<Hooks>;
+type G2Config = ark_bls12_381_ext::g2::Config<Hooks>;
+
+/// Hooks jumping into the upstream *Arkworks* implementation.
+pub struct Hooks;
+
+impl CurveHooks for Hooks {
+    fn bls12_381_multi_miller_loop(
+        g1: impl Iterator<Item = <Bls12_381 as ark_ec::pairing::Pairing>::G1Prepared>,
+        g2: impl Iterator<Item = <Bls12_381 as ark_ec::pairing::Pairing>::G2Prepared>,
+    ) -> Result<<Bls12_381 as ark_ec::pairing::Pairing>::TargetField, ()> {
+        test_utils::multi_miller_loop_generic::<Bls12_381, ark_bls12_381::Bls12_381>(g1, g2)
+    }
+
+    fn bls12_381_final_exponentiation(
+        target: <Bls12_381 as ark_ec::pairing::Pairing>::TargetField,
+    ) -> Result<<Bls12_381 as ark_ec::pairing::Pairing>::TargetField, ()> {
+        test_utils::final_exponentiation_generic::<Bls12_381, ark_bls12_381::Bls12_381>(target)
+    }
+
+    fn bls12_381_msm_g1(
+        bases: &[G1Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<G1Projective<Self>, ()> {
+        test_utils::msm_sw_generic::<G1Config, ark_bls12_381::g1::Config>(bases, scalars)
+    }
+
+    fn bls12_381_msm_g2(
+        bases: &[G2Affine<Self>],
+        scalars: &[Fr],
+    ) -> Result<G2Projective<Self>, ()> {
+        test_utils::msm_sw_generic::<G2Config, ark_bls12_381::g2::Config>(bases, scalars)
+    }
+
+    fn bls12_381_mul_projective_g1(
+        base: &G1Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G1Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<G1Config, ark_bls12_381::g1::Config>(base, scalar)
+    }
+
+    fn bls12_381_mul_projective_g2(
+        base: &G2Projective<Self>,
+        scalar: &[u64],
+    ) -> Result<G2Projective<Self>, ()> {
+        test_utils::mul_projective_sw_generic::<G2Config, ark_bls12_381::g2::Config>(base, scalar)
+    }
+}
+
+/// G1 multi scalar multiplication through the hooks.
+pub fn msm_g1(bases: &[G1Affine<Hooks>], scalars: &[Fr]) -> Result<G1Projective<Hooks>, usize> {
+    <G1Config as ark_ec::short_weierstrass::SWCurveConfig>::msm(bases, scalars)
+}
+
+/// Compressed encoding of G1 points.
+pub fn serialize_g1(points: &[G1Affine<Hooks>]) -> Result<Vec<u8>, SerializationError> {
+    let mut buf = vec![];
+    points.serialize_compressed(&mut buf)?;
+    Ok(buf)
+}
+
+/// Validated decoding of compressed G1 points.
+pub fn deserialize_g1(buf: &[u8]) -> Result<Vec<G1Affine<Hooks>>, SerializationError> {
+    Vec::deserialize_compressed(buf)
+}
+
+/// Constant time selection of `b` if `choice` is set, of `a` otherwise.
+pub fn select_g1(a: &G1Affine<Hooks>, b: &G1Affine<Hooks>, choice: bool) -> G1Affine<Hooks> {
+    ark_bls12_381_ext::g1::conditional_select(a, b, subtle::Choice::from(choice as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    fn points(n: u64) -> (Vec<G1Affine<Hooks>>, Vec<Fr>) {
+        let g = G1Affine::<Hooks>::generator();
+        let bases = (1..=n).map(|i| (g * Fr::from(i)).into_affine()).collect();
+        let scalars = (1..=n).map(|i| Fr::from(i * i + 7)).collect();
+        (bases, scalars)
+    }
+
+    #[test]
+    fn msm_matches_naive_sum() {
+        let (bases, scalars) = points(8);
+        let expected: G1Projective<Hooks> = bases.iter().zip(&scalars).map(|(b, s)| *b * s).sum();
+        assert_eq!(msm_g1(&bases, &scalars).unwrap(), expected);
+        assert_eq!(msm_g1(&bases, &scalars[..3]), Err(3));
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let (bases, _) = points(4);
+        let buf = serialize_g1(&bases).unwrap();
+        assert_eq!(deserialize_g1(&buf).unwrap(), bases);
+        assert!(deserialize_g1(&buf[..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn conditional_select() {
+        let (bases, _) = points(2);
+        assert_eq!(select_g1(&bases[0], &bases[1], false), bases[0]);
+        assert_eq!(select_g1(&bases[0], &bases[1], true), bases[1]);
+    }
+}
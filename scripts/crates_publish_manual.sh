@@ -12,5 +12,11 @@ cargo publish -p ark-models-ext
 cargo publish -p ark-bls12-377-ext
 cargo publish -p ark-ed-on-bls12-377-ext
 cargo publish -p ark-bls12-381-ext
+cargo publish -p ark-bn254-ext
 cargo publish -p ark-bw6-761-ext
+cargo publish -p ark-ed-on-bw6-761-ext
+cargo publish -p ark-ed-on-cp6-782-ext
+cargo publish -p ark-ed-on-mnt4-298-ext
+cargo publish -p ark-ed-on-mnt4-753-ext
 cargo publish -p ark-ed-on-bls12-381-bandersnatch-ext
+cargo publish -p ark-grumpkin-ext
@@ -0,0 +1,90 @@
+//! Hooks wrapper recording the host calls.
+//!
+//! A curve crate implements its `CurveHooks` for `CountingHooks<H>` by recording the
+//! hook name and then delegating to the inner hooks `H` through the `*_generic`
+//! helpers, which transmute between the two (otherwise identical) configurations.
+//! Results are thus the same as the ones of the wrapped hooks.
+//!
+//! Counts are kept per wrapped hooks type and shared by every thread, thus calls
+//! made on other threads (e.g. on the `rayon` pool) are recorded as well. Tests
+//! counting calls hold the `lock` guard, so that they don't observe each other's
+//! calls when run concurrently.
+
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    marker::PhantomData,
+    sync::{Mutex, MutexGuard},
+};
+
+static COUNTS: Mutex<BTreeMap<(TypeId, &'static str), usize>> = Mutex::new(BTreeMap::new());
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Lock ignoring the poisoning, a failed test doesn't invalidate the counts of others.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Hooks wrapper counting the calls before delegating to `H`.
+pub struct CountingHooks<H>(PhantomData<fn() -> H>);
+
+impl<H: 'static> CountingHooks<H> {
+    /// Exclusive access to the counts, which are reset.
+    ///
+    /// To be held for the whole test, i.e. including the calls made before the counted
+    /// section.
+    pub fn lock() -> CountsGuard {
+        let guard = lock(&LOCK);
+        Self::reset();
+        CountsGuard { _guard: guard }
+    }
+
+    /// Record a call to the hook named `hook`.
+    pub fn record(hook: &'static str) {
+        *lock(&COUNTS).entry((TypeId::of::<H>(), hook)).or_default() += 1;
+    }
+
+    /// Snapshot of the calls recorded for `H`.
+    pub fn counts() -> HookCounts {
+        let counts = lock(&COUNTS);
+        HookCounts(
+            counts
+                .iter()
+                .filter(|((id, _), _)| *id == TypeId::of::<H>())
+                .map(|((_, hook), count)| (*hook, *count))
+                .collect(),
+        )
+    }
+
+    /// Forget the calls recorded for `H`.
+    pub fn reset() {
+        lock(&COUNTS).retain(|(id, _), _| *id != TypeId::of::<H>());
+    }
+}
+
+/// Guard returned by `CountingHooks::lock`.
+pub struct CountsGuard {
+    _guard: MutexGuard<'static, ()>,
+}
+
+/// Calls per hook name.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HookCounts(BTreeMap<&'static str, usize>);
+
+impl HookCounts {
+    /// Number of calls to the hook named `hook`.
+    pub fn get(&self, hook: &str) -> usize {
+        self.0.get(hook).copied().unwrap_or_default()
+    }
+
+    /// Number of calls to any hook.
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+
+    /// Iterator over the called hooks and their number of calls.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.0.iter().map(|(hook, count)| (*hook, *count))
+    }
+}
@@ -7,13 +7,26 @@
 #![allow(clippy::result_unit_err)]
 
 use ark_ec::{
-    pairing::{MillerLoopOutput, Pairing},
+    pairing::{MillerLoopOutput, Pairing, PairingOutput},
     short_weierstrass::{Affine as SWAffine, Projective as SWProjective, SWCurveConfig},
     twisted_edwards::{Affine as TEAffine, Projective as TEProjective, TECurveConfig},
-    CurveConfig, VariableBaseMSM,
+    CurveConfig, CurveGroup, PrimeGroup, VariableBaseMSM,
 };
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use ark_std::vec::Vec;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress};
+use ark_std::{marker::PhantomData, vec::Vec};
+
+pub use ark_models_ext::pippenger::*;
+
+#[cfg(feature = "std")]
+mod serialization;
+#[cfg(feature = "std")]
+pub use serialization::*;
+
+#[cfg(feature = "std")]
+mod counting;
+#[cfg(feature = "std")]
+pub use counting::*;
 
 trait TryTransmute {
     fn try_transmute<U: CanonicalDeserialize>(self) -> Result<U, ()>;
@@ -45,6 +58,16 @@ pub fn final_exponentiation_generic<ExtPairing: Pairing, ArkPairing: Pairing>(
     res.try_transmute()
 }
 
+pub fn multi_pairing_generic<ExtPairing: Pairing, ArkPairing: Pairing>(
+    g1: impl Iterator<Item = ExtPairing::G1Prepared>,
+    g2: impl Iterator<Item = ExtPairing::G2Prepared>,
+) -> Result<PairingOutput<ExtPairing>, ()> {
+    let g1: Vec<ArkPairing::G1Affine> = g1.collect::<Vec<_>>().try_transmute()?;
+    let g2: Vec<ArkPairing::G2Affine> = g2.collect::<Vec<_>>().try_transmute()?;
+    let res = ArkPairing::multi_pairing(g1, g2).0;
+    Ok(PairingOutput(res.try_transmute()?))
+}
+
 pub fn msm_sw_generic<ExtCurve: SWCurveConfig, ArkCurve: SWCurveConfig>(
     bases: &[SWAffine<ExtCurve>],
     scalars: &[ExtCurve::ScalarField],
@@ -55,6 +78,18 @@ pub fn msm_sw_generic<ExtCurve: SWCurveConfig, ArkCurve: SWCurveConfig>(
     res.try_transmute()
 }
 
+/// Same as `msm_sw_generic`, with the result normalized to affine by the upstream
+/// implementation, as done by hooks returning an affine MSM.
+pub fn msm_sw_affine_generic<ExtCurve: SWCurveConfig, ArkCurve: SWCurveConfig>(
+    bases: &[SWAffine<ExtCurve>],
+    scalars: &[ExtCurve::ScalarField],
+) -> Result<SWAffine<ExtCurve>, ()> {
+    let bases: Vec<SWAffine<ArkCurve>> = bases.try_transmute()?;
+    let scalars: Vec<ArkCurve::ScalarField> = scalars.try_transmute()?;
+    let res = <SWProjective<ArkCurve> as VariableBaseMSM>::msm(&bases, &scalars).map_err(|_| ())?;
+    res.into_affine().try_transmute()
+}
+
 pub fn msm_te_generic<ExtConfig: TECurveConfig, ArkConfig: TECurveConfig>(
     bases: &[TEAffine<ExtConfig>],
     scalars: &[ExtConfig::ScalarField],
@@ -75,6 +110,16 @@ pub fn mul_projective_sw_generic<ExtConfig: SWCurveConfig, ArkConfig: SWCurveCon
     res.try_transmute()
 }
 
+/// Scalar `s` as given to the `mul_projective` hooks of every curve.
+///
+/// These are the 64-bit limbs of the canonical (i.e. not in Montgomery form)
+/// representation of `s`, least significant limb first. All the limbs of the
+/// scalar field big integer are returned, the most significant ones being zero
+/// for small scalars. This is the same input `mul_bigint(s.into_bigint())` gives.
+pub fn scalar_limbs<C: CurveConfig>(s: &C::ScalarField) -> Vec<u64> {
+    s.into_bigint().as_ref().to_vec()
+}
+
 pub fn mul_projective_te_generic<ExtConfig: TECurveConfig, ArkConfig: TECurveConfig>(
     base: &TEProjective<ExtConfig>,
     scalar: &[u64],
@@ -83,3 +128,120 @@ pub fn mul_projective_te_generic<ExtConfig: TECurveConfig, ArkConfig: TECurveCon
     let res = <ArkConfig as TECurveConfig>::mul_projective(&base, scalar);
     res.try_transmute()
 }
+
+/// Test hooks jumping into the upstream *Arkworks* curve configuration `C`.
+///
+/// Meant for single group curves: a test module declares
+/// `type TestHooks = ArkTestHooks<ArkConfig>;` and implements its `CurveHooks` for it
+/// with `impl_ark_test_hooks!`, which forwards each hook to the matching method below.
+pub struct ArkTestHooks<C>(PhantomData<fn() -> C>);
+
+impl<C: SWCurveConfig> ArkTestHooks<C> {
+    pub fn msm_sw<ExtConfig: SWCurveConfig>(
+        bases: &[SWAffine<ExtConfig>],
+        scalars: &[ExtConfig::ScalarField],
+    ) -> Result<SWProjective<ExtConfig>, ()> {
+        msm_sw_generic::<ExtConfig, C>(bases, scalars)
+    }
+
+    pub fn mul_projective_sw<ExtConfig: SWCurveConfig>(
+        base: &SWProjective<ExtConfig>,
+        scalar: &[u64],
+    ) -> Result<SWProjective<ExtConfig>, ()> {
+        mul_projective_sw_generic::<ExtConfig, C>(base, scalar)
+    }
+}
+
+impl<C: TECurveConfig> ArkTestHooks<C> {
+    pub fn msm_te<ExtConfig: TECurveConfig>(
+        bases: &[TEAffine<ExtConfig>],
+        scalars: &[ExtConfig::ScalarField],
+    ) -> Result<TEProjective<ExtConfig>, ()> {
+        msm_te_generic::<ExtConfig, C>(bases, scalars)
+    }
+
+    pub fn mul_projective_te<ExtConfig: TECurveConfig>(
+        base: &TEProjective<ExtConfig>,
+        scalar: &[u64],
+    ) -> Result<TEProjective<ExtConfig>, ()> {
+        mul_projective_te_generic::<ExtConfig, C>(base, scalar)
+    }
+}
+
+#[doc(hidden)]
+pub mod reexports {
+    pub use ark_ec::{
+        short_weierstrass::{Affine as SWAffine, Projective as SWProjective},
+        twisted_edwards::{Affine as TEAffine, Projective as TEProjective},
+        CurveConfig,
+    };
+}
+
+/// Implement the `CurveHooks` of a single group curve for `ArkTestHooks<ArkConfig>`.
+///
+/// Takes the hooks trait, the upstream configuration, the curve model (`sw` or `te`)
+/// with the Arkworks-Ext configuration, then the names of the `msm` and
+/// `mul_projective` hooks:
+///
+/// ```ignore
+/// test_utils::impl_ark_test_hooks!(
+///     CurveHooks => ArkConfig,
+///     te: crate::EdwardsConfig,
+///     ed_on_bls12_377_msm,
+///     ed_on_bls12_377_mul_projective,
+/// );
+/// ```
+#[macro_export]
+macro_rules! impl_ark_test_hooks {
+    ($hooks:path => $ark:ty, sw: $($config:ident)::+, $msm:ident, $mul:ident $(,)?) => {
+        impl $hooks for $crate::ArkTestHooks<$ark> {
+            fn $msm(
+                bases: &[$crate::reexports::SWAffine<$($config)::+<Self>>],
+                scalars: &[<$($config)::+<Self> as $crate::reexports::CurveConfig>::ScalarField],
+            ) -> Result<$crate::reexports::SWProjective<$($config)::+<Self>>, ()> {
+                Self::msm_sw(bases, scalars)
+            }
+
+            fn $mul(
+                base: &$crate::reexports::SWProjective<$($config)::+<Self>>,
+                scalar: &[u64],
+            ) -> Result<$crate::reexports::SWProjective<$($config)::+<Self>>, ()> {
+                Self::mul_projective_sw(base, scalar)
+            }
+        }
+    };
+    ($hooks:path => $ark:ty, te: $($config:ident)::+, $msm:ident, $mul:ident $(,)?) => {
+        impl $hooks for $crate::ArkTestHooks<$ark> {
+            fn $msm(
+                bases: &[$crate::reexports::TEAffine<$($config)::+<Self>>],
+                scalars: &[<$($config)::+<Self> as $crate::reexports::CurveConfig>::ScalarField],
+            ) -> Result<$crate::reexports::TEProjective<$($config)::+<Self>>, ()> {
+                Self::msm_te(bases, scalars)
+            }
+
+            fn $mul(
+                base: &$crate::reexports::TEProjective<$($config)::+<Self>>,
+                scalar: &[u64],
+            ) -> Result<$crate::reexports::TEProjective<$($config)::+<Self>>, ()> {
+                Self::mul_projective_te(base, scalar)
+            }
+        }
+    };
+}
+
+/// Serialized points test vectors, i.e. the first `count` multiples of the generator,
+/// starting from the identity, encoded back to back according to `compress`.
+///
+/// Reproduces the generation of the checked-in `*_valid_test_vectors.dat` files.
+pub fn gen_sw_test_vectors<Curve: SWCurveConfig>(count: usize, compress: Compress) -> Vec<u8> {
+    let generator = SWProjective::<Curve>::generator();
+    let mut point = SWProjective::<Curve>::default();
+    let mut buf =
+        Vec::with_capacity(count * SWAffine::<Curve>::default().serialized_size(compress));
+    for _ in 0..count {
+        // Writing into a `Vec` can't fail.
+        let _ = SWAffine::from(point).serialize_with_mode(&mut buf, compress);
+        point += generator;
+    }
+    buf
+}
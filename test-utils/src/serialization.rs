@@ -0,0 +1,122 @@
+//! Property-based serialization invariants.
+//!
+//! Checks shared by the curve crates, parameterized over the curve configuration
+//! and driven by `proptest`. The runner is seeded deterministically, thus failures
+//! are reproducible, and reports the (shrunk) seed of the failing point.
+
+use ark_ec::{
+    short_weierstrass::{Affine as SWAffine, Projective as SWProjective, SWCurveConfig},
+    AffineRepr, CurveGroup,
+};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::{
+    rand::{rngs::StdRng, Rng, SeedableRng},
+    vec::Vec,
+    UniformRand, Zero,
+};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseResult, TestRng, TestRunner},
+};
+
+/// Check the serialization invariants of the curve `C` over `cases` random inputs.
+///
+/// For both compression modes:
+/// - deserialization of the encoding gives back the point, the point at infinity included;
+/// - the encoding is `serialized_size` bytes long, and any truncation is rejected;
+/// - a point on the curve but out of the prime order subgroup is rejected with
+///   `Validate::Yes` and decoded as is with `Validate::No`, unless the cofactor is one.
+///
+/// Panics on the first violated invariant, with the minimal failing input.
+pub fn check_sw_serialization<C: SWCurveConfig>(cases: u32) {
+    run(cases, (sw_point::<C>(), any::<bool>()), |(point, yes)| {
+        check_round_trip(&point, compress(yes))
+    });
+
+    if C::cofactor_is_one() {
+        return;
+    }
+    let off_subgroup =
+        any::<[u8; 32]>().prop_map(|seed| off_subgroup_point::<C>(&mut StdRng::from_seed(seed)));
+    run(cases, (off_subgroup, any::<bool>()), |(point, yes)| {
+        check_validation(&point, compress(yes))
+    });
+}
+
+fn run<S: Strategy>(cases: u32, strategy: S, test: impl Fn(S::Value) -> TestCaseResult) {
+    let config = Config {
+        cases,
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let rng = TestRng::deterministic_rng(config.rng_algorithm);
+    let mut runner = TestRunner::new_with_rng(config, rng);
+    if let Err(err) = runner.run(&strategy, test) {
+        panic!("{err}");
+    }
+}
+
+/// Random points of the curve `C`, drawn from a 32 bytes seed, and the point at infinity.
+fn sw_point<C: SWCurveConfig>() -> impl Strategy<Value = SWAffine<C>> {
+    prop_oneof![
+        1 => Just(SWAffine::<C>::zero()),
+        15 => any::<[u8; 32]>().prop_map(|seed| {
+            SWProjective::<C>::rand(&mut StdRng::from_seed(seed)).into_affine()
+        }),
+    ]
+}
+
+/// Compression mode, drawn as a `bool` since strategy values must be `Debug`,
+/// which `Compress` isn't.
+fn compress(yes: bool) -> Compress {
+    if yes {
+        Compress::Yes
+    } else {
+        Compress::No
+    }
+}
+
+fn check_round_trip<C: SWCurveConfig>(point: &SWAffine<C>, compress: Compress) -> TestCaseResult {
+    let mut buf = Vec::new();
+    point.serialize_with_mode(&mut buf, compress).unwrap();
+    prop_assert_eq!(buf.len(), point.serialized_size(compress));
+    let res = SWAffine::<C>::deserialize_with_mode(&buf[..], compress, Validate::Yes);
+    prop_assert_eq!(res.ok(), Some(*point));
+    let res = SWAffine::<C>::deserialize_with_mode(&buf[..buf.len() - 1], compress, Validate::Yes);
+    prop_assert!(res.is_err());
+    Ok(())
+}
+
+fn check_validation<C: SWCurveConfig>(point: &SWAffine<C>, compress: Compress) -> TestCaseResult {
+    let mut buf = Vec::new();
+    point.serialize_with_mode(&mut buf, compress).unwrap();
+    let res = SWAffine::<C>::deserialize_with_mode(&buf[..], compress, Validate::Yes);
+    prop_assert!(res.is_err());
+    let res = SWAffine::<C>::deserialize_with_mode(&buf[..], compress, Validate::No);
+    prop_assert_eq!(res.ok(), Some(*point));
+    Ok(())
+}
+
+/// Point on the curve `C` out of the prime order subgroup.
+///
+/// Membership is checked through the multiplication by the subgroup order, not
+/// through the curve specific check under test.
+fn off_subgroup_point<C: SWCurveConfig>(rng: &mut impl Rng) -> SWAffine<C> {
+    // The order is split in two halves, each smaller than the order itself: some
+    // `mul_projective` implementations, e.g. the GLV one of the upstream BLS12-381
+    // G1, first convert the scalar limbs to a scalar field element, which reduces
+    // the order to zero and gives the identity for any point.
+    let order = C::ScalarField::characteristic();
+    let (mut lo, mut hi) = (order.to_vec(), order.to_vec());
+    lo[order.len() / 2..].fill(0);
+    hi[..order.len() / 2].fill(0);
+    loop {
+        let x = C::BaseField::rand(rng);
+        if let Some(point) = SWAffine::<C>::get_point_from_x_unchecked(x, false) {
+            if !(point.mul_bigint(&lo) + point.mul_bigint(&hi)).is_zero() {
+                return point;
+            }
+        }
+    }
+}